@@ -47,19 +47,26 @@ pub fn invoke_protocol_cpi<'info>(
 }
 
 /// Build a CPI instruction from remaining_accounts and invoke with PDA signer seeds.
+/// Accounts derived from `signer_seeds` are passed to the protocol as signers.
 pub fn invoke_protocol_cpi_signed<'info>(
     program: &AccountInfo<'info>,
     accounts: &[AccountInfo<'info>],
     data: Vec<u8>,
     signer_seeds: &[&[&[u8]]],
 ) -> Result<()> {
+    let pda_signers: Vec<Pubkey> = signer_seeds
+        .iter()
+        .filter_map(|seeds| Pubkey::create_program_address(seeds, &crate::ID).ok())
+        .collect();
+
     let account_metas: Vec<AccountMeta> = accounts
         .iter()
         .map(|a| {
+            let is_signer = a.is_signer || pda_signers.contains(a.key);
             if a.is_writable {
-                AccountMeta::new(*a.key, a.is_signer)
+                AccountMeta::new(*a.key, is_signer)
             } else {
-                AccountMeta::new_readonly(*a.key, a.is_signer)
+                AccountMeta::new_readonly(*a.key, is_signer)
             }
         })
         .collect();
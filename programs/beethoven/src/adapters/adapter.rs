@@ -0,0 +1,193 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{token, token_2022};
+use crate::adapters::account_bridge::invoke_protocol_cpi_signed;
+use crate::adapters::protocol_ids::{DepositProtocol, SwapProtocol};
+use crate::adapters::swap_adapter::SwapMode;
+use crate::error::ErrorCode;
+
+/// Amounts and direction of one swap, as passed to `swap_adapter::execute_swap`.
+#[derive(Clone, Copy, Debug)]
+pub struct SwapRequest {
+    pub in_amount: u64,  // Exact input, or the input ceiling for exact-out
+    pub out_amount: u64, // Minimum output, or the exact output for exact-out
+    pub is_base_in: bool,
+    pub mode: SwapMode,
+    pub input_mint: Pubkey, // Mint the trader spends; sets the direction on pool venues
+}
+
+/// One swap venue. Implementations encode the protocol's swap instruction
+/// and check its account layout; routing, mode checks and the CPI itself
+/// are shared by `swap_adapter::execute_swap`.
+pub trait SwapAdapter: Sync {
+    fn protocol(&self) -> SwapProtocol;
+
+    fn program_id(&self) -> Pubkey;
+
+    /// Accounts the protocol needs after the program account.
+    fn min_accounts(&self) -> usize;
+
+    fn supports_mode(&self, mode: SwapMode) -> bool {
+        mode == SwapMode::ExactIn
+    }
+
+    /// Check accounts with a fixed role, such as the token program.
+    /// `program` is the invoked protocol program, which a registry override
+    /// may point away from `program_id`; pool accounts are checked against it.
+    fn validate_accounts(&self, _program: &Pubkey, _accounts: &[AccountInfo]) -> Result<()> {
+        Ok(())
+    }
+
+    /// Positions of the trader's input and output token accounts among
+    /// `accounts`, so a swap can be bound to known accounts. `None` for
+    /// venues whose layout doesn't pin them down; user swaps always bind
+    /// their token accounts and reject such venues.
+    fn trader_token_accounts(
+        &self,
+        _accounts: &[AccountInfo],
+        _request: &SwapRequest,
+    ) -> Result<Option<(usize, usize)>> {
+        Ok(None)
+    }
+
+    /// Instruction data for the protocol's swap instruction. `accounts` have
+    /// passed `validate_accounts`, so venues may read pool state from them.
+    fn encode(&self, accounts: &[AccountInfo], request: &SwapRequest) -> Result<Vec<u8>>;
+
+    /// Run the swap. Defaults to a CPI into the protocol program, with
+    /// `signer_seeds` signing for a program PDA trader.
+    fn swap<'info>(
+        &self,
+        program: &AccountInfo<'info>,
+        accounts: &'info [AccountInfo<'info>],
+        request: &SwapRequest,
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        let data = self.encode(accounts, request)?;
+        invoke_protocol_cpi_signed(program, accounts, data, signer_seeds)
+    }
+}
+
+/// One yield venue, with the deposit and withdraw encodings of its
+/// lending or earn program.
+pub trait DepositAdapter: Sync {
+    fn protocol(&self) -> DepositProtocol;
+
+    fn program_id(&self) -> Pubkey;
+
+    /// Accounts the protocol's deposit needs after the program account.
+    fn min_accounts(&self) -> usize;
+
+    /// Accounts the protocol's withdraw needs after the program account.
+    fn min_withdraw_accounts(&self) -> usize {
+        self.min_accounts()
+    }
+
+    /// Check deposit accounts with a fixed role, such as the token program.
+    fn validate_accounts(&self, _accounts: &[AccountInfo]) -> Result<()> {
+        Ok(())
+    }
+
+    /// Check withdraw accounts with a fixed role.
+    fn validate_withdraw_accounts(&self, accounts: &[AccountInfo]) -> Result<()> {
+        self.validate_accounts(accounts)
+    }
+
+    /// Position of the depositor's underlying token account among the
+    /// deposit accounts.
+    fn deposit_source_index(&self) -> usize;
+
+    /// Position of the token account receiving the underlying on withdraw.
+    fn withdraw_destination_index(&self) -> usize;
+
+    /// Position of the depositor's receipt token account (cToken, fToken)
+    /// among the deposit accounts; `None` for venues that track positions
+    /// in a program account instead.
+    fn deposit_receipt_index(&self) -> Option<usize> {
+        None
+    }
+
+    /// Position of the receipt token account burned by a withdraw.
+    fn withdraw_receipt_index(&self) -> Option<usize> {
+        None
+    }
+
+    fn encode_deposit(&self, amount: u64) -> Vec<u8>;
+
+    fn encode_withdraw(&self, amount: u64) -> Vec<u8>;
+
+    /// Run the deposit, with `signer_seeds` signing for a program PDA depositor.
+    fn deposit<'info>(
+        &self,
+        program: &AccountInfo<'info>,
+        accounts: &[AccountInfo<'info>],
+        amount: u64,
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        invoke_protocol_cpi_signed(program, accounts, self.encode_deposit(amount), signer_seeds)
+    }
+
+    fn withdraw<'info>(
+        &self,
+        program: &AccountInfo<'info>,
+        accounts: &[AccountInfo<'info>],
+        amount: u64,
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        invoke_protocol_cpi_signed(program, accounts, self.encode_withdraw(amount), signer_seeds)
+    }
+}
+
+/// Anchor-style instruction data: 8-byte discriminator followed by two u64 amounts.
+pub fn encode_two_amounts(discriminator: [u8; 8], first: u64, second: u64) -> Vec<u8> {
+    let mut data = Vec::with_capacity(24);
+    data.extend_from_slice(&discriminator);
+    data.extend_from_slice(&first.to_le_bytes());
+    data.extend_from_slice(&second.to_le_bytes());
+    data
+}
+
+/// Anchor-style instruction data: 8-byte discriminator followed by one u64 amount.
+pub fn encode_amount(discriminator: [u8; 8], amount: u64) -> Vec<u8> {
+    let mut data = Vec::with_capacity(16);
+    data.extend_from_slice(&discriminator);
+    data.extend_from_slice(&amount.to_le_bytes());
+    data
+}
+
+/// Require `account` to be SPL Token or Token-2022.
+pub fn require_token_program(account: &AccountInfo) -> Result<()> {
+    require!(
+        account.key() == token::ID || account.key() == token_2022::ID,
+        ErrorCode::InvalidParameter
+    );
+    Ok(())
+}
+
+/// Balance of an SPL Token or Token-2022 account, read without
+/// deserializing extensions.
+pub fn token_balance(account: &AccountInfo) -> Result<u64> {
+    require!(
+        *account.owner == token::ID || *account.owner == token_2022::ID,
+        ErrorCode::InvalidParameter
+    );
+    let data = account.try_borrow_data()?;
+    let bytes: [u8; 8] = data
+        .get(64..72)
+        .and_then(|slice| slice.try_into().ok())
+        .ok_or(ErrorCode::InvalidParameter)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// Owner (authority) of an SPL Token or Token-2022 account.
+pub fn token_owner(account: &AccountInfo) -> Result<Pubkey> {
+    require!(
+        *account.owner == token::ID || *account.owner == token_2022::ID,
+        ErrorCode::InvalidParameter
+    );
+    let data = account.try_borrow_data()?;
+    let bytes: [u8; 32] = data
+        .get(32..64)
+        .and_then(|slice| slice.try_into().ok())
+        .ok_or(ErrorCode::InvalidParameter)?;
+    Ok(Pubkey::new_from_array(bytes))
+}
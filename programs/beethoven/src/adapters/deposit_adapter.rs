@@ -1,8 +1,15 @@
 use anchor_lang::prelude::*;
-use crate::adapters::protocol_ids::{detect_deposit_protocol, DepositProtocol};
-#[allow(unused_imports)]
-use crate::adapters::account_bridge::invoke_protocol_cpi;
+use crate::adapters::adapter::{token_balance, token_owner, DepositAdapter};
+use crate::adapters::registry::resolve_deposit_adapter;
 use crate::error::ErrorCode;
+use crate::state::RegistryAccount;
+
+/// Accounts a deposit or withdrawal must settle against.
+#[derive(Clone, Copy, Debug)]
+pub struct Depositor {
+    pub token_account: Pubkey, // Underlying token account spent from or paid into
+    pub authority: Pubkey,     // Owner of the receipt token account
+}
 
 /// Execute a deposit via Beethoven's composable routing pattern.
 ///
@@ -10,107 +17,122 @@ use crate::error::ErrorCode;
 ///   [0] = Protocol program ID (executable)
 ///   [1..] = Protocol-specific accounts
 ///
+/// Deposits of `mint` count against the protocol's registry volume cap. Returns the
+/// receipt tokens minted to the depositor, measured by balance delta, or 0
+/// for venues without a receipt token. `signer_seeds` sign for a program
+/// PDA depositor, such as the fund. When `depositor` is set, the venue must
+/// spend from its token account and mint receipts to an account it owns.
+///
 /// See: https://blueshift.gg/research/composable-defi-with-beethoven
-#[allow(unused_variables)]
 pub fn execute_deposit<'info>(
+    registry: &mut RegistryAccount<'info>,
     remaining_accounts: &[AccountInfo<'info>],
+    mint: &Pubkey,
     amount: u64,
-) -> Result<()> {
-    require!(
-        !remaining_accounts.is_empty(),
-        ErrorCode::UnsupportedProtocol
-    );
-
-    let protocol_program = &remaining_accounts[0];
-    let protocol = detect_deposit_protocol(protocol_program.key);
-
+    depositor: Option<Depositor>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<u64> {
+    let adapter = resolve_adapter(registry, remaining_accounts)?;
+    let accounts = &remaining_accounts[1..];
     require!(
-        protocol != DepositProtocol::Unknown,
-        ErrorCode::UnsupportedProtocol
+        accounts.len() >= adapter.min_accounts(),
+        ErrorCode::InvalidParameter
     );
+    adapter.validate_accounts(accounts)?;
+    if let Some(depositor) = depositor {
+        require_depositor_accounts(
+            accounts,
+            adapter.deposit_source_index(),
+            adapter.deposit_receipt_index(),
+            &depositor,
+        )?;
+    }
+    registry.record_volume(&adapter.program_id(), mint, amount, Clock::get()?.unix_timestamp)?;
 
-    let accounts = &remaining_accounts[1..];
+    let receipt = adapter.deposit_receipt_index().map(|index| &accounts[index]);
+    let pre_receipt = receipt.map(token_balance).transpose()?;
+    adapter.deposit(&remaining_accounts[0], accounts, amount, signer_seeds)?;
 
-    match protocol {
-        #[cfg(feature = "kamino-deposit")]
-        DepositProtocol::Kamino => {
-            // Kamino klend depositReserveLiquidity:
-            //   discriminator(8) + liquidityAmount(8) = 16 bytes
-            //   Accounts (9): owner, reserve, lendingMarket, lendingMarketAuthority,
-            //     reserveLiquiditySupply, reserveCollateralMint,
-            //     userSourceLiquidity, userDestinationCollateral, tokenProgram
-            require!(accounts.len() >= 9, ErrorCode::InvalidParameter);
-            let mut data = Vec::with_capacity(16);
-            data.extend_from_slice(&[169, 201, 30, 126, 6, 205, 102, 68]); // depositReserveLiquidity
-            data.extend_from_slice(&amount.to_le_bytes());
-            invoke_protocol_cpi(protocol_program, accounts, data)
-        }
-        #[cfg(feature = "jupiter-deposit")]
-        DepositProtocol::Jupiter => {
-            // Jupiter Earn deposit:
-            //   discriminator(8) + amount(8) = 16 bytes
-            //   Accounts (17): signer, depositorTokenAccount, recipientTokenAccount, mint,
-            //     lendingAdmin, lending, fTokenMint, supplyTokenReservesLiquidity,
-            //     lendingSupplyPositionOnLiquidity, rateModel, vault, liquidity,
-            //     liquidityProgram, rewardsRateModel, tokenProgram,
-            //     associatedTokenProgram, systemProgram
-            require!(accounts.len() >= 17, ErrorCode::InvalidParameter);
-            let mut data = Vec::with_capacity(16);
-            data.extend_from_slice(&[242, 35, 198, 137, 82, 225, 242, 182]); // deposit
-            data.extend_from_slice(&amount.to_le_bytes());
-            invoke_protocol_cpi(protocol_program, accounts, data)
-        }
-        DepositProtocol::Unknown => Err(ErrorCode::UnsupportedProtocol.into()),
+    match (receipt, pre_receipt) {
+        (Some(receipt), Some(pre)) => token_balance(receipt)?
+            .checked_sub(pre)
+            .ok_or(ErrorCode::MathUnderflow.into()),
+        _ => Ok(0),
     }
 }
 
 /// Execute withdrawal from a yield protocol via Beethoven routing.
-#[allow(unused_variables)]
+/// Withdrawals are not volume capped, so funds can always leave a venue
+/// that is still enabled. Returns the receipt tokens burned, measured by
+/// balance delta, or 0 for venues without a receipt token. When `depositor`
+/// is set, the venue must pay into its token account and burn receipts from
+/// an account it owns.
 pub fn execute_withdraw<'info>(
+    registry: &RegistryAccount<'info>,
     remaining_accounts: &[AccountInfo<'info>],
     amount: u64,
-) -> Result<()> {
+    depositor: Option<Depositor>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<u64> {
+    let adapter = resolve_adapter(registry, remaining_accounts)?;
+    let accounts = &remaining_accounts[1..];
     require!(
-        !remaining_accounts.is_empty(),
-        ErrorCode::UnsupportedProtocol
+        accounts.len() >= adapter.min_withdraw_accounts(),
+        ErrorCode::InvalidParameter
     );
+    adapter.validate_withdraw_accounts(accounts)?;
+    if let Some(depositor) = depositor {
+        require_depositor_accounts(
+            accounts,
+            adapter.withdraw_destination_index(),
+            adapter.withdraw_receipt_index(),
+            &depositor,
+        )?;
+    }
 
-    let protocol_program = &remaining_accounts[0];
-    let protocol = detect_deposit_protocol(protocol_program.key);
-
-    require!(
-        protocol != DepositProtocol::Unknown,
-        ErrorCode::UnsupportedProtocol
-    );
+    let receipt = adapter.withdraw_receipt_index().map(|index| &accounts[index]);
+    let pre_receipt = receipt.map(token_balance).transpose()?;
+    adapter.withdraw(&remaining_accounts[0], accounts, amount, signer_seeds)?;
 
-    let accounts = &remaining_accounts[1..];
+    match (receipt, pre_receipt) {
+        (Some(receipt), Some(pre)) => pre
+            .checked_sub(token_balance(receipt)?)
+            .ok_or(ErrorCode::MathUnderflow.into()),
+        _ => Ok(0),
+    }
+}
 
-    match protocol {
-        #[cfg(feature = "kamino-deposit")]
-        DepositProtocol::Kamino => {
-            // Kamino klend redeemReserveCollateral:
-            //   discriminator(8) + collateralAmount(8) = 16 bytes
-            //   Accounts (9): owner, lendingMarket, reserve, lendingMarketAuthority,
-            //     reserveCollateralMint, reserveLiquiditySupply,
-            //     userSourceCollateral, userDestinationLiquidity, tokenProgram
-            require!(accounts.len() >= 9, ErrorCode::InvalidParameter);
-            let mut data = Vec::with_capacity(16);
-            data.extend_from_slice(&[234, 117, 181, 125, 185, 142, 220, 29]); // redeemReserveCollateral
-            data.extend_from_slice(&amount.to_le_bytes());
-            invoke_protocol_cpi(protocol_program, accounts, data)
-        }
-        #[cfg(feature = "jupiter-deposit")]
-        DepositProtocol::Jupiter => {
-            // Jupiter Earn withdraw:
-            //   discriminator(8) + amount(8) = 16 bytes
-            //   Accounts (17): same layout as deposit
-            require!(accounts.len() >= 17, ErrorCode::InvalidParameter);
-            let mut data = Vec::with_capacity(16);
-            data.extend_from_slice(&[183, 18, 70, 156, 148, 109, 161, 34]); // withdraw
-            data.extend_from_slice(&amount.to_le_bytes());
-            invoke_protocol_cpi(protocol_program, accounts, data)
-        }
-        DepositProtocol::Unknown => Err(ErrorCode::UnsupportedProtocol.into()),
+/// Require the venue's underlying token account to be the depositor's and
+/// its receipt token account, if any, to be owned by the depositor.
+fn require_depositor_accounts(
+    accounts: &[AccountInfo],
+    token_index: usize,
+    receipt_index: Option<usize>,
+    depositor: &Depositor,
+) -> Result<()> {
+    require_keys_eq!(
+        accounts[token_index].key(),
+        depositor.token_account,
+        ErrorCode::DepositorAccountMismatch
+    );
+    if let Some(index) = receipt_index {
+        require_keys_eq!(
+            token_owner(&accounts[index])?,
+            depositor.authority,
+            ErrorCode::DepositorAccountMismatch
+        );
     }
+    Ok(())
 }
 
+/// Look up the adapter for remaining_accounts[0].
+fn resolve_adapter(
+    registry: &RegistryAccount,
+    remaining_accounts: &[AccountInfo],
+) -> Result<&'static dyn DepositAdapter> {
+    require!(
+        !remaining_accounts.is_empty(),
+        ErrorCode::UnsupportedProtocol
+    );
+    resolve_deposit_adapter(registry, remaining_accounts[0].key)
+}
@@ -0,0 +1,251 @@
+#![allow(unused_imports)]
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::{associated_token, token};
+use crate::adapters::adapter::{encode_amount, require_token_program, DepositAdapter};
+use crate::adapters::protocol_ids::{deposit_protocols, DepositProtocol};
+use crate::error::ErrorCode;
+
+// One `DepositAdapter` per yield protocol. Register new venues in `registry`.
+
+/// Kamino Finance klend reserves.
+#[cfg(feature = "kamino-deposit")]
+pub struct Kamino;
+
+#[cfg(feature = "kamino-deposit")]
+impl DepositAdapter for Kamino {
+    fn protocol(&self) -> DepositProtocol {
+        DepositProtocol::Kamino
+    }
+
+    fn program_id(&self) -> Pubkey {
+        deposit_protocols::KAMINO
+    }
+
+    // depositReserveLiquidity (9): owner, reserve, lendingMarket, lendingMarketAuthority,
+    //   reserveLiquiditySupply, reserveCollateralMint,
+    //   userSourceLiquidity, userDestinationCollateral, tokenProgram
+    // redeemReserveCollateral (9): owner, lendingMarket, reserve, lendingMarketAuthority,
+    //   reserveCollateralMint, reserveLiquiditySupply,
+    //   userSourceCollateral, userDestinationLiquidity, tokenProgram
+    fn min_accounts(&self) -> usize {
+        9
+    }
+
+    fn validate_accounts(&self, accounts: &[AccountInfo]) -> Result<()> {
+        require_token_program(&accounts[8])
+    }
+
+    fn deposit_source_index(&self) -> usize {
+        6 // userSourceLiquidity
+    }
+
+    fn withdraw_destination_index(&self) -> usize {
+        7 // userDestinationLiquidity
+    }
+
+    fn deposit_receipt_index(&self) -> Option<usize> {
+        Some(7) // userDestinationCollateral
+    }
+
+    fn withdraw_receipt_index(&self) -> Option<usize> {
+        Some(6) // userSourceCollateral
+    }
+
+    // discriminator(8) + liquidityAmount(8) = 16 bytes
+    fn encode_deposit(&self, amount: u64) -> Vec<u8> {
+        encode_amount([169, 201, 30, 126, 6, 205, 102, 68], amount) // depositReserveLiquidity
+    }
+
+    // discriminator(8) + collateralAmount(8) = 16 bytes
+    fn encode_withdraw(&self, amount: u64) -> Vec<u8> {
+        encode_amount([234, 117, 181, 125, 185, 142, 220, 29], amount) // redeemReserveCollateral
+    }
+}
+
+/// Jupiter Earn lending.
+#[cfg(feature = "jupiter-deposit")]
+pub struct Jupiter;
+
+#[cfg(feature = "jupiter-deposit")]
+impl DepositAdapter for Jupiter {
+    fn protocol(&self) -> DepositProtocol {
+        DepositProtocol::Jupiter
+    }
+
+    fn program_id(&self) -> Pubkey {
+        deposit_protocols::JUPITER
+    }
+
+    // deposit / withdraw (17): signer, depositorTokenAccount, recipientTokenAccount, mint,
+    //   lendingAdmin, lending, fTokenMint, supplyTokenReservesLiquidity,
+    //   lendingSupplyPositionOnLiquidity, rateModel, vault, liquidity,
+    //   liquidityProgram, rewardsRateModel, tokenProgram,
+    //   associatedTokenProgram, systemProgram
+    fn min_accounts(&self) -> usize {
+        17
+    }
+
+    fn validate_accounts(&self, accounts: &[AccountInfo]) -> Result<()> {
+        require_token_program(&accounts[14])?;
+        require_keys_eq!(accounts[15].key(), associated_token::ID, ErrorCode::InvalidParameter);
+        require_keys_eq!(accounts[16].key(), system_program::ID, ErrorCode::InvalidParameter);
+        Ok(())
+    }
+
+    fn deposit_source_index(&self) -> usize {
+        1 // depositorTokenAccount (underlying)
+    }
+
+    fn withdraw_destination_index(&self) -> usize {
+        2 // recipientTokenAccount (underlying)
+    }
+
+    fn deposit_receipt_index(&self) -> Option<usize> {
+        Some(2) // recipientTokenAccount (fToken)
+    }
+
+    fn withdraw_receipt_index(&self) -> Option<usize> {
+        Some(1) // depositorTokenAccount (fToken)
+    }
+
+    // discriminator(8) + amount(8) = 16 bytes
+    fn encode_deposit(&self, amount: u64) -> Vec<u8> {
+        encode_amount([242, 35, 198, 137, 82, 225, 242, 182], amount) // deposit
+    }
+
+    fn encode_withdraw(&self, amount: u64) -> Vec<u8> {
+        encode_amount([183, 18, 70, 156, 148, 109, 161, 34], amount) // withdraw
+    }
+}
+
+/// marginfi v2 lending banks. Deposits are booked as shares on the
+/// depositor's marginfi account, so there is no receipt token to track.
+#[cfg(feature = "marginfi-deposit")]
+pub struct Marginfi;
+
+#[cfg(feature = "marginfi-deposit")]
+impl DepositAdapter for Marginfi {
+    fn protocol(&self) -> DepositProtocol {
+        DepositProtocol::Marginfi
+    }
+
+    fn program_id(&self) -> Pubkey {
+        deposit_protocols::MARGINFI
+    }
+
+    // lending_account_deposit (7): marginfi_group, marginfi_account, authority, bank,
+    //   signer_token_account, liquidity_vault, token_program
+    fn min_accounts(&self) -> usize {
+        7
+    }
+
+    // lending_account_withdraw (8+): marginfi_group, marginfi_account, authority, bank,
+    //   destination_token_account, liquidity_vault_authority, liquidity_vault,
+    //   token_program, then the bank/oracle pairs for the health check
+    fn min_withdraw_accounts(&self) -> usize {
+        8
+    }
+
+    fn validate_accounts(&self, accounts: &[AccountInfo]) -> Result<()> {
+        require_token_program(&accounts[6])
+    }
+
+    fn validate_withdraw_accounts(&self, accounts: &[AccountInfo]) -> Result<()> {
+        require_token_program(&accounts[7])
+    }
+
+    fn deposit_source_index(&self) -> usize {
+        4 // signer_token_account
+    }
+
+    fn withdraw_destination_index(&self) -> usize {
+        4 // destination_token_account
+    }
+
+    // discriminator(8) + amount(8) + deposit_up_to_limit(None: 1) = 17 bytes
+    fn encode_deposit(&self, amount: u64) -> Vec<u8> {
+        // lending_account_deposit
+        let mut data = encode_amount([171, 94, 235, 103, 82, 64, 212, 140], amount);
+        data.push(0); // deposit_up_to_limit: None
+        data
+    }
+
+    // discriminator(8) + amount(8) + withdraw_all(None: 1) = 17 bytes
+    fn encode_withdraw(&self, amount: u64) -> Vec<u8> {
+        // lending_account_withdraw
+        let mut data = encode_amount([36, 72, 74, 19, 210, 210, 192, 192], amount);
+        data.push(0); // withdraw_all: None
+        data
+    }
+}
+
+/// Save (formerly Solend) reserves.
+#[cfg(feature = "save-deposit")]
+pub struct Save;
+
+#[cfg(feature = "save-deposit")]
+impl Save {
+    const DEPOSIT_RESERVE_LIQUIDITY: u8 = 4;
+    const REDEEM_RESERVE_COLLATERAL: u8 = 5;
+
+    fn encode(tag: u8, amount: u64) -> Vec<u8> {
+        let mut data = Vec::with_capacity(9);
+        data.push(tag);
+        data.extend_from_slice(&amount.to_le_bytes());
+        data
+    }
+}
+
+#[cfg(feature = "save-deposit")]
+impl DepositAdapter for Save {
+    fn protocol(&self) -> DepositProtocol {
+        DepositProtocol::Save
+    }
+
+    fn program_id(&self) -> Pubkey {
+        deposit_protocols::SAVE
+    }
+
+    // DepositReserveLiquidity (9): source_liquidity, destination_collateral, reserve,
+    //   reserve_liquidity_supply, reserve_collateral_mint, lending_market,
+    //   lending_market_authority, user_transfer_authority, token_program
+    // RedeemReserveCollateral (9): source_collateral, destination_liquidity, reserve,
+    //   reserve_collateral_mint, reserve_liquidity_supply, lending_market,
+    //   lending_market_authority, user_transfer_authority, token_program
+    fn min_accounts(&self) -> usize {
+        9
+    }
+
+    // Save reserves only hold SPL Token mints
+    fn validate_accounts(&self, accounts: &[AccountInfo]) -> Result<()> {
+        require_keys_eq!(accounts[8].key(), token::ID, ErrorCode::InvalidParameter);
+        Ok(())
+    }
+
+    fn deposit_source_index(&self) -> usize {
+        0 // source_liquidity
+    }
+
+    fn withdraw_destination_index(&self) -> usize {
+        1 // destination_liquidity
+    }
+
+    fn deposit_receipt_index(&self) -> Option<usize> {
+        Some(1) // destination_collateral
+    }
+
+    fn withdraw_receipt_index(&self) -> Option<usize> {
+        Some(0) // source_collateral
+    }
+
+    // tag(1) + liquidity_amount(8) = 9 bytes
+    fn encode_deposit(&self, amount: u64) -> Vec<u8> {
+        Self::encode(Self::DEPOSIT_RESERVE_LIQUIDITY, amount)
+    }
+
+    // tag(1) + collateral_amount(8) = 9 bytes
+    fn encode_withdraw(&self, amount: u64) -> Vec<u8> {
+        Self::encode(Self::REDEEM_RESERVE_COLLATERAL, amount)
+    }
+}
@@ -2,7 +2,13 @@ pub mod protocol_ids;
 pub mod swap_adapter;
 pub mod deposit_adapter;
 pub mod account_bridge;
+pub mod native_pool;
+pub mod adapter;
+pub mod swap_venues;
+pub mod deposit_venues;
+pub mod registry;
 
 pub use protocol_ids::*;
 pub use swap_adapter::*;
 pub use deposit_adapter::*;
+pub use adapter::*;
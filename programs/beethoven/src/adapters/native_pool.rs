@@ -0,0 +1,188 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Transfer};
+use crate::constants::SWAP_POOL_SEED;
+use crate::error::ErrorCode;
+use crate::adapters::adapter::{SwapAdapter, SwapRequest};
+use crate::adapters::protocol_ids::SwapProtocol;
+use crate::adapters::swap_adapter::SwapMode;
+use crate::math::amm::{swap_exact_in, swap_exact_out};
+use crate::state::SwapPool;
+
+/// Swap against one of beethoven's own constant-product pools.
+///
+/// Routed like an external protocol, with beethoven's program id in
+/// remaining_accounts[0]. `accounts` (remaining_accounts[1..]):
+///   [0] pool (writable)
+///   [1] pool vault of the input mint (writable)
+///   [2] pool vault of the output mint (writable)
+///   [3] trader (signer, or a PDA in `signer_seeds`)
+///   [4] trader input token account (writable)
+///   [5] trader output token account (writable)
+///   [6] token program
+///
+/// The input vault sets the direction. Amounts follow `mode` as in
+/// `swap_adapter::execute_swap`.
+pub fn swap<'info>(
+    accounts: &'info [AccountInfo<'info>],
+    in_amount: u64,
+    out_amount: u64,
+    mode: SwapMode,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    require!(accounts.len() >= 7, ErrorCode::InvalidParameter);
+    let vault_in = &accounts[1];
+    let vault_out = &accounts[2];
+    let token_program = &accounts[6];
+
+    let mut pool: Account<SwapPool> = Account::try_from(&accounts[0])?;
+    let a_to_b = match (vault_in.key(), vault_out.key()) {
+        (vin, vout) if vin == pool.vault_a && vout == pool.vault_b => true,
+        (vin, vout) if vin == pool.vault_b && vout == pool.vault_a => false,
+        _ => return Err(ErrorCode::PoolAccountMismatch.into()),
+    };
+
+    let (reserve_in, reserve_out) = if a_to_b {
+        (pool.reserve_a, pool.reserve_b)
+    } else {
+        (pool.reserve_b, pool.reserve_a)
+    };
+    let quote = match mode {
+        SwapMode::ExactIn => {
+            let quote = swap_exact_in(
+                reserve_in,
+                reserve_out,
+                in_amount,
+                pool.fee_bps,
+                pool.protocol_fee_share_bps,
+            )?;
+            require!(quote.amount_out >= out_amount, ErrorCode::SlippageExceeded);
+            quote
+        }
+        SwapMode::ExactOut => {
+            let quote = swap_exact_out(
+                reserve_in,
+                reserve_out,
+                out_amount,
+                pool.fee_bps,
+                pool.protocol_fee_share_bps,
+            )?;
+            require!(quote.amount_in <= in_amount, ErrorCode::SlippageExceeded);
+            quote
+        }
+    };
+    require!(quote.amount_out > 0, ErrorCode::SwapOutputZero);
+    require!(quote.amount_out < reserve_out, ErrorCode::InsufficientReserves);
+    let amount_in = quote.amount_in;
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            Transfer {
+                from: accounts[4].clone(),
+                to: vault_in.clone(),
+                authority: accounts[3].clone(),
+            },
+            signer_seeds,
+        ),
+        amount_in,
+    )?;
+
+    let pool_seeds = &[
+        SWAP_POOL_SEED,
+        pool.mint_a.as_ref(),
+        pool.mint_b.as_ref(),
+        &[pool.bump],
+    ];
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.clone(),
+            Transfer {
+                from: vault_out.clone(),
+                to: accounts[5].clone(),
+                authority: accounts[0].clone(),
+            },
+            &[&pool_seeds[..]],
+        ),
+        quote.amount_out,
+    )?;
+
+    // The LP share of the fee stays in the input reserve
+    let reserve_in_after = reserve_in
+        .checked_add(amount_in - quote.protocol_fee)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let reserve_out_after = reserve_out - quote.amount_out;
+    if a_to_b {
+        pool.reserve_a = reserve_in_after;
+        pool.reserve_b = reserve_out_after;
+        pool.protocol_fees_a = pool
+            .protocol_fees_a
+            .checked_add(quote.protocol_fee)
+            .ok_or(ErrorCode::MathOverflow)?;
+        pool.volume_a = pool.volume_a.saturating_add(amount_in);
+    } else {
+        pool.reserve_b = reserve_in_after;
+        pool.reserve_a = reserve_out_after;
+        pool.protocol_fees_b = pool
+            .protocol_fees_b
+            .checked_add(quote.protocol_fee)
+            .ok_or(ErrorCode::MathOverflow)?;
+        pool.volume_b = pool.volume_b.saturating_add(amount_in);
+    }
+    pool.exit(&crate::ID)
+}
+
+/// Registry entry for native pools: beethoven's own program id routes to
+/// `swap` in-process instead of a CPI.
+pub struct NativePool;
+
+impl SwapAdapter for NativePool {
+    fn protocol(&self) -> SwapProtocol {
+        SwapProtocol::Native
+    }
+
+    fn program_id(&self) -> Pubkey {
+        crate::ID
+    }
+
+    fn min_accounts(&self) -> usize {
+        7
+    }
+
+    fn supports_mode(&self, _mode: SwapMode) -> bool {
+        true
+    }
+
+    fn validate_accounts(&self, _program: &Pubkey, accounts: &[AccountInfo]) -> Result<()> {
+        require_keys_eq!(accounts[6].key(), token::ID, ErrorCode::InvalidParameter);
+        Ok(())
+    }
+
+    fn trader_token_accounts(
+        &self,
+        _accounts: &[AccountInfo],
+        _request: &SwapRequest,
+    ) -> Result<Option<(usize, usize)>> {
+        Ok(Some((4, 5)))
+    }
+
+    // No instruction is built; the pool is updated directly
+    fn encode(&self, _accounts: &[AccountInfo], _request: &SwapRequest) -> Result<Vec<u8>> {
+        Ok(Vec::new())
+    }
+
+    fn swap<'info>(
+        &self,
+        _program: &AccountInfo<'info>,
+        accounts: &'info [AccountInfo<'info>],
+        request: &SwapRequest,
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<()> {
+        swap(
+            accounts,
+            request.in_amount,
+            request.out_amount,
+            request.mode,
+            signer_seeds,
+        )
+    }
+}
@@ -1,9 +1,12 @@
 use anchor_lang::prelude::*;
+use crate::adapters::registry::{resolve_deposit_adapter, resolve_swap_adapter};
+use crate::state::RegistryAccount;
 
 // ── Protocol program IDs ─────────────────────────────────────────────
 // Sourced from the beethoven SDK protocol crates (feature-gated).
 // Beethoven detects which protocol to route to by checking
-// remaining_accounts[0] against these known program IDs.
+// remaining_accounts[0] against these known program IDs, unless the
+// on-chain ProtocolRegistry overrides or disables them.
 //
 // Protocols are opt-in via Cargo.toml feature flags:
 //   beethoven-sdk = { ..., features = ["manifest-swap", "kamino-deposit", ...] }
@@ -47,6 +50,22 @@ pub mod swap_protocols {
     /// Futarchy — `beethoven_sdk::futarchy::FUTARCHY_PROGRAM_ID`
     #[cfg(feature = "futarchy-swap")]
     pub const FUTARCHY: Pubkey = pubkey!("FUTARELBfJfQ8RDGhg1wdhddq1odMAJUePHFuBYfUxKq");
+
+    /// Orca Whirlpools — mainnet and devnet program ID
+    #[cfg(feature = "orca-swap")]
+    pub const ORCA_WHIRLPOOL: Pubkey = pubkey!("whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc");
+
+    /// Raydium CPMM — mainnet program ID
+    #[cfg(feature = "raydium-cpmm-swap")]
+    pub const RAYDIUM_CPMM: Pubkey = pubkey!("CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C");
+
+    /// Raydium CLMM — mainnet program ID
+    #[cfg(feature = "raydium-clmm-swap")]
+    pub const RAYDIUM_CLMM: Pubkey = pubkey!("CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK");
+
+    /// Phoenix v1 — mainnet and devnet program ID
+    #[cfg(feature = "phoenix-swap")]
+    pub const PHOENIX: Pubkey = pubkey!("PhoeNiXZ8ByJGLkxNfZRnkUfjvmuYqLR89jjFHGqdXY");
 }
 
 /// Deposit protocol program IDs (from beethoven deposit crates)
@@ -65,6 +84,14 @@ pub mod deposit_protocols {
     /// Devnet program ID for Jupiter Earn (lending/yield)
     #[cfg(feature = "jupiter-deposit")]
     pub const JUPITER: Pubkey = pubkey!("7tjE28izRUjzmxC1QNXnNwcc4N82CNYCexf3k8mw67s3");
+
+    /// marginfi v2 — mainnet program ID
+    #[cfg(feature = "marginfi-deposit")]
+    pub const MARGINFI: Pubkey = pubkey!("MFv2hWf31Z9kbCa1snEPYctwafyhdvnV7FZnsebVacA");
+
+    /// Save (Solend) — mainnet program ID
+    #[cfg(feature = "save-deposit")]
+    pub const SAVE: Pubkey = pubkey!("So1endDq2YkqhipRh3WViPa8hdiSpxWy6z3Q7bSnYkf");
 }
 
 /// Governance protocol program IDs (MetaDAO)
@@ -105,6 +132,16 @@ pub enum SwapProtocol {
     SolFi,
     #[cfg(feature = "futarchy-swap")]
     Futarchy,
+    #[cfg(feature = "orca-swap")]
+    Orca,
+    #[cfg(feature = "raydium-cpmm-swap")]
+    RaydiumCpmm,
+    #[cfg(feature = "raydium-clmm-swap")]
+    RaydiumClmm,
+    #[cfg(feature = "phoenix-swap")]
+    Phoenix,
+    /// Beethoven's own constant-product pools (always available)
+    Native,
     Unknown,
 }
 
@@ -114,54 +151,28 @@ pub enum DepositProtocol {
     Kamino,
     #[cfg(feature = "jupiter-deposit")]
     Jupiter,
+    #[cfg(feature = "marginfi-deposit")]
+    Marginfi,
+    #[cfg(feature = "save-deposit")]
+    Save,
     Unknown,
 }
 
-/// Detect which swap protocol from program ID (remaining_accounts[0]).
+/// Detect which swap protocol from program ID (remaining_accounts[0]),
+/// honouring the registry's overrides and disabled entries.
 /// Mirrors beethoven's `try_from_swap_context` detection pattern.
-pub fn detect_swap_protocol(program_id: &Pubkey) -> SwapProtocol {
-    #[cfg(feature = "manifest-swap")]
-    if *program_id == swap_protocols::MANIFEST {
-        return SwapProtocol::Manifest;
-    }
-    #[cfg(feature = "perena-swap")]
-    if *program_id == swap_protocols::PERENA {
-        return SwapProtocol::Perena;
-    }
-    #[cfg(feature = "heaven-swap")]
-    if *program_id == swap_protocols::HEAVEN {
-        return SwapProtocol::Heaven;
-    }
-    #[cfg(feature = "aldrin-swap")]
-    if *program_id == swap_protocols::ALDRIN {
-        return SwapProtocol::Aldrin;
-    }
-    #[cfg(feature = "gamma-swap")]
-    if *program_id == swap_protocols::GAMMA {
-        return SwapProtocol::Gamma;
-    }
-    #[cfg(feature = "solfi-swap")]
-    if *program_id == swap_protocols::SOLFI {
-        return SwapProtocol::SolFi;
-    }
-    #[cfg(feature = "futarchy-swap")]
-    if *program_id == swap_protocols::FUTARCHY {
-        return SwapProtocol::Futarchy;
-    }
-    let _ = program_id;
-    SwapProtocol::Unknown
+pub fn detect_swap_protocol(registry: &RegistryAccount, program_id: &Pubkey) -> SwapProtocol {
+    resolve_swap_adapter(registry, program_id)
+        .map(|adapter| adapter.protocol())
+        .unwrap_or(SwapProtocol::Unknown)
 }
 
-/// Detect which deposit protocol from program ID
-pub fn detect_deposit_protocol(program_id: &Pubkey) -> DepositProtocol {
-    #[cfg(feature = "kamino-deposit")]
-    if *program_id == deposit_protocols::KAMINO {
-        return DepositProtocol::Kamino;
-    }
-    #[cfg(feature = "jupiter-deposit")]
-    if *program_id == deposit_protocols::JUPITER {
-        return DepositProtocol::Jupiter;
-    }
-    let _ = program_id;
-    DepositProtocol::Unknown
+/// Detect which deposit protocol from program ID, honouring the registry.
+pub fn detect_deposit_protocol(
+    registry: &RegistryAccount,
+    program_id: &Pubkey,
+) -> DepositProtocol {
+    resolve_deposit_adapter(registry, program_id)
+        .map(|adapter| adapter.protocol())
+        .unwrap_or(DepositProtocol::Unknown)
 }
@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+use crate::adapters::adapter::{DepositAdapter, SwapAdapter};
+#[allow(unused_imports)]
+use crate::adapters::{deposit_venues, native_pool, swap_venues};
+use crate::error::ErrorCode;
+use crate::state::RegistryAccount;
+
+/// Every swap venue compiled into this build, keyed by program id.
+static SWAP_ADAPTERS: &[&dyn SwapAdapter] = &[
+    #[cfg(feature = "manifest-swap")]
+    &swap_venues::Manifest,
+    #[cfg(feature = "perena-swap")]
+    &swap_venues::Perena,
+    #[cfg(feature = "heaven-swap")]
+    &swap_venues::Heaven,
+    #[cfg(feature = "aldrin-swap")]
+    &swap_venues::Aldrin,
+    #[cfg(feature = "gamma-swap")]
+    &swap_venues::Gamma,
+    #[cfg(feature = "solfi-swap")]
+    &swap_venues::SolFi,
+    #[cfg(feature = "futarchy-swap")]
+    &swap_venues::Futarchy,
+    #[cfg(feature = "orca-swap")]
+    &swap_venues::Orca,
+    #[cfg(feature = "raydium-cpmm-swap")]
+    &swap_venues::RaydiumCpmm,
+    #[cfg(feature = "raydium-clmm-swap")]
+    &swap_venues::RaydiumClmm,
+    #[cfg(feature = "phoenix-swap")]
+    &swap_venues::Phoenix,
+    &native_pool::NativePool,
+];
+
+/// Every yield venue compiled into this build, keyed by program id.
+static DEPOSIT_ADAPTERS: &[&dyn DepositAdapter] = &[
+    #[cfg(feature = "kamino-deposit")]
+    &deposit_venues::Kamino,
+    #[cfg(feature = "jupiter-deposit")]
+    &deposit_venues::Jupiter,
+    #[cfg(feature = "marginfi-deposit")]
+    &deposit_venues::Marginfi,
+    #[cfg(feature = "save-deposit")]
+    &deposit_venues::Save,
+];
+
+pub fn swap_adapters() -> &'static [&'static dyn SwapAdapter] {
+    SWAP_ADAPTERS
+}
+
+pub fn deposit_adapters() -> &'static [&'static dyn DepositAdapter] {
+    DEPOSIT_ADAPTERS
+}
+
+/// Swap adapter for the protocol program in remaining_accounts[0].
+pub fn find_swap_adapter(program_id: &Pubkey) -> Result<&'static dyn SwapAdapter> {
+    SWAP_ADAPTERS
+        .iter()
+        .find(|adapter| adapter.program_id() == *program_id)
+        .copied()
+        .ok_or(ErrorCode::UnsupportedProtocol.into())
+}
+
+/// Deposit adapter for the protocol program in remaining_accounts[0].
+pub fn find_deposit_adapter(program_id: &Pubkey) -> Result<&'static dyn DepositAdapter> {
+    DEPOSIT_ADAPTERS
+        .iter()
+        .find(|adapter| adapter.program_id() == *program_id)
+        .copied()
+        .ok_or(ErrorCode::UnsupportedProtocol.into())
+}
+
+/// Whether `adapter_id` is the compiled program id of any adapter.
+pub fn is_compiled_adapter(adapter_id: &Pubkey) -> bool {
+    SWAP_ADAPTERS.iter().any(|adapter| adapter.program_id() == *adapter_id)
+        || DEPOSIT_ADAPTERS.iter().any(|adapter| adapter.program_id() == *adapter_id)
+}
+
+/// Swap adapter for `program_id` under the on-chain registry's overrides.
+pub fn resolve_swap_adapter(
+    registry: &RegistryAccount,
+    program_id: &Pubkey,
+) -> Result<&'static dyn SwapAdapter> {
+    find_swap_adapter(&registry.resolve_adapter_id(program_id)?)
+}
+
+/// Deposit adapter for `program_id` under the on-chain registry's overrides.
+pub fn resolve_deposit_adapter(
+    registry: &RegistryAccount,
+    program_id: &Pubkey,
+) -> Result<&'static dyn DepositAdapter> {
+    find_deposit_adapter(&registry.resolve_adapter_id(program_id)?)
+}
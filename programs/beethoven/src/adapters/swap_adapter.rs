@@ -1,8 +1,17 @@
 use anchor_lang::prelude::*;
-use crate::adapters::protocol_ids::{detect_swap_protocol, SwapProtocol};
-#[allow(unused_imports)]
-use crate::adapters::account_bridge::invoke_protocol_cpi;
+use crate::adapters::adapter::SwapRequest;
+use crate::adapters::registry::resolve_swap_adapter;
 use crate::error::ErrorCode;
+use crate::state::RegistryAccount;
+
+/// Which side of a swap is fixed.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SwapMode {
+    /// Spend exactly the input amount; the output amount is a minimum
+    ExactIn,
+    /// Receive exactly the output amount; the input amount is a maximum
+    ExactOut,
+}
 
 /// Execute a swap via Beethoven's composable routing pattern.
 ///
@@ -10,15 +19,32 @@ use crate::error::ErrorCode;
 ///   remaining_accounts[0] = Protocol program ID (executable)
 ///   remaining_accounts[1..] = Protocol-specific accounts
 ///
-/// Protocol detection uses beethoven SDK program ID constants (feature-gated).
+/// The protocol program is looked up in the adapter registry, which holds
+/// one `SwapAdapter` per feature-enabled protocol, after applying the
+/// on-chain `ProtocolRegistry` overrides; the swap's input counts against
+/// the protocol's volume cap, counted in `input_mint`. Passing this program's own id routes to a
+/// native constant-product pool.
 /// CPI is invoked through the account bridge since we're in Anchor context.
+/// `signer_seeds` lets a program PDA act as the trader (empty for a plain
+/// user-signed swap), so user swaps and fund swaps share this path.
+/// `trader_accounts`, when set, are the (input, output) token accounts the
+/// venue must debit and credit; the swap fails on venues whose layout can't
+/// be checked against them.
+///
+/// With `SwapMode::ExactIn`, `in_amount` is spent and `out_amount` is the
+/// minimum output; with `SwapMode::ExactOut`, `out_amount` is received and
+/// `in_amount` caps the input. `is_base_in` sets the direction on order-book
+/// venues (Manifest); pool venues take it from the order of their vault
+/// accounts or from `input_mint`.
+/// Exact-out is supported on Manifest, Gamma and native pools.
 ///
 /// See: https://blueshift.gg/research/composable-defi-with-beethoven
-#[allow(unused_variables)]
 pub fn execute_swap<'info>(
-    remaining_accounts: &[AccountInfo<'info>],
-    amount_in: u64,
-    minimum_amount_out: u64,
+    registry: &mut RegistryAccount<'info>,
+    remaining_accounts: &'info [AccountInfo<'info>],
+    request: &SwapRequest,
+    trader_accounts: Option<(Pubkey, Pubkey)>,
+    signer_seeds: &[&[&[u8]]],
 ) -> Result<()> {
     require!(
         !remaining_accounts.is_empty(),
@@ -26,89 +52,33 @@ pub fn execute_swap<'info>(
     );
 
     let protocol_program = &remaining_accounts[0];
-    let protocol = detect_swap_protocol(protocol_program.key);
+    let adapter = resolve_swap_adapter(registry, protocol_program.key)?;
+    let accounts = &remaining_accounts[1..];
 
     require!(
-        protocol != SwapProtocol::Unknown,
-        ErrorCode::UnsupportedProtocol
+        adapter.supports_mode(request.mode),
+        ErrorCode::UnsupportedSwapMode
     );
-
-    let accounts = &remaining_accounts[1..];
-
-    // Build protocol-specific instruction data and invoke CPI.
-    // Each protocol has its own discriminator and data layout,
-    // matching the instruction encoding in beethoven's swap crates.
-    match protocol {
-        #[cfg(feature = "manifest-swap")]
-        SwapProtocol::Manifest => {
-            // Manifest Swap: discriminator(1) + in_atoms(8) + out_atoms(8) + is_base_in(1) + is_exact_in(1) = 19 bytes
-            // Accounts: payer, market, trader_base, trader_quote, base_vault, quote_vault, token_program_base
-            // Note: Manifest SwapContext uses sequential parsing — no system_program account for Swap.
-            require!(accounts.len() >= 7, ErrorCode::InvalidParameter);
-            let mut data = Vec::with_capacity(19);
-            data.push(4u8); // Manifest Swap discriminator
-            data.extend_from_slice(&amount_in.to_le_bytes());
-            data.extend_from_slice(&minimum_amount_out.to_le_bytes());
-            data.push(0u8); // is_base_in = false (spending quote to buy base)
-            data.push(1u8); // is_exact_in = true
-            invoke_protocol_cpi(protocol_program, accounts, data)
-        }
-        #[cfg(feature = "perena-swap")]
-        SwapProtocol::Perena => {
-            require!(accounts.len() >= 5, ErrorCode::InvalidParameter);
-            let mut data = Vec::with_capacity(24);
-            // Perena uses an 8-byte discriminator
-            data.extend_from_slice(&[0x30, 0x31, 0x36, 0x64, 0x62, 0x39, 0x61, 0x35]);
-            data.extend_from_slice(&amount_in.to_le_bytes());
-            data.extend_from_slice(&minimum_amount_out.to_le_bytes());
-            invoke_protocol_cpi(protocol_program, accounts, data)
-        }
-        #[cfg(feature = "heaven-swap")]
-        SwapProtocol::Heaven => {
-            require!(accounts.len() >= 5, ErrorCode::InvalidParameter);
-            let mut data = Vec::with_capacity(24);
-            data.extend_from_slice(&[0xe5, 0x17, 0xcb, 0x97, 0x7a, 0xe3, 0xad, 0x2a]);
-            data.extend_from_slice(&amount_in.to_le_bytes());
-            data.extend_from_slice(&minimum_amount_out.to_le_bytes());
-            invoke_protocol_cpi(protocol_program, accounts, data)
-        }
-        #[cfg(feature = "aldrin-swap")]
-        SwapProtocol::Aldrin => {
-            require!(accounts.len() >= 6, ErrorCode::InvalidParameter);
-            let mut data = Vec::with_capacity(24);
-            data.extend_from_slice(&[0x87, 0x6a, 0xdc, 0x47, 0x11, 0x4e, 0x79, 0xb1]);
-            data.extend_from_slice(&amount_in.to_le_bytes());
-            data.extend_from_slice(&minimum_amount_out.to_le_bytes());
-            invoke_protocol_cpi(protocol_program, accounts, data)
-        }
-        #[cfg(feature = "gamma-swap")]
-        SwapProtocol::Gamma => {
-            // Gamma: discriminator(8) + in_amount(8) + min_out(8) = 24 bytes
-            require!(accounts.len() >= 13, ErrorCode::InvalidParameter);
-            let mut data = Vec::with_capacity(24);
-            data.extend_from_slice(&[239, 82, 192, 187, 160, 26, 223, 223]); // Gamma SWAP_DISCRIMINATOR
-            data.extend_from_slice(&amount_in.to_le_bytes());
-            data.extend_from_slice(&minimum_amount_out.to_le_bytes());
-            invoke_protocol_cpi(protocol_program, accounts, data)
-        }
-        #[cfg(feature = "solfi-swap")]
-        SwapProtocol::SolFi => {
-            require!(accounts.len() >= 5, ErrorCode::InvalidParameter);
-            let mut data = Vec::with_capacity(24);
-            data.extend_from_slice(&[0xa3, 0xb2, 0xc1, 0xd0, 0xe4, 0xf5, 0x06, 0x17]);
-            data.extend_from_slice(&amount_in.to_le_bytes());
-            data.extend_from_slice(&minimum_amount_out.to_le_bytes());
-            invoke_protocol_cpi(protocol_program, accounts, data)
-        }
-        #[cfg(feature = "futarchy-swap")]
-        SwapProtocol::Futarchy => {
-            require!(accounts.len() >= 5, ErrorCode::InvalidParameter);
-            let mut data = Vec::with_capacity(24);
-            data.extend_from_slice(&[0xb4, 0xc3, 0xd2, 0xe1, 0xf5, 0x06, 0x17, 0x28]);
-            data.extend_from_slice(&amount_in.to_le_bytes());
-            data.extend_from_slice(&minimum_amount_out.to_le_bytes());
-            invoke_protocol_cpi(protocol_program, accounts, data)
-        }
-        SwapProtocol::Unknown => Err(ErrorCode::UnsupportedProtocol.into()),
+    require!(
+        accounts.len() >= adapter.min_accounts(),
+        ErrorCode::InvalidParameter
+    );
+    adapter.validate_accounts(protocol_program.key, accounts)?;
+    if let Some((input, output)) = trader_accounts {
+        let (input_index, output_index) = adapter
+            .trader_token_accounts(accounts, request)?
+            .ok_or(ErrorCode::TraderAccountMismatch)?;
+        require!(
+            accounts[input_index].key() == input && accounts[output_index].key() == output,
+            ErrorCode::TraderAccountMismatch
+        );
     }
+    registry.record_volume(
+        &adapter.program_id(),
+        &request.input_mint,
+        request.in_amount,
+        Clock::get()?.unix_timestamp,
+    )?;
+
+    adapter.swap(protocol_program, accounts, request, signer_seeds)
 }
@@ -0,0 +1,772 @@
+#![allow(unused_imports)]
+use anchor_lang::prelude::*;
+use anchor_spl::{token, token_2022};
+use crate::adapters::adapter::{encode_two_amounts, require_token_program, SwapAdapter, SwapRequest};
+use crate::adapters::protocol_ids::{swap_protocols, SwapProtocol};
+use crate::adapters::swap_adapter::SwapMode;
+use crate::error::ErrorCode;
+
+// One `SwapAdapter` per external swap protocol, matching the instruction
+// encoding in beethoven's swap crates. Register new venues in `registry`.
+
+/// SPL Memo program, required by the concentrated-liquidity swap_v2 instructions.
+#[cfg(any(feature = "orca-swap", feature = "raydium-clmm-swap"))]
+const MEMO_PROGRAM: Pubkey = pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+/// Pubkey stored at `offset` in a protocol account's data.
+#[cfg(any(
+    feature = "orca-swap",
+    feature = "raydium-cpmm-swap",
+    feature = "raydium-clmm-swap",
+    feature = "phoenix-swap",
+))]
+fn read_pubkey(data: &[u8], offset: usize) -> Result<Pubkey> {
+    let bytes: [u8; 32] = data
+        .get(offset..offset + 32)
+        .and_then(|slice| slice.try_into().ok())
+        .ok_or(ErrorCode::InvalidParameter)?;
+    Ok(Pubkey::new_from_array(bytes))
+}
+
+/// Manifest DEX order books.
+#[cfg(feature = "manifest-swap")]
+pub struct Manifest;
+
+#[cfg(feature = "manifest-swap")]
+impl SwapAdapter for Manifest {
+    fn protocol(&self) -> SwapProtocol {
+        SwapProtocol::Manifest
+    }
+
+    fn program_id(&self) -> Pubkey {
+        swap_protocols::MANIFEST
+    }
+
+    // payer, market, trader_base, trader_quote, base_vault, quote_vault, token_program_base
+    // Note: Manifest SwapContext uses sequential parsing — no system_program account for Swap.
+    fn min_accounts(&self) -> usize {
+        7
+    }
+
+    fn supports_mode(&self, _mode: SwapMode) -> bool {
+        true
+    }
+
+    fn validate_accounts(&self, _program: &Pubkey, accounts: &[AccountInfo]) -> Result<()> {
+        require_token_program(&accounts[6])
+    }
+
+    fn trader_token_accounts(
+        &self,
+        _accounts: &[AccountInfo],
+        request: &SwapRequest,
+    ) -> Result<Option<(usize, usize)>> {
+        Ok(Some(if request.is_base_in { (2, 3) } else { (3, 2) }))
+    }
+
+    // discriminator(1) + in_atoms(8) + out_atoms(8) + is_base_in(1) + is_exact_in(1) = 19 bytes
+    // in_atoms is exact (exact-in) or a maximum (exact-out); out_atoms the reverse.
+    fn encode(&self, _accounts: &[AccountInfo], request: &SwapRequest) -> Result<Vec<u8>> {
+        let mut data = Vec::with_capacity(19);
+        data.push(4u8); // Manifest Swap discriminator
+        data.extend_from_slice(&request.in_amount.to_le_bytes());
+        data.extend_from_slice(&request.out_amount.to_le_bytes());
+        data.push(request.is_base_in as u8);
+        data.push((request.mode == SwapMode::ExactIn) as u8);
+        Ok(data)
+    }
+}
+
+/// Perena Numéraire stable pools.
+#[cfg(feature = "perena-swap")]
+pub struct Perena;
+
+#[cfg(feature = "perena-swap")]
+impl SwapAdapter for Perena {
+    fn protocol(&self) -> SwapProtocol {
+        SwapProtocol::Perena
+    }
+
+    fn program_id(&self) -> Pubkey {
+        swap_protocols::PERENA
+    }
+
+    fn min_accounts(&self) -> usize {
+        5
+    }
+
+    // Perena uses an 8-byte discriminator
+    fn encode(&self, _accounts: &[AccountInfo], request: &SwapRequest) -> Result<Vec<u8>> {
+        Ok(encode_two_amounts(
+            [0x30, 0x31, 0x36, 0x64, 0x62, 0x39, 0x61, 0x35],
+            request.in_amount,
+            request.out_amount,
+        ))
+    }
+}
+
+/// Heaven Protocol AMM.
+#[cfg(feature = "heaven-swap")]
+pub struct Heaven;
+
+#[cfg(feature = "heaven-swap")]
+impl SwapAdapter for Heaven {
+    fn protocol(&self) -> SwapProtocol {
+        SwapProtocol::Heaven
+    }
+
+    fn program_id(&self) -> Pubkey {
+        swap_protocols::HEAVEN
+    }
+
+    fn min_accounts(&self) -> usize {
+        5
+    }
+
+    fn encode(&self, _accounts: &[AccountInfo], request: &SwapRequest) -> Result<Vec<u8>> {
+        Ok(encode_two_amounts(
+            [0xe5, 0x17, 0xcb, 0x97, 0x7a, 0xe3, 0xad, 0x2a],
+            request.in_amount,
+            request.out_amount,
+        ))
+    }
+}
+
+/// Aldrin AMM.
+#[cfg(feature = "aldrin-swap")]
+pub struct Aldrin;
+
+#[cfg(feature = "aldrin-swap")]
+impl SwapAdapter for Aldrin {
+    fn protocol(&self) -> SwapProtocol {
+        SwapProtocol::Aldrin
+    }
+
+    fn program_id(&self) -> Pubkey {
+        swap_protocols::ALDRIN
+    }
+
+    fn min_accounts(&self) -> usize {
+        6
+    }
+
+    fn encode(&self, _accounts: &[AccountInfo], request: &SwapRequest) -> Result<Vec<u8>> {
+        Ok(encode_two_amounts(
+            [0x87, 0x6a, 0xdc, 0x47, 0x11, 0x4e, 0x79, 0xb1],
+            request.in_amount,
+            request.out_amount,
+        ))
+    }
+}
+
+/// Gamma oracle-based pools.
+#[cfg(feature = "gamma-swap")]
+pub struct Gamma;
+
+#[cfg(feature = "gamma-swap")]
+impl SwapAdapter for Gamma {
+    fn protocol(&self) -> SwapProtocol {
+        SwapProtocol::Gamma
+    }
+
+    fn program_id(&self) -> Pubkey {
+        swap_protocols::GAMMA
+    }
+
+    // payer, authority, amm_config, pool_state, input_token_account,
+    //   output_token_account, input_vault, output_vault, input_token_program,
+    //   output_token_program, input_token_mint, output_token_mint, observation_state
+    fn min_accounts(&self) -> usize {
+        13
+    }
+
+    fn supports_mode(&self, _mode: SwapMode) -> bool {
+        true
+    }
+
+    fn trader_token_accounts(
+        &self,
+        _accounts: &[AccountInfo],
+        _request: &SwapRequest,
+    ) -> Result<Option<(usize, usize)>> {
+        Ok(Some((4, 5)))
+    }
+
+    // discriminator(8) + two amounts(8 + 8) = 24 bytes
+    //   oracle_based_swap_base_input:  amount_in, minimum_amount_out
+    //   oracle_based_swap_base_output: max_amount_in, amount_out
+    fn encode(&self, _accounts: &[AccountInfo], request: &SwapRequest) -> Result<Vec<u8>> {
+        let discriminator = match request.mode {
+            SwapMode::ExactIn => [239, 82, 192, 187, 160, 26, 223, 223], // Gamma SWAP_DISCRIMINATOR
+            SwapMode::ExactOut => [79, 248, 244, 178, 237, 17, 198, 117], // oracle_based_swap_base_output
+        };
+        Ok(encode_two_amounts(
+            discriminator,
+            request.in_amount,
+            request.out_amount,
+        ))
+    }
+}
+
+/// SolFi prop AMM.
+#[cfg(feature = "solfi-swap")]
+pub struct SolFi;
+
+#[cfg(feature = "solfi-swap")]
+impl SwapAdapter for SolFi {
+    fn protocol(&self) -> SwapProtocol {
+        SwapProtocol::SolFi
+    }
+
+    fn program_id(&self) -> Pubkey {
+        swap_protocols::SOLFI
+    }
+
+    fn min_accounts(&self) -> usize {
+        5
+    }
+
+    fn encode(&self, _accounts: &[AccountInfo], request: &SwapRequest) -> Result<Vec<u8>> {
+        Ok(encode_two_amounts(
+            [0xa3, 0xb2, 0xc1, 0xd0, 0xe4, 0xf5, 0x06, 0x17],
+            request.in_amount,
+            request.out_amount,
+        ))
+    }
+}
+
+/// Futarchy (MetaDAO) conditional markets.
+#[cfg(feature = "futarchy-swap")]
+pub struct Futarchy;
+
+#[cfg(feature = "futarchy-swap")]
+impl SwapAdapter for Futarchy {
+    fn protocol(&self) -> SwapProtocol {
+        SwapProtocol::Futarchy
+    }
+
+    fn program_id(&self) -> Pubkey {
+        swap_protocols::FUTARCHY
+    }
+
+    fn min_accounts(&self) -> usize {
+        5
+    }
+
+    fn encode(&self, _accounts: &[AccountInfo], request: &SwapRequest) -> Result<Vec<u8>> {
+        Ok(encode_two_amounts(
+            [0xb4, 0xc3, 0xd2, 0xe1, 0xf5, 0x06, 0x17, 0x28],
+            request.in_amount,
+            request.out_amount,
+        ))
+    }
+}
+
+/// Orca Whirlpools concentrated-liquidity pools.
+#[cfg(feature = "orca-swap")]
+pub struct Orca;
+
+#[cfg(feature = "orca-swap")]
+impl Orca {
+    // Whirlpool account offsets (after the 8-byte discriminator)
+    const WHIRLPOOL_LEN: usize = 653;
+    const MINT_A_OFFSET: usize = 101;
+    const VAULT_A_OFFSET: usize = 133;
+    const MINT_B_OFFSET: usize = 181;
+    const VAULT_B_OFFSET: usize = 213;
+
+    // Fixed tick arrays store their whirlpool after 88 ticks; dynamic tick
+    // arrays store it right after start_tick_index
+    const FIXED_TICK_ARRAY_LEN: usize = 9988;
+    const FIXED_TICK_ARRAY_WHIRLPOOL_OFFSET: usize = 9956;
+    const DYNAMIC_TICK_ARRAY_WHIRLPOOL_OFFSET: usize = 12;
+
+    // Price bounds of the Whirlpool program; swapping to the bound in the
+    // direction of trade means no price limit
+    const MIN_SQRT_PRICE: u128 = 4_295_048_016;
+    const MAX_SQRT_PRICE: u128 = 79_226_673_515_401_279_992_447_579_055;
+
+    /// (token_mint_a, token_mint_b) of a Whirlpool-owned pool account.
+    fn pool_mints(whirlpool: &AccountInfo) -> Result<(Pubkey, Pubkey)> {
+        let data = whirlpool.try_borrow_data()?;
+        Ok((
+            read_pubkey(&data, Self::MINT_A_OFFSET)?,
+            read_pubkey(&data, Self::MINT_B_OFFSET)?,
+        ))
+    }
+
+    fn require_tick_array(
+        program: &Pubkey,
+        tick_array: &AccountInfo,
+        whirlpool: &Pubkey,
+    ) -> Result<()> {
+        require_keys_eq!(*tick_array.owner, *program, ErrorCode::PoolAccountMismatch);
+        let data = tick_array.try_borrow_data()?;
+        let offset = if data.len() == Self::FIXED_TICK_ARRAY_LEN {
+            Self::FIXED_TICK_ARRAY_WHIRLPOOL_OFFSET
+        } else {
+            Self::DYNAMIC_TICK_ARRAY_WHIRLPOOL_OFFSET
+        };
+        require_keys_eq!(
+            read_pubkey(&data, offset)?,
+            *whirlpool,
+            ErrorCode::PoolAccountMismatch
+        );
+        Ok(())
+    }
+}
+
+#[cfg(feature = "orca-swap")]
+impl SwapAdapter for Orca {
+    fn protocol(&self) -> SwapProtocol {
+        SwapProtocol::Orca
+    }
+
+    fn program_id(&self) -> Pubkey {
+        swap_protocols::ORCA_WHIRLPOOL
+    }
+
+    // swap_v2 (15): token_program_a, token_program_b, memo_program, token_authority,
+    //   whirlpool, token_mint_a, token_mint_b, token_owner_account_a, token_vault_a,
+    //   token_owner_account_b, token_vault_b, tick_array_0, tick_array_1,
+    //   tick_array_2, oracle
+    fn min_accounts(&self) -> usize {
+        15
+    }
+
+    fn supports_mode(&self, _mode: SwapMode) -> bool {
+        true
+    }
+
+    fn validate_accounts(&self, program: &Pubkey, accounts: &[AccountInfo]) -> Result<()> {
+        require_token_program(&accounts[0])?;
+        require_token_program(&accounts[1])?;
+        require_keys_eq!(accounts[2].key(), MEMO_PROGRAM, ErrorCode::InvalidParameter);
+
+        let whirlpool = &accounts[4];
+        require_keys_eq!(*whirlpool.owner, *program, ErrorCode::PoolAccountMismatch);
+        {
+            let data = whirlpool.try_borrow_data()?;
+            require!(data.len() >= Self::WHIRLPOOL_LEN, ErrorCode::PoolAccountMismatch);
+            for (offset, account) in [
+                (Self::MINT_A_OFFSET, &accounts[5]),
+                (Self::MINT_B_OFFSET, &accounts[6]),
+                (Self::VAULT_A_OFFSET, &accounts[8]),
+                (Self::VAULT_B_OFFSET, &accounts[10]),
+            ] {
+                require_keys_eq!(
+                    read_pubkey(&data, offset)?,
+                    account.key(),
+                    ErrorCode::PoolAccountMismatch
+                );
+            }
+        }
+
+        // The caller picks the tick arrays for the direction of trade; each
+        // must belong to this pool
+        for tick_array in &accounts[11..14] {
+            Self::require_tick_array(program, tick_array, &whirlpool.key())?;
+        }
+        Ok(())
+    }
+
+    // token_owner_account_a and _b; a_to_b when the input is mint A
+    fn trader_token_accounts(
+        &self,
+        accounts: &[AccountInfo],
+        request: &SwapRequest,
+    ) -> Result<Option<(usize, usize)>> {
+        let (mint_a, _) = Self::pool_mints(&accounts[4])?;
+        Ok(Some(if request.input_mint == mint_a { (7, 9) } else { (9, 7) }))
+    }
+
+    // discriminator(8) + amount(8) + other_amount_threshold(8) + sqrt_price_limit(16)
+    //   + amount_specified_is_input(1) + a_to_b(1) + remaining_accounts_info(1) = 43 bytes
+    // amount is the exact input (exact-in) or exact output (exact-out);
+    // other_amount_threshold the minimum output or maximum input.
+    fn encode(&self, accounts: &[AccountInfo], request: &SwapRequest) -> Result<Vec<u8>> {
+        let (mint_a, mint_b) = Self::pool_mints(&accounts[4])?;
+        let a_to_b = if request.input_mint == mint_a {
+            true
+        } else {
+            require_keys_eq!(request.input_mint, mint_b, ErrorCode::PoolAccountMismatch);
+            false
+        };
+        let (amount, other_amount_threshold) = match request.mode {
+            SwapMode::ExactIn => (request.in_amount, request.out_amount),
+            SwapMode::ExactOut => (request.out_amount, request.in_amount),
+        };
+        let sqrt_price_limit = if a_to_b {
+            Self::MIN_SQRT_PRICE
+        } else {
+            Self::MAX_SQRT_PRICE
+        };
+
+        let mut data = Vec::with_capacity(43);
+        data.extend_from_slice(&[43, 4, 237, 11, 26, 201, 30, 98]); // swap_v2
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.extend_from_slice(&other_amount_threshold.to_le_bytes());
+        data.extend_from_slice(&sqrt_price_limit.to_le_bytes());
+        data.push((request.mode == SwapMode::ExactIn) as u8);
+        data.push(a_to_b as u8);
+        data.push(0); // remaining_accounts_info: None
+        Ok(data)
+    }
+}
+
+/// Raydium CPMM constant-product pools.
+#[cfg(feature = "raydium-cpmm-swap")]
+pub struct RaydiumCpmm;
+
+#[cfg(feature = "raydium-cpmm-swap")]
+impl RaydiumCpmm {
+    // PoolState offsets (after the 8-byte discriminator)
+    const TOKEN_0_VAULT_OFFSET: usize = 72;
+    const TOKEN_1_VAULT_OFFSET: usize = 104;
+    const TOKEN_0_MINT_OFFSET: usize = 168;
+    const TOKEN_1_MINT_OFFSET: usize = 200;
+    const OBSERVATION_KEY_OFFSET: usize = 296;
+}
+
+#[cfg(feature = "raydium-cpmm-swap")]
+impl SwapAdapter for RaydiumCpmm {
+    fn protocol(&self) -> SwapProtocol {
+        SwapProtocol::RaydiumCpmm
+    }
+
+    fn program_id(&self) -> Pubkey {
+        swap_protocols::RAYDIUM_CPMM
+    }
+
+    // swap_base_input / swap_base_output (13): payer, authority, amm_config, pool_state,
+    //   input_token_account, output_token_account, input_vault, output_vault,
+    //   input_token_program, output_token_program, input_token_mint,
+    //   output_token_mint, observation_state
+    fn min_accounts(&self) -> usize {
+        13
+    }
+
+    fn supports_mode(&self, _mode: SwapMode) -> bool {
+        true
+    }
+
+    fn validate_accounts(&self, program: &Pubkey, accounts: &[AccountInfo]) -> Result<()> {
+        require_token_program(&accounts[8])?;
+        require_token_program(&accounts[9])?;
+
+        let pool = &accounts[3];
+        require_keys_eq!(*pool.owner, *program, ErrorCode::PoolAccountMismatch);
+        let data = pool.try_borrow_data()?;
+        let vault_0 = read_pubkey(&data, Self::TOKEN_0_VAULT_OFFSET)?;
+        let vault_1 = read_pubkey(&data, Self::TOKEN_1_VAULT_OFFSET)?;
+        let mint_0 = read_pubkey(&data, Self::TOKEN_0_MINT_OFFSET)?;
+        let mint_1 = read_pubkey(&data, Self::TOKEN_1_MINT_OFFSET)?;
+
+        // Direction is set by account order: input vault and mint first
+        let (input_vault, input_mint, output_vault, output_mint) =
+            if accounts[6].key() == vault_0 {
+                (vault_0, mint_0, vault_1, mint_1)
+            } else {
+                (vault_1, mint_1, vault_0, mint_0)
+            };
+        for (expected, account) in [
+            (input_vault, &accounts[6]),
+            (output_vault, &accounts[7]),
+            (input_mint, &accounts[10]),
+            (output_mint, &accounts[11]),
+        ] {
+            require_keys_eq!(expected, account.key(), ErrorCode::PoolAccountMismatch);
+        }
+        require_keys_eq!(
+            read_pubkey(&data, Self::OBSERVATION_KEY_OFFSET)?,
+            accounts[12].key(),
+            ErrorCode::PoolAccountMismatch
+        );
+        Ok(())
+    }
+
+    fn trader_token_accounts(
+        &self,
+        _accounts: &[AccountInfo],
+        _request: &SwapRequest,
+    ) -> Result<Option<(usize, usize)>> {
+        Ok(Some((4, 5)))
+    }
+
+    // discriminator(8) + two amounts(8 + 8) = 24 bytes
+    //   swap_base_input:  amount_in, minimum_amount_out
+    //   swap_base_output: max_amount_in, amount_out
+    fn encode(&self, accounts: &[AccountInfo], request: &SwapRequest) -> Result<Vec<u8>> {
+        require_keys_eq!(
+            accounts[10].key(),
+            request.input_mint,
+            ErrorCode::PoolAccountMismatch
+        );
+        let discriminator = match request.mode {
+            SwapMode::ExactIn => [143, 190, 90, 218, 196, 30, 51, 222], // swap_base_input
+            SwapMode::ExactOut => [55, 217, 98, 86, 163, 74, 180, 173], // swap_base_output
+        };
+        Ok(encode_two_amounts(
+            discriminator,
+            request.in_amount,
+            request.out_amount,
+        ))
+    }
+}
+
+/// Raydium CLMM concentrated-liquidity pools.
+#[cfg(feature = "raydium-clmm-swap")]
+pub struct RaydiumClmm;
+
+#[cfg(feature = "raydium-clmm-swap")]
+impl RaydiumClmm {
+    // PoolState offsets (after the 8-byte discriminator and bump)
+    const TOKEN_MINT_0_OFFSET: usize = 73;
+    const TOKEN_MINT_1_OFFSET: usize = 105;
+    const TOKEN_VAULT_0_OFFSET: usize = 137;
+    const TOKEN_VAULT_1_OFFSET: usize = 169;
+    const OBSERVATION_KEY_OFFSET: usize = 201;
+
+    // Tick arrays and the bitmap extension both lead with their pool id
+    const POOL_ID_OFFSET: usize = 8;
+
+    const FIXED_ACCOUNTS: usize = 13;
+}
+
+#[cfg(feature = "raydium-clmm-swap")]
+impl SwapAdapter for RaydiumClmm {
+    fn protocol(&self) -> SwapProtocol {
+        SwapProtocol::RaydiumClmm
+    }
+
+    fn program_id(&self) -> Pubkey {
+        swap_protocols::RAYDIUM_CLMM
+    }
+
+    // swap_v2 (13 + tick arrays): payer, amm_config, pool_state, input_token_account,
+    //   output_token_account, input_vault, output_vault, observation_state,
+    //   token_program, token_program_2022, memo_program, input_vault_mint,
+    //   output_vault_mint, then the optional tick-array bitmap extension and
+    //   the tick arrays the swap crosses, in order of traversal
+    fn min_accounts(&self) -> usize {
+        Self::FIXED_ACCOUNTS + 1
+    }
+
+    fn supports_mode(&self, _mode: SwapMode) -> bool {
+        true
+    }
+
+    fn validate_accounts(&self, program: &Pubkey, accounts: &[AccountInfo]) -> Result<()> {
+        require_keys_eq!(accounts[8].key(), token::ID, ErrorCode::InvalidParameter);
+        require_keys_eq!(accounts[9].key(), token_2022::ID, ErrorCode::InvalidParameter);
+        require_keys_eq!(accounts[10].key(), MEMO_PROGRAM, ErrorCode::InvalidParameter);
+
+        let pool = &accounts[2];
+        require_keys_eq!(*pool.owner, *program, ErrorCode::PoolAccountMismatch);
+        {
+            let data = pool.try_borrow_data()?;
+            let vault_0 = read_pubkey(&data, Self::TOKEN_VAULT_0_OFFSET)?;
+            let vault_1 = read_pubkey(&data, Self::TOKEN_VAULT_1_OFFSET)?;
+            let mint_0 = read_pubkey(&data, Self::TOKEN_MINT_0_OFFSET)?;
+            let mint_1 = read_pubkey(&data, Self::TOKEN_MINT_1_OFFSET)?;
+
+            // Direction is set by account order: input vault and mint first
+            let (input_vault, input_mint, output_vault, output_mint) =
+                if accounts[5].key() == vault_0 {
+                    (vault_0, mint_0, vault_1, mint_1)
+                } else {
+                    (vault_1, mint_1, vault_0, mint_0)
+                };
+            for (expected, account) in [
+                (input_vault, &accounts[5]),
+                (output_vault, &accounts[6]),
+                (input_mint, &accounts[11]),
+                (output_mint, &accounts[12]),
+            ] {
+                require_keys_eq!(expected, account.key(), ErrorCode::PoolAccountMismatch);
+            }
+            require_keys_eq!(
+                read_pubkey(&data, Self::OBSERVATION_KEY_OFFSET)?,
+                accounts[7].key(),
+                ErrorCode::PoolAccountMismatch
+            );
+        }
+
+        // Every trailing account is a tick array or the bitmap extension of this pool
+        for tick_account in &accounts[Self::FIXED_ACCOUNTS..] {
+            require_keys_eq!(*tick_account.owner, *program, ErrorCode::PoolAccountMismatch);
+            let data = tick_account.try_borrow_data()?;
+            require_keys_eq!(
+                read_pubkey(&data, Self::POOL_ID_OFFSET)?,
+                pool.key(),
+                ErrorCode::PoolAccountMismatch
+            );
+        }
+        Ok(())
+    }
+
+    fn trader_token_accounts(
+        &self,
+        _accounts: &[AccountInfo],
+        _request: &SwapRequest,
+    ) -> Result<Option<(usize, usize)>> {
+        Ok(Some((3, 4)))
+    }
+
+    // discriminator(8) + amount(8) + other_amount_threshold(8)
+    //   + sqrt_price_limit_x64(16) + is_base_input(1) = 41 bytes
+    // A zero price limit lets the swap run to the pool's price bound.
+    fn encode(&self, accounts: &[AccountInfo], request: &SwapRequest) -> Result<Vec<u8>> {
+        require_keys_eq!(
+            accounts[11].key(),
+            request.input_mint,
+            ErrorCode::PoolAccountMismatch
+        );
+        let (amount, other_amount_threshold) = match request.mode {
+            SwapMode::ExactIn => (request.in_amount, request.out_amount),
+            SwapMode::ExactOut => (request.out_amount, request.in_amount),
+        };
+
+        let mut data = Vec::with_capacity(41);
+        data.extend_from_slice(&[43, 4, 237, 11, 26, 201, 30, 98]); // swap_v2
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.extend_from_slice(&other_amount_threshold.to_le_bytes());
+        data.extend_from_slice(&0u128.to_le_bytes());
+        data.push((request.mode == SwapMode::ExactIn) as u8);
+        Ok(data)
+    }
+}
+
+/// Phoenix order books, taken with immediate-or-cancel orders.
+#[cfg(feature = "phoenix-swap")]
+pub struct Phoenix;
+
+#[cfg(feature = "phoenix-swap")]
+impl Phoenix {
+    // MarketHeader offsets; markets carry no Anchor discriminator
+    const BASE_MINT_OFFSET: usize = 48;
+    const BASE_VAULT_OFFSET: usize = 80;
+    const BASE_LOT_SIZE_OFFSET: usize = 112;
+    const QUOTE_MINT_OFFSET: usize = 128;
+    const QUOTE_VAULT_OFFSET: usize = 160;
+    const QUOTE_LOT_SIZE_OFFSET: usize = 192;
+
+    const SIDE_BID: u8 = 0; // Buy base with quote
+    const SIDE_ASK: u8 = 1; // Sell base for quote
+
+    fn read_u64(data: &[u8], offset: usize) -> Result<u64> {
+        let bytes: [u8; 8] = data
+            .get(offset..offset + 8)
+            .and_then(|slice| slice.try_into().ok())
+            .ok_or(ErrorCode::InvalidParameter)?;
+        Ok(u64::from_le_bytes(bytes))
+    }
+}
+
+#[cfg(feature = "phoenix-swap")]
+impl SwapAdapter for Phoenix {
+    fn protocol(&self) -> SwapProtocol {
+        SwapProtocol::Phoenix
+    }
+
+    fn program_id(&self) -> Pubkey {
+        swap_protocols::PHOENIX
+    }
+
+    // Swap (9): phoenix_program, log_authority, market, trader, base_account,
+    //   quote_account, base_vault, quote_vault, token_program
+    // Phoenix logs through a self-CPI, so its program is also the first swap account.
+    fn min_accounts(&self) -> usize {
+        9
+    }
+
+    fn validate_accounts(&self, program: &Pubkey, accounts: &[AccountInfo]) -> Result<()> {
+        require_keys_eq!(accounts[0].key(), *program, ErrorCode::InvalidParameter);
+        require_token_program(&accounts[8])?;
+
+        let market = &accounts[2];
+        require_keys_eq!(*market.owner, *program, ErrorCode::PoolAccountMismatch);
+        let data = market.try_borrow_data()?;
+        require_keys_eq!(
+            read_pubkey(&data, Self::BASE_VAULT_OFFSET)?,
+            accounts[6].key(),
+            ErrorCode::PoolAccountMismatch
+        );
+        require_keys_eq!(
+            read_pubkey(&data, Self::QUOTE_VAULT_OFFSET)?,
+            accounts[7].key(),
+            ErrorCode::PoolAccountMismatch
+        );
+        Ok(())
+    }
+
+    // base_account and quote_account; selling base when the input is the base mint
+    fn trader_token_accounts(
+        &self,
+        accounts: &[AccountInfo],
+        request: &SwapRequest,
+    ) -> Result<Option<(usize, usize)>> {
+        let data = accounts[2].try_borrow_data()?;
+        let is_ask = request.input_mint == read_pubkey(&data, Self::BASE_MINT_OFFSET)?;
+        Ok(Some(if is_ask { (4, 5) } else { (5, 4) }))
+    }
+
+    // discriminant(1) + OrderPacket::ImmediateOrCancel:
+    //   variant(1) + side(1) + price_in_ticks(None: 1) + num_base_lots(8)
+    //   + num_quote_lots(8) + min_base_lots_to_fill(8) + min_quote_lots_to_fill(8)
+    //   + self_trade_behavior(1) + match_limit(None: 1) + client_order_id(16)
+    //   + use_only_deposited_funds(1) + last_valid_slot(None: 1)
+    //   + last_valid_unix_timestamp_in_seconds(None: 1) = 57 bytes
+    // The side comes from the input mint. The input rounds down to whole lots
+    // and the minimum fill rounds up, so the order never overspends or
+    // underfills in atoms.
+    fn encode(&self, accounts: &[AccountInfo], request: &SwapRequest) -> Result<Vec<u8>> {
+        let data = accounts[2].try_borrow_data()?;
+        let base_lot_size = Self::read_u64(&data, Self::BASE_LOT_SIZE_OFFSET)?;
+        let quote_lot_size = Self::read_u64(&data, Self::QUOTE_LOT_SIZE_OFFSET)?;
+        require!(
+            base_lot_size > 0 && quote_lot_size > 0,
+            ErrorCode::PoolAccountMismatch
+        );
+
+        let is_ask = request.input_mint == read_pubkey(&data, Self::BASE_MINT_OFFSET)?;
+        if !is_ask {
+            require_keys_eq!(
+                request.input_mint,
+                read_pubkey(&data, Self::QUOTE_MINT_OFFSET)?,
+                ErrorCode::PoolAccountMismatch
+            );
+        }
+
+        let (input_lot_size, output_lot_size) = if is_ask {
+            (base_lot_size, quote_lot_size)
+        } else {
+            (quote_lot_size, base_lot_size)
+        };
+        let input_lots = request.in_amount / input_lot_size;
+        require!(input_lots > 0, ErrorCode::InvalidAmount);
+        let min_output_lots = request.out_amount.div_ceil(output_lot_size);
+        let (side, num_base_lots, num_quote_lots, min_base_lots, min_quote_lots) = if is_ask {
+            (Self::SIDE_ASK, input_lots, 0, 0, min_output_lots)
+        } else {
+            (Self::SIDE_BID, 0, input_lots, min_output_lots, 0)
+        };
+
+        let mut ix_data = Vec::with_capacity(57);
+        ix_data.push(0u8); // Swap instruction
+        ix_data.push(2u8); // OrderPacket::ImmediateOrCancel
+        ix_data.push(side);
+        ix_data.push(0); // price_in_ticks: None (market order)
+        ix_data.extend_from_slice(&num_base_lots.to_le_bytes());
+        ix_data.extend_from_slice(&num_quote_lots.to_le_bytes());
+        ix_data.extend_from_slice(&min_base_lots.to_le_bytes());
+        ix_data.extend_from_slice(&min_quote_lots.to_le_bytes());
+        ix_data.push(0); // self_trade_behavior: Abort
+        ix_data.push(0); // match_limit: None
+        ix_data.extend_from_slice(&0u128.to_le_bytes()); // client_order_id
+        ix_data.push(0); // use_only_deposited_funds: false
+        ix_data.push(0); // last_valid_slot: None
+        ix_data.push(0); // last_valid_unix_timestamp_in_seconds: None
+        Ok(ix_data)
+    }
+}
@@ -14,6 +14,36 @@ pub const LENDING_POOL_SEED: &[u8] = b"lending_pool";
 pub const LENDING_POSITION_SEED: &[u8] = b"lending_position";
 #[constant]
 pub const VAULT_SEED: &[u8] = b"vault";
+#[constant]
+pub const PERP_VAULT_SEED: &[u8] = b"perp_vault";
+#[constant]
+pub const PERP_VAULT_TOKEN_SEED: &[u8] = b"perp_vault_token";
+#[constant]
+pub const PERP_LP_POOL_SEED: &[u8] = b"perp_lp_pool";
+#[constant]
+pub const PERP_LP_MINT_SEED: &[u8] = b"perp_lp_mint";
+#[constant]
+pub const USER_MARKET_STATS_SEED: &[u8] = b"user_market_stats";
+#[constant]
+pub const PRICE_ACCUMULATOR_SEED: &[u8] = b"price_accumulator";
+#[constant]
+pub const REFERRER_SEED: &[u8] = b"referrer";
+#[constant]
+pub const SPOT_MARKET_SEED: &[u8] = b"spot_market";
+#[constant]
+pub const SPOT_VAULT_SEED: &[u8] = b"spot_vault";
+#[constant]
+pub const OPEN_ORDERS_SEED: &[u8] = b"open_orders";
+#[constant]
+pub const SWAP_POOL_SEED: &[u8] = b"swap_pool";
+#[constant]
+pub const SWAP_POOL_VAULT_SEED: &[u8] = b"swap_pool_vault";
+#[constant]
+pub const SWAP_POOL_LP_MINT_SEED: &[u8] = b"swap_pool_lp_mint";
+#[constant]
+pub const PROTOCOL_REGISTRY_SEED: &[u8] = b"protocol_registry";
+#[constant]
+pub const YIELD_POSITION_SEED: &[u8] = b"yield_position";
 
 pub const WAD: u128 = 1_000_000_000_000_000_000;
 
@@ -24,6 +54,8 @@ pub const BPS_DENOMINATOR: u64 = 10_000;
 pub const MAX_SWAP_FEE_BPS: u64 = 100; // 1%
 pub const MAX_PERP_FEE_BPS: u64 = 50; // 0.5%
 pub const MAX_LENDING_FEE_BPS: u64 = 200; // 2%
+pub const MAX_SPOT_FEE_BPS: u64 = 100; // 1%
+pub const MAX_POOL_FEE_BPS: u64 = 100; // 1%
 
 pub const MIN_LEVERAGE: u64 = 1;
 pub const MAX_LEVERAGE: u64 = 50;
@@ -49,9 +81,46 @@ pub const MAX_FUNDING_RATE: u128 = 10_000_000_000_000_000; // 1% per interval
 pub const MAX_ORACLE_STALENESS: u64 = 60; // 60 seconds
 pub const PYTH_PRICE_EXPO_ADJUSTMENT: i32 = -8; // Pyth typically uses exponent -8
 
+// Price accumulator (TWAP/EMA)
+pub const PRICE_OBSERVATION_CAPACITY: usize = 64;
+pub const MIN_EMA_HALF_LIFE: i64 = 10; // 10 seconds
+pub const MAX_EMA_HALF_LIFE: i64 = 7 * 24 * 3600; // 1 week
+pub const LN_2_WAD: u128 = 693_147_180_559_945_309; // ln(2) in WAD
+
+// Margin tiers
+pub const MAX_MARGIN_TIERS: usize = 5;
+
+// Referrals (in basis points)
+pub const MAX_REFERRAL_SHARE_BPS: u64 = 5_000; // 50% of the fee
+pub const MAX_REFEREE_DISCOUNT_BPS: u64 = 5_000; // 50% off the fee
+
+// Fee tiers
+pub const MAX_FEE_TIERS: usize = 6;
+pub const VOLUME_WINDOW_DAYS: usize = 30; // Rolling volume window for fee tiers
+pub const SECONDS_PER_DAY: i64 = 86_400;
+
+// Spot order book
+pub const ORDER_BOOK_CAPACITY: usize = 256; // Slab nodes per side (about half are orders)
+pub const EVENT_QUEUE_CAPACITY: usize = 128;
+pub const MAX_MATCHES_PER_ORDER: usize = 16; // Bounds compute per place_spot_order
+
+// Native swap pools
+pub const MINIMUM_POOL_LIQUIDITY: u64 = 1_000; // LP shares locked by the first deposit
+
+// Swap routing
+pub const MAX_ROUTE_LEGS: usize = 4;
+
+// Protocol registry
+pub const MAX_PROTOCOL_ENTRIES: usize = 16;
+pub const PROTOCOL_VOLUME_WINDOW: i64 = 86_400; // Volume caps reset daily
+
+// Delegated trading
+pub const MAX_DELEGATE_MARKETS: usize = 8;
+
 // Position limits
 pub const MAX_PERP_POSITIONS: u8 = 10;
 pub const MAX_LENDING_POSITIONS: u8 = 10;
+pub const MAX_SUB_ACCOUNTS: u16 = 16; // Numbered sub-accounts per wallet, besides the primary
 
 // Fund PDA seeds
 #[constant]
@@ -112,4 +112,168 @@ pub enum ErrorCode {
     FundWindingDown,
     #[msg("Insufficient fund liquidity for withdrawal")]
     InsufficientFundLiquidity,
+
+    // Price accumulator (6100-6109)
+    #[msg("Not enough price history for the requested TWAP window")]
+    TwapWindowUnavailable,
+    #[msg("EMA half-life out of range")]
+    InvalidEmaHalfLife,
+
+    // Vault (6110-6119)
+    #[msg("Bucket is not valid for this vault kind")]
+    InvalidVaultBucket,
+    #[msg("Insufficient balance in vault bucket")]
+    InsufficientBucketBalance,
+    #[msg("Vault buckets exceed token account balance")]
+    VaultInvariantViolated,
+
+    // Perp LP pool (6120-6129)
+    #[msg("Deposit would push pool above its max open interest utilization cap")]
+    PerpLpDepositCapExceeded,
+    #[msg("Remaining accounts must list every market in the pool with its oracle")]
+    PoolMarketsMismatch,
+    #[msg("Perp LP pool has no net asset value")]
+    PerpLpPoolInsolvent,
+
+    // Perp risk limits (6130-6139)
+    #[msg("Open interest notional limit exceeded")]
+    OpenInterestNotionalExceeded,
+    #[msg("Long/short skew limit exceeded")]
+    SkewLimitExceeded,
+    #[msg("Per-user notional limit exceeded for this market")]
+    UserNotionalLimitExceeded,
+
+    // Margin tiers (6140-6149)
+    #[msg("Margin tiers must ascend in notional with falling leverage and rising maintenance")]
+    InvalidMarginTiers,
+    #[msg("Position notional exceeds the market's largest margin tier")]
+    PositionExceedsMarginTiers,
+    #[msg("Removing margin would exceed the tier's max leverage")]
+    MarginRemovalExceedsLeverage,
+
+    // Contract types (6150-6159)
+    #[msg("Collateral mint does not match the market's contract type")]
+    InvalidCollateralMint,
+
+    // Circuit breaker (6160-6169)
+    #[msg("Circuit breaker tripped: market is reduce-only")]
+    MarketReduceOnly,
+    #[msg("Circuit breaker tripped: liquidations are in their grace period")]
+    LiquidationGracePeriod,
+    #[msg("Invalid circuit breaker configuration")]
+    InvalidCircuitBreaker,
+
+    // Fee tiers (6170-6179)
+    #[msg("Fee tiers must ascend in volume with non-decreasing discounts")]
+    InvalidFeeTiers,
+
+    // Referrals (6180-6189)
+    #[msg("Users cannot refer themselves")]
+    SelfReferral,
+    #[msg("Referrer must have an existing user account")]
+    InvalidReferrer,
+    #[msg("Referrer chain would form a cycle")]
+    ReferralCycle,
+    #[msg("No referral rewards available to claim")]
+    NoReferralRewards,
+    #[msg("Referral share or discount exceeds its maximum")]
+    InvalidReferralParams,
+
+    // Delegates (6190-6199)
+    #[msg("Invalid delegate configuration")]
+    InvalidDelegate,
+    #[msg("Delegate authorization has expired")]
+    DelegateExpired,
+    #[msg("Action is outside the delegate's scope")]
+    DelegateScopeExceeded,
+    #[msg("No delegate is set")]
+    NoDelegate,
+
+    // Sub-accounts (6200-6209)
+    #[msg("Sub-account ids must be assigned sequentially")]
+    InvalidSubAccountId,
+    #[msg("Maximum sub-accounts reached")]
+    MaxSubAccountsReached,
+    #[msg("Source and destination sub-accounts must differ")]
+    SameSubAccount,
+
+    // Spot order book (6210-6219)
+    #[msg("Order book side is full")]
+    OrderBookFull,
+    #[msg("Event queue is full; consume events first")]
+    EventQueueFull,
+    #[msg("Order not found")]
+    OrderNotFound,
+    #[msg("Invalid order price or size")]
+    InvalidOrder,
+    #[msg("Post-only order would cross the book")]
+    PostOnlyWouldCross,
+    #[msg("Maker OpenOrders account missing for the next event")]
+    MissingOpenOrders,
+
+    // Native swap pools (6220-6229)
+    #[msg("Pool reserves cannot cover this trade")]
+    InsufficientReserves,
+    #[msg("Initial deposit must exceed the minimum locked liquidity")]
+    InitialLiquidityTooLow,
+    #[msg("Token account does not belong to this pool")]
+    PoolAccountMismatch,
+
+    // Swap modes and routing (6230-6239)
+    #[msg("Protocol does not support this swap mode")]
+    UnsupportedSwapMode,
+    #[msg("Route legs do not match the supplied accounts")]
+    InvalidRoute,
+    #[msg("Split weights must be positive and sum to 10,000 bps")]
+    InvalidSplitWeights,
+    #[msg("Swap fill is worse than the oracle price band")]
+    OracleDeviationExceeded,
+    #[msg("Fee vault mint does not match the swap input mint")]
+    FeeVaultMintMismatch,
+    #[msg("Input and output mints must differ")]
+    IdenticalSwapMints,
+
+    // Protocol registry (6240-6249)
+    #[msg("Protocol is disabled in the registry")]
+    ProtocolDisabled,
+    #[msg("Protocol volume cap reached for this window")]
+    ProtocolVolumeCapExceeded,
+    #[msg("Protocol registry is full")]
+    ProtocolRegistryFull,
+    #[msg("Program id is already routed to another protocol")]
+    DuplicateProtocolProgram,
+
+    // Yield positions (6250-6259)
+    #[msg("Protocol does not match the routed protocol program")]
+    ProtocolAccountMismatch,
+
+    // Account migration (6260-6269)
+    #[msg("Account is not a program account of the expected type")]
+    InvalidMigrationAccount,
+    #[msg("Account already has the current layout")]
+    AccountAlreadyMigrated,
+
+    // Bound venue accounts (6270-6279)
+    #[msg("Swap venue accounts are not the trader's token accounts")]
+    TraderAccountMismatch,
+    #[msg("Deposit venue accounts are not the depositor's token accounts")]
+    DepositorAccountMismatch,
+
+    // Fund actions (6280-6289)
+    #[msg("Fund action is not supported")]
+    UnsupportedFundAction,
+
+    // Protocol volume caps (6290-6299)
+    #[msg("Input mint is not the mint this protocol's volume cap is counted in")]
+    ProtocolVolumeMintMismatch,
+    #[msg("Protocol registry must be writable to record capped volume")]
+    ProtocolRegistryReadOnly,
+
+    // Yield position receipts (6300-6309)
+    #[msg("Withdrawal burned more receipts than the position holds")]
+    ReceiptsExceedPosition,
+
+    // Exact-in fills (6310-6319)
+    #[msg("Swap venue did not spend the exact input amount")]
+    SwapInputMismatch,
 }
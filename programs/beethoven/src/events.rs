@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use crate::state::{ContractType, OrderSide, OrderType};
 
 // Swap events
 #[event]
@@ -9,16 +10,41 @@ pub struct SwapExecuted {
     pub amount_in: u64,
     pub amount_out: u64,
     pub fee: u64,
+    pub fee_tier: Option<u8>, // None below the lowest volume tier
     pub protocol: Pubkey,
     pub timestamp: i64,
 }
 
+/// One venue's share of a split swap.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct RouteLegFill {
+    pub protocol: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+}
+
+#[event]
+pub struct RouteExecuted {
+    pub user: Pubkey,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee: u64,
+    pub fee_tier: Option<u8>,
+    pub legs: Vec<RouteLegFill>,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct LiquidityAdded {
     pub user: Pubkey,
     pub mint: Pubkey,
-    pub amount: u64,
+    pub amount: u64,          // Underlying spent, measured by balance delta
     pub protocol: Pubkey,
+    pub position: Pubkey,
+    pub receipt_amount: u64,  // Receipt tokens minted; 0 on venues without one
+    pub principal: u64,       // Position principal after the deposit
     pub timestamp: i64,
 }
 
@@ -26,8 +52,120 @@ pub struct LiquidityAdded {
 pub struct LiquidityRemoved {
     pub user: Pubkey,
     pub mint: Pubkey,
-    pub amount: u64,
+    pub amount: u64,          // Underlying returned, measured by balance delta
     pub protocol: Pubkey,
+    pub position: Pubkey,
+    pub receipt_burned: u64,  // Receipt tokens redeemed; 0 on venues without one
+    pub cost_basis: u64,      // Principal closed out by this withdrawal
+    pub realized_yield: i64,  // amount - cost_basis
+    pub principal: u64,       // Position principal after the withdrawal
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SwapPoolCreated {
+    pub pool: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub lp_mint: Pubkey,
+    pub fee_bps: u64,
+    pub protocol_fee_share_bps: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PoolLiquidityAdded {
+    pub pool: Pubkey,
+    pub depositor: Pubkey,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub shares_minted: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PoolLiquidityRemoved {
+    pub pool: Pubkey,
+    pub withdrawer: Pubkey,
+    pub shares_burned: u64,
+    pub amount_a: u64,
+    pub amount_b: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SwapPoolFeesCollected {
+    pub pool: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ProtocolEntryUpdated {
+    pub adapter_id: Pubkey,
+    pub program_id: Pubkey,
+    pub enabled: bool,
+    pub volume_cap: u64,
+    pub volume_mint: Pubkey,
+    pub timestamp: i64,
+}
+
+// Spot events
+#[event]
+pub struct SpotMarketCreated {
+    pub market: Pubkey,
+    pub market_index: u16,
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub base_lot_size: u64,
+    pub tick_size: u64,
+    pub maker_fee_bps: u64,
+    pub taker_fee_bps: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SpotOrderPlaced {
+    pub owner: Pubkey,
+    pub market: Pubkey,
+    pub side: OrderSide,
+    pub order_type: OrderType,
+    pub price: u64,
+    pub base_lots: u64,
+    pub filled_base_lots: u64,
+    pub filled_quote: u64,
+    pub taker_fee: u64,
+    pub order_id: u128, // 0 when nothing rested on the book
+    pub client_order_id: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SpotOrderCancelled {
+    pub owner: Pubkey,
+    pub market: Pubkey,
+    pub side: OrderSide,
+    pub order_id: u128,
+    pub base_lots: u64,
+    pub client_order_id: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SpotFundsSettled {
+    pub owner: Pubkey,
+    pub market: Pubkey,
+    pub base_amount: u64,
+    pub quote_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SpotEventsConsumed {
+    pub market: Pubkey,
+    pub consumed: u16,
+    pub remaining: u64,
     pub timestamp: i64,
 }
 
@@ -41,6 +179,19 @@ pub struct PerpPositionOpened {
     pub collateral: u64,
     pub entry_price: u64,
     pub leverage: u64,
+    pub fee: u64,
+    pub fee_tier: Option<u8>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PerpMarginRemoved {
+    pub user: Pubkey,
+    pub market: Pubkey,
+    pub position: Pubkey,
+    pub amount: u64,
+    pub new_collateral: u64,
+    pub new_leverage: u64,
     pub timestamp: i64,
 }
 
@@ -53,6 +204,7 @@ pub struct PerpPositionClosed {
     pub exit_price: u64,
     pub pnl: i64,
     pub fee: u64,
+    pub fee_tier: Option<u8>,
     pub timestamp: i64,
 }
 
@@ -93,6 +245,16 @@ pub struct CollateralWithdrawn {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct LendingDepositTransferred {
+    pub owner: Pubkey,
+    pub pool: Pubkey,
+    pub from_user_account: Pubkey,
+    pub to_user_account: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct BorrowExecuted {
     pub user: Pubkey,
@@ -132,6 +294,8 @@ pub struct PerpMarketCreated {
     pub market: Pubkey,
     pub base_mint: Pubkey,
     pub quote_mint: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub contract_type: ContractType,
     pub timestamp: i64,
 }
 
@@ -142,6 +306,14 @@ pub struct LendingPoolCreated {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct PriceAccumulatorCreated {
+    pub accumulator: Pubkey,
+    pub oracle: Pubkey,
+    pub ema_half_life: i64,
+    pub timestamp: i64,
+}
+
 #[event]
 pub struct FeesCollected {
     pub vault: Pubkey,
@@ -150,6 +322,144 @@ pub struct FeesCollected {
     pub timestamp: i64,
 }
 
+#[event]
+pub struct PerpMarketLimitsUpdated {
+    pub market: Pubkey,
+    pub max_open_interest: u64,
+    pub max_oi_notional: u64,
+    pub max_skew_notional: u64,
+    pub max_user_notional: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MarginTiersUpdated {
+    pub market: Pubkey,
+    pub tier_count: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReferralParamsUpdated {
+    pub referral_share_bps: u64,
+    pub referee_discount_bps: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReferralRewardsClaimed {
+    pub referrer: Pubkey,
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SubAccountCreated {
+    pub owner: Pubkey,
+    pub user_account: Pubkey,
+    pub sub_account_id: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DelegateUpdated {
+    pub owner: Pubkey,
+    pub delegate: Pubkey,
+    pub expires_at: i64,
+    pub max_notional: u64,
+    pub allow_swaps: bool,
+    pub markets: Vec<u16>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DelegateRevoked {
+    pub owner: Pubkey,
+    pub delegate: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeeTiersUpdated {
+    pub tier_count: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CircuitBreakerUpdated {
+    pub market: Pubkey,
+    pub threshold_bps: u64,
+    pub window: i64,
+    pub cooldown: i64,
+    pub liquidation_grace: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct CircuitBreakerTripped {
+    pub market: Pubkey,
+    pub reference_price: u64,
+    pub price: u64,
+    pub move_bps: u64,
+    pub reduce_only_until: i64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PerpLpPoolCreated {
+    pub pool: Pubkey,
+    pub quote_mint: Pubkey,
+    pub lp_mint: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PerpLiquidityDeposited {
+    pub pool: Pubkey,
+    pub depositor: Pubkey,
+    pub amount: u64,
+    pub shares_minted: u64,
+    pub nav_per_share: u128,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PerpLiquidityWithdrawn {
+    pub pool: Pubkey,
+    pub withdrawer: Pubkey,
+    pub shares_burned: u64,
+    pub amount_returned: u64,
+    pub nav_per_share: u128,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct InsuranceFunded {
+    pub vault: Pubkey,
+    pub funder: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct AccountMigrated {
+    pub account: Pubkey,
+    pub old_len: u32,
+    pub new_len: u32,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VaultMigrated {
+    pub vault: Pubkey,
+    pub perp_vault: Pubkey,
+    pub lending_liquidity: u64,
+    pub perp_margin: u64,
+    pub timestamp: i64,
+}
+
 // Fund events
 #[event]
 pub struct FundInitialized {
@@ -2,7 +2,7 @@ use anchor_lang::prelude::*;
 use crate::constants::*;
 use crate::error::ErrorCode;
 use crate::events::FeesCollected;
-use crate::state::{Exchange, VaultState};
+use crate::state::{Exchange, VaultBucket, VaultState};
 
 use anchor_spl::token::{TokenAccount, Token};
 
@@ -20,7 +20,7 @@ pub struct CollectFees<'info> {
 
     #[account(
         mut,
-        seeds = [VAULT_SEED, vault_state.mint.as_ref()],
+        seeds = [vault_state.seed_prefix(), vault_state.mint.as_ref()],
         bump = vault_state.bump,
     )]
     pub vault_state: Account<'info, VaultState>,
@@ -47,15 +47,12 @@ pub fn handler(ctx: Context<CollectFees>, amount: u64) -> Result<()> {
         ErrorCode::InsufficientVaultBalance
     );
 
-    vault_state.collected_fees = vault_state
-        .collected_fees
-        .checked_sub(amount)
-        .ok_or(ErrorCode::MathUnderflow)?;
+    vault_state.debit(VaultBucket::Fees, amount)?;
 
     // Transfer fees from vault to recipient
     let mint_key = vault_state.mint;
     let seeds = &[
-        VAULT_SEED,
+        vault_state.seed_prefix(),
         mint_key.as_ref(),
         &[vault_state.bump],
     ];
@@ -74,6 +71,11 @@ pub fn handler(ctx: Context<CollectFees>, amount: u64) -> Result<()> {
         amount,
     )?;
 
+    ctx.accounts.vault_token_account.reload()?;
+    ctx.accounts
+        .vault_state
+        .assert_backed(ctx.accounts.vault_token_account.amount)?;
+
     let clock = Clock::get()?;
     emit!(FeesCollected {
         vault: ctx.accounts.vault_state.key(),
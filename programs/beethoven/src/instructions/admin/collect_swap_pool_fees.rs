@@ -0,0 +1,97 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::error::ErrorCode;
+use crate::events::SwapPoolFeesCollected;
+use crate::state::{SwapPool, VaultBucket, VaultState};
+
+use anchor_spl::token::{Token, TokenAccount};
+
+#[derive(Accounts)]
+pub struct CollectSwapPoolFees<'info> {
+    /// Anyone can sweep protocol fees into the vault (permissionless)
+    pub cranker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [SWAP_POOL_SEED, pool.mint_a.as_ref(), pool.mint_b.as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, SwapPool>>,
+
+    /// Either of the pool's vaults; fees are swept one mint at a time
+    #[account(
+        mut,
+        constraint = pool_vault.key() == pool.vault_a
+            || pool_vault.key() == pool.vault_b @ ErrorCode::PoolAccountMismatch,
+    )]
+    pub pool_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, pool_vault.mint.as_ref()],
+        bump = vault_state.bump,
+    )]
+    pub vault_state: Box<Account<'info, VaultState>>,
+
+    #[account(
+        mut,
+        constraint = vault_token_account.key() == vault_state.token_account @ ErrorCode::InvalidParameter,
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn handler(ctx: Context<CollectSwapPoolFees>) -> Result<()> {
+    let pool = &ctx.accounts.pool;
+    let is_a = ctx.accounts.pool_vault.key() == pool.vault_a;
+    let amount = if is_a {
+        pool.protocol_fees_a
+    } else {
+        pool.protocol_fees_b
+    };
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    let seeds = &[
+        SWAP_POOL_SEED,
+        pool.mint_a.as_ref(),
+        pool.mint_b.as_ref(),
+        &[pool.bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    anchor_spl::token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            anchor_spl::token::Transfer {
+                from: ctx.accounts.pool_vault.to_account_info(),
+                to: ctx.accounts.vault_token_account.to_account_info(),
+                authority: ctx.accounts.pool.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )?;
+
+    ctx.accounts.vault_state.credit(VaultBucket::Fees, amount)?;
+    ctx.accounts.vault_token_account.reload()?;
+    ctx.accounts
+        .vault_state
+        .assert_backed(ctx.accounts.vault_token_account.amount)?;
+
+    let pool = &mut ctx.accounts.pool;
+    if is_a {
+        pool.protocol_fees_a = 0;
+    } else {
+        pool.protocol_fees_b = 0;
+    }
+
+    emit!(SwapPoolFeesCollected {
+        pool: pool.key(),
+        mint: ctx.accounts.pool_vault.mint,
+        amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,111 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::error::ErrorCode;
+use crate::events::SpotEventsConsumed;
+use crate::state::{EventQueue, OpenOrders, SpotMarket};
+
+#[derive(Accounts)]
+pub struct ConsumeSpotEvents<'info> {
+    /// Anyone can crank the event queue (permissionless)
+    pub cranker: Signer<'info>,
+
+    #[account(
+        seeds = [SPOT_MARKET_SEED, &spot_market.market_index.to_le_bytes()],
+        bump = spot_market.bump,
+    )]
+    pub spot_market: Box<Account<'info, SpotMarket>>,
+
+    #[account(
+        mut,
+        address = spot_market.event_queue @ ErrorCode::InvalidParameter,
+    )]
+    pub event_queue: AccountLoader<'info, EventQueue>,
+}
+
+/// Settle up to `limit` maker fills, oldest first, into the makers'
+/// OpenOrders accounts.
+///
+/// remaining_accounts carries the (writable) OpenOrders account of every
+/// maker in the batch. Consumption stops at the first event whose maker is
+/// missing, so events are always settled in order.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ConsumeSpotEvents<'info>>,
+    limit: u16,
+) -> Result<()> {
+    require!(limit > 0, ErrorCode::InvalidParameter);
+
+    let market_key = ctx.accounts.spot_market.key();
+    let base_lot_size = ctx.accounts.spot_market.base_lot_size;
+    let mut queue = ctx.accounts.event_queue.load_mut()?;
+
+    let mut consumed: u16 = 0;
+    while consumed < limit {
+        let Some(event) = queue.peek().copied() else {
+            break;
+        };
+        let Some(info) = ctx
+            .remaining_accounts
+            .iter()
+            .find(|info| info.key() == event.maker)
+        else {
+            break;
+        };
+
+        let mut open_orders: Account<OpenOrders> = Account::try_from(info)?;
+        require!(
+            open_orders.market == market_key,
+            ErrorCode::MissingOpenOrders
+        );
+
+        let base = event
+            .base_lots
+            .checked_mul(base_lot_size)
+            .ok_or(ErrorCode::MathOverflow)?;
+        if event.maker_side == 0 {
+            // Maker bought base with locked quote
+            open_orders.quote_locked = open_orders
+                .quote_locked
+                .checked_sub(event.quote_released)
+                .ok_or(ErrorCode::MathUnderflow)?;
+            open_orders.base_free = open_orders
+                .base_free
+                .checked_add(base)
+                .ok_or(ErrorCode::MathOverflow)?;
+        } else {
+            // Maker sold locked base for quote, less the maker fee
+            open_orders.base_locked = open_orders
+                .base_locked
+                .checked_sub(base)
+                .ok_or(ErrorCode::MathUnderflow)?;
+            let proceeds = event
+                .quote_amount
+                .checked_sub(event.maker_fee)
+                .ok_or(ErrorCode::MathUnderflow)?;
+            open_orders.quote_free = open_orders
+                .quote_free
+                .checked_add(proceeds)
+                .ok_or(ErrorCode::MathOverflow)?;
+        }
+        if event.maker_out == 1 {
+            open_orders.order_count = open_orders.order_count.saturating_sub(1);
+        }
+        open_orders.exit(&crate::ID)?;
+
+        queue.pop();
+        consumed += 1;
+    }
+
+    require!(
+        consumed > 0 || queue.count == 0,
+        ErrorCode::MissingOpenOrders
+    );
+
+    emit!(SpotEventsConsumed {
+        market: market_key,
+        consumed,
+        remaining: queue.count,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -3,7 +3,7 @@ use anchor_spl::token::Mint as AnchorMint;
 use crate::constants::*;
 use crate::error::ErrorCode;
 use crate::events::LendingPoolCreated;
-use crate::state::{Exchange, LendingPool, VaultState};
+use crate::state::{Exchange, LendingPool, VaultKind, VaultState};
 
 use anchor_spl::token::{TokenAccount, Token};
 
@@ -113,7 +113,12 @@ pub fn handler(ctx: Context<CreateLendingPool>, params: CreateLendingPoolParams)
     vault_state.bump = ctx.bumps.vault_state;
     vault_state.collected_fees = 0;
     vault_state.insurance_balance = 0;
-    vault_state._reserved = [0u8; 64];
+    vault_state.kind = VaultKind::Lending;
+    vault_state.perp_margin_balance = 0;
+    vault_state.lending_liquidity_balance = 0;
+    vault_state.perp_liquidity_balance = 0;
+    vault_state.referral_balance = 0;
+    vault_state._reserved = [0u8; 31];
 
     let exchange = &mut ctx.accounts.exchange;
     exchange.total_lending_pools = exchange
@@ -0,0 +1,127 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::error::ErrorCode;
+use crate::events::PerpLpPoolCreated;
+use crate::state::{Exchange, PerpLpPool, VaultKind, VaultState};
+
+use anchor_spl::token::{Mint, TokenAccount, Token};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct CreatePerpLpPoolParams {
+    pub fee_share_bps: u64,
+    pub target_utilization_bps: u64,
+}
+
+#[derive(Accounts)]
+pub struct CreatePerpLpPool<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [EXCHANGE_SEED],
+        bump = exchange.bump,
+        constraint = exchange.admin == admin.key() @ ErrorCode::Unauthorized,
+    )]
+    pub exchange: Box<Account<'info, Exchange>>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = PerpLpPool::LEN,
+        seeds = [PERP_LP_POOL_SEED, quote_mint.key().as_ref()],
+        bump,
+    )]
+    pub lp_pool: Box<Account<'info, PerpLpPool>>,
+
+    /// The quote token mint (e.g., USDC)
+    pub quote_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [PERP_LP_MINT_SEED, quote_mint.key().as_ref()],
+        bump,
+        mint::decimals = quote_mint.decimals,
+        mint::authority = lp_pool,
+    )]
+    pub lp_mint: Box<Account<'info, Mint>>,
+
+    /// Perp collateral vault for the quote mint, kept apart from the lending pool vault
+    #[account(
+        init,
+        payer = admin,
+        space = VaultState::LEN,
+        seeds = [PERP_VAULT_SEED, quote_mint.key().as_ref()],
+        bump,
+    )]
+    pub vault_state: Box<Account<'info, VaultState>>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [PERP_VAULT_TOKEN_SEED, quote_mint.key().as_ref()],
+        bump,
+        token::mint = quote_mint,
+        token::authority = vault_state,
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn handler(ctx: Context<CreatePerpLpPool>, params: CreatePerpLpPoolParams) -> Result<()> {
+    require!(
+        params.fee_share_bps <= BPS_DENOMINATOR,
+        ErrorCode::FeeExceedsMaximum
+    );
+
+    // Capture keys before mutable borrows
+    let pool_key = ctx.accounts.lp_pool.key();
+    let exchange_key = ctx.accounts.exchange.key();
+    let quote_mint_key = ctx.accounts.quote_mint.key();
+    let lp_mint_key = ctx.accounts.lp_mint.key();
+    let vault_key = ctx.accounts.vault_state.key();
+    let vault_token_key = ctx.accounts.vault_token_account.key();
+    let now = Clock::get()?.unix_timestamp;
+
+    let pool = &mut ctx.accounts.lp_pool;
+    pool.exchange = exchange_key;
+    pool.bump = ctx.bumps.lp_pool;
+    pool.quote_mint = quote_mint_key;
+    pool.lp_mint = lp_mint_key;
+    pool.vault_state = vault_key;
+    pool.market_count = 0;
+    pool.total_shares = 0;
+    pool.nav_per_share = WAD;
+    pool.total_nav = 0;
+    pool.fee_share_bps = params.fee_share_bps;
+    pool.target_utilization_bps = params.target_utilization_bps;
+    pool.created_at = now;
+    pool.last_nav_update = now;
+    pool._reserved = [0u8; 64];
+
+    let vault_state = &mut ctx.accounts.vault_state;
+    vault_state.exchange = exchange_key;
+    vault_state.mint = quote_mint_key;
+    vault_state.token_account = vault_token_key;
+    vault_state.bump = ctx.bumps.vault_state;
+    vault_state.collected_fees = 0;
+    vault_state.insurance_balance = 0;
+    vault_state.kind = VaultKind::Perp;
+    vault_state.perp_margin_balance = 0;
+    vault_state.lending_liquidity_balance = 0;
+    vault_state.perp_liquidity_balance = 0;
+    vault_state.referral_balance = 0;
+    vault_state._reserved = [0u8; 31];
+
+    emit!(PerpLpPoolCreated {
+        pool: pool_key,
+        quote_mint: quote_mint_key,
+        lp_mint: lp_mint_key,
+        timestamp: now,
+    });
+
+    Ok(())
+}
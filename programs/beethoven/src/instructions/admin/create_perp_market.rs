@@ -2,9 +2,9 @@ use anchor_lang::prelude::*;
 use crate::constants::*;
 use crate::error::ErrorCode;
 use crate::events::PerpMarketCreated;
-use crate::state::{Exchange, PerpMarket};
+use crate::state::{CircuitBreaker, ContractType, Exchange, MarginTier, PerpLpPool, PerpMarket};
 
-use anchor_spl::token::{TokenAccount, Token, Mint};
+use anchor_spl::token::Mint;
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
 pub struct CreatePerpMarketParams {
@@ -12,6 +12,10 @@ pub struct CreatePerpMarketParams {
     pub max_leverage: u64,
     pub min_position_size: u64,
     pub max_open_interest: u64,
+    pub max_oi_notional: u64,
+    pub max_skew_notional: u64,
+    pub max_user_notional: u64,
+    pub contract_type: ContractType,
 }
 
 #[derive(Accounts)]
@@ -43,9 +47,25 @@ pub struct CreatePerpMarket<'info> {
     /// The quote token mint (e.g., USDC)
     pub quote_mint: Account<'info, Mint>,
 
+    /// Settlement mint: quote_mint for linear markets, base_mint for inverse
+    #[account(
+        constraint = collateral_mint.key()
+            == params.contract_type.collateral_mint(base_mint.key(), quote_mint.key())
+            @ ErrorCode::InvalidCollateralMint,
+    )]
+    pub collateral_mint: Account<'info, Mint>,
+
     /// CHECK: Pyth oracle price feed account, validated by CPI at runtime
     pub oracle: UncheckedAccount<'info>,
 
+    /// LP pool for the collateral mint; must exist before markets can settle in it
+    #[account(
+        mut,
+        seeds = [PERP_LP_POOL_SEED, collateral_mint.key().as_ref()],
+        bump = lp_pool.bump,
+    )]
+    pub lp_pool: Box<Account<'info, PerpLpPool>>,
+
     pub system_program: Program<'info, System>,
 }
 
@@ -60,6 +80,7 @@ pub fn handler(ctx: Context<CreatePerpMarket>, params: CreatePerpMarketParams) -
     let exchange_key = ctx.accounts.exchange.key();
     let base_mint_key = ctx.accounts.base_mint.key();
     let quote_mint_key = ctx.accounts.quote_mint.key();
+    let collateral_mint_key = ctx.accounts.collateral_mint.key();
     let oracle_key = ctx.accounts.oracle.key();
     let now = Clock::get()?.unix_timestamp;
 
@@ -80,7 +101,25 @@ pub fn handler(ctx: Context<CreatePerpMarket>, params: CreatePerpMarketParams) -
     market.cumulative_funding_short = 0;
     market.last_funding_update = now;
     market.paused = false;
-    market._reserved = [0u8; 128];
+    market.long_entry_notional = 0;
+    market.short_entry_notional = 0;
+    market.max_oi_notional = params.max_oi_notional;
+    market.max_skew_notional = params.max_skew_notional;
+    market.max_user_notional = params.max_user_notional;
+    market.margin_tier_count = 0;
+    market.margin_tiers = [MarginTier::default(); MAX_MARGIN_TIERS];
+    market.contract_type = params.contract_type;
+    market.collateral_mint = collateral_mint_key;
+    market.long_funding_basis = 0;
+    market.short_funding_basis = 0;
+    market.circuit_breaker = CircuitBreaker::default();
+    market._reserved = [0u8; 23];
+
+    let pool = &mut ctx.accounts.lp_pool;
+    pool.market_count = pool
+        .market_count
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflow)?;
 
     let exchange = &mut ctx.accounts.exchange;
     exchange.total_perp_markets = exchange
@@ -92,6 +131,8 @@ pub fn handler(ctx: Context<CreatePerpMarket>, params: CreatePerpMarketParams) -
         market: market_key,
         base_mint: base_mint_key,
         quote_mint: quote_mint_key,
+        collateral_mint: collateral_mint_key,
+        contract_type: params.contract_type,
         timestamp: now,
     });
 
@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::error::ErrorCode;
+use crate::events::PriceAccumulatorCreated;
+use crate::state::{Exchange, PriceAccumulator, PriceObservation};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct CreatePriceAccumulatorParams {
+    pub ema_half_life: i64,
+    pub min_observation_interval: i64,
+}
+
+#[derive(Accounts)]
+pub struct CreatePriceAccumulator<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [EXCHANGE_SEED],
+        bump = exchange.bump,
+        constraint = exchange.admin == admin.key() @ ErrorCode::Unauthorized,
+    )]
+    pub exchange: Account<'info, Exchange>,
+
+    /// One accumulator per oracle, so a PerpMarket and any spot asset
+    /// priced by the same feed share the same history.
+    #[account(
+        init,
+        payer = admin,
+        space = PriceAccumulator::LEN,
+        seeds = [PRICE_ACCUMULATOR_SEED, oracle.key().as_ref()],
+        bump,
+    )]
+    pub price_accumulator: Box<Account<'info, PriceAccumulator>>,
+
+    /// CHECK: Pyth oracle price feed, validated when observations are recorded
+    pub oracle: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(
+    ctx: Context<CreatePriceAccumulator>,
+    params: CreatePriceAccumulatorParams,
+) -> Result<()> {
+    require!(
+        params.ema_half_life >= MIN_EMA_HALF_LIFE && params.ema_half_life <= MAX_EMA_HALF_LIFE,
+        ErrorCode::InvalidEmaHalfLife
+    );
+    require!(
+        params.min_observation_interval >= 0,
+        ErrorCode::InvalidParameter
+    );
+
+    let accumulator_key = ctx.accounts.price_accumulator.key();
+    let exchange_key = ctx.accounts.exchange.key();
+    let oracle_key = ctx.accounts.oracle.key();
+    let now = Clock::get()?.unix_timestamp;
+
+    let accumulator = &mut ctx.accounts.price_accumulator;
+    accumulator.exchange = exchange_key;
+    accumulator.oracle = oracle_key;
+    accumulator.bump = ctx.bumps.price_accumulator;
+    accumulator.observation_index = 0;
+    accumulator.observation_count = 0;
+    accumulator.min_observation_interval = params.min_observation_interval;
+    accumulator.observations = [PriceObservation::default(); PRICE_OBSERVATION_CAPACITY];
+    accumulator.ema_half_life = params.ema_half_life;
+    accumulator.ema_price = 0;
+    accumulator.last_update = 0;
+    accumulator._reserved = [0u8; 64];
+
+    emit!(PriceAccumulatorCreated {
+        accumulator: accumulator_key,
+        oracle: oracle_key,
+        ema_half_life: params.ema_half_life,
+        timestamp: now,
+    });
+
+    Ok(())
+}
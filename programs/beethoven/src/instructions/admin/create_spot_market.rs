@@ -0,0 +1,155 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::error::ErrorCode;
+use crate::events::SpotMarketCreated;
+use crate::state::{EventQueue, Exchange, OrderBookSide, OrderSide, SpotMarket, VaultState};
+
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct CreateSpotMarketParams {
+    pub market_index: u16,
+    pub base_lot_size: u64,
+    pub tick_size: u64,
+    pub maker_fee_bps: u64,
+    pub taker_fee_bps: u64,
+}
+
+#[derive(Accounts)]
+#[instruction(params: CreateSpotMarketParams)]
+pub struct CreateSpotMarket<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [EXCHANGE_SEED],
+        bump = exchange.bump,
+        constraint = exchange.admin == admin.key() @ ErrorCode::Unauthorized,
+    )]
+    pub exchange: Box<Account<'info, Exchange>>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = SpotMarket::LEN,
+        seeds = [SPOT_MARKET_SEED, &params.market_index.to_le_bytes()],
+        bump,
+    )]
+    pub spot_market: Box<Account<'info, SpotMarket>>,
+
+    pub base_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        constraint = quote_mint.key() != base_mint.key() @ ErrorCode::InvalidParameter,
+    )]
+    pub quote_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [SPOT_VAULT_SEED, spot_market.key().as_ref(), base_mint.key().as_ref()],
+        bump,
+        token::mint = base_mint,
+        token::authority = spot_market,
+    )]
+    pub base_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [SPOT_VAULT_SEED, spot_market.key().as_ref(), quote_mint.key().as_ref()],
+        bump,
+        token::mint = quote_mint,
+        token::authority = spot_market,
+    )]
+    pub quote_vault: Box<Account<'info, TokenAccount>>,
+
+    /// Pre-allocated by the client (OrderBookSide::LEN bytes, owned by this program)
+    #[account(zero)]
+    pub bids: AccountLoader<'info, OrderBookSide>,
+
+    /// Pre-allocated by the client (OrderBookSide::LEN bytes, owned by this program)
+    #[account(
+        zero,
+        constraint = asks.key() != bids.key() @ ErrorCode::InvalidParameter,
+    )]
+    pub asks: AccountLoader<'info, OrderBookSide>,
+
+    /// Pre-allocated by the client (EventQueue::LEN bytes, owned by this program)
+    #[account(zero)]
+    pub event_queue: AccountLoader<'info, EventQueue>,
+
+    /// Quote mint's vault; trading fees are credited to its fee bucket
+    #[account(
+        seeds = [VAULT_SEED, quote_mint.key().as_ref()],
+        bump = fee_vault_state.bump,
+    )]
+    pub fee_vault_state: Box<Account<'info, VaultState>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn handler(ctx: Context<CreateSpotMarket>, params: CreateSpotMarketParams) -> Result<()> {
+    require!(
+        params.base_lot_size > 0 && params.tick_size > 0,
+        ErrorCode::InvalidParameter
+    );
+    require!(
+        params.maker_fee_bps <= MAX_SPOT_FEE_BPS && params.taker_fee_bps <= MAX_SPOT_FEE_BPS,
+        ErrorCode::FeeExceedsMaximum
+    );
+    require!(
+        ctx.accounts.event_queue.key() != ctx.accounts.bids.key()
+            && ctx.accounts.event_queue.key() != ctx.accounts.asks.key(),
+        ErrorCode::InvalidParameter
+    );
+
+    // Capture keys before mutable borrows
+    let market_key = ctx.accounts.spot_market.key();
+    let base_mint = ctx.accounts.base_mint.key();
+    let quote_mint = ctx.accounts.quote_mint.key();
+    let now = Clock::get()?.unix_timestamp;
+
+    ctx.accounts.bids.load_init()?.init(market_key, OrderSide::Bid);
+    ctx.accounts.asks.load_init()?.init(market_key, OrderSide::Ask);
+    ctx.accounts.event_queue.load_init()?.init(market_key);
+
+    let market = &mut ctx.accounts.spot_market;
+    market.exchange = ctx.accounts.exchange.key();
+    market.bump = ctx.bumps.spot_market;
+    market.market_index = params.market_index;
+    market.base_mint = base_mint;
+    market.quote_mint = quote_mint;
+    market.base_vault = ctx.accounts.base_vault.key();
+    market.quote_vault = ctx.accounts.quote_vault.key();
+    market.bids = ctx.accounts.bids.key();
+    market.asks = ctx.accounts.asks.key();
+    market.event_queue = ctx.accounts.event_queue.key();
+    market.base_lot_size = params.base_lot_size;
+    market.tick_size = params.tick_size;
+    market.maker_fee_bps = params.maker_fee_bps;
+    market.taker_fee_bps = params.taker_fee_bps;
+    market.seq_num = 0;
+    market.base_volume = 0;
+    market.quote_volume = 0;
+    market.fees_collected = 0;
+    market.paused = false;
+    market.created_at = now;
+    market._reserved = [0u8; 64];
+
+    emit!(SpotMarketCreated {
+        market: market_key,
+        market_index: params.market_index,
+        base_mint,
+        quote_mint,
+        base_lot_size: params.base_lot_size,
+        tick_size: params.tick_size,
+        maker_fee_bps: params.maker_fee_bps,
+        taker_fee_bps: params.taker_fee_bps,
+        timestamp: now,
+    });
+
+    Ok(())
+}
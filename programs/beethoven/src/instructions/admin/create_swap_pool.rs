@@ -0,0 +1,127 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::error::ErrorCode;
+use crate::events::SwapPoolCreated;
+use crate::state::{Exchange, SwapPool};
+
+use anchor_spl::token::{Mint, Token, TokenAccount};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct CreateSwapPoolParams {
+    pub fee_bps: u64,
+    pub protocol_fee_share_bps: u64,
+}
+
+#[derive(Accounts)]
+pub struct CreateSwapPool<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [EXCHANGE_SEED],
+        bump = exchange.bump,
+        constraint = exchange.admin == admin.key() @ ErrorCode::Unauthorized,
+    )]
+    pub exchange: Box<Account<'info, Exchange>>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = SwapPool::LEN,
+        seeds = [SWAP_POOL_SEED, mint_a.key().as_ref(), mint_b.key().as_ref()],
+        bump,
+    )]
+    pub pool: Box<Account<'info, SwapPool>>,
+
+    pub mint_a: Box<Account<'info, Mint>>,
+
+    /// Mints are ordered so each pair has a single pool
+    #[account(
+        constraint = mint_a.key() < mint_b.key() @ ErrorCode::InvalidParameter,
+    )]
+    pub mint_b: Box<Account<'info, Mint>>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [SWAP_POOL_VAULT_SEED, pool.key().as_ref(), mint_a.key().as_ref()],
+        bump,
+        token::mint = mint_a,
+        token::authority = pool,
+    )]
+    pub vault_a: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [SWAP_POOL_VAULT_SEED, pool.key().as_ref(), mint_b.key().as_ref()],
+        bump,
+        token::mint = mint_b,
+        token::authority = pool,
+    )]
+    pub vault_b: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = admin,
+        seeds = [SWAP_POOL_LP_MINT_SEED, pool.key().as_ref()],
+        bump,
+        mint::decimals = mint_a.decimals,
+        mint::authority = pool,
+    )]
+    pub lp_mint: Box<Account<'info, Mint>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn handler(ctx: Context<CreateSwapPool>, params: CreateSwapPoolParams) -> Result<()> {
+    require!(
+        params.fee_bps <= MAX_POOL_FEE_BPS,
+        ErrorCode::FeeExceedsMaximum
+    );
+    require!(
+        params.protocol_fee_share_bps <= BPS_DENOMINATOR,
+        ErrorCode::FeeExceedsMaximum
+    );
+
+    // Capture keys before mutable borrows
+    let pool_key = ctx.accounts.pool.key();
+    let mint_a = ctx.accounts.mint_a.key();
+    let mint_b = ctx.accounts.mint_b.key();
+    let lp_mint = ctx.accounts.lp_mint.key();
+    let now = Clock::get()?.unix_timestamp;
+
+    let pool = &mut ctx.accounts.pool;
+    pool.exchange = ctx.accounts.exchange.key();
+    pool.bump = ctx.bumps.pool;
+    pool.mint_a = mint_a;
+    pool.mint_b = mint_b;
+    pool.vault_a = ctx.accounts.vault_a.key();
+    pool.vault_b = ctx.accounts.vault_b.key();
+    pool.lp_mint = lp_mint;
+    pool.reserve_a = 0;
+    pool.reserve_b = 0;
+    pool.total_shares = 0;
+    pool.fee_bps = params.fee_bps;
+    pool.protocol_fee_share_bps = params.protocol_fee_share_bps;
+    pool.protocol_fees_a = 0;
+    pool.protocol_fees_b = 0;
+    pool.volume_a = 0;
+    pool.volume_b = 0;
+    pool.created_at = now;
+    pool._reserved = [0u8; 64];
+
+    emit!(SwapPoolCreated {
+        pool: pool_key,
+        mint_a,
+        mint_b,
+        lp_mint,
+        fee_bps: params.fee_bps,
+        protocol_fee_share_bps: params.protocol_fee_share_bps,
+        timestamp: now,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::error::ErrorCode;
+use crate::events::InsuranceFunded;
+use crate::state::{Exchange, VaultBucket, VaultState};
+
+use anchor_spl::token::{TokenAccount, Token};
+
+#[derive(Accounts)]
+pub struct FundInsurance<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [EXCHANGE_SEED],
+        bump = exchange.bump,
+        constraint = exchange.admin == admin.key() @ ErrorCode::Unauthorized,
+    )]
+    pub exchange: Account<'info, Exchange>,
+
+    #[account(
+        mut,
+        seeds = [vault_state.seed_prefix(), vault_state.mint.as_ref()],
+        bump = vault_state.bump,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        constraint = vault_token_account.key() == vault_state.token_account @ ErrorCode::InvalidParameter,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = funder_token_account.owner == admin.key() @ ErrorCode::Unauthorized,
+    )]
+    pub funder_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn handler(ctx: Context<FundInsurance>, amount: u64) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    anchor_spl::token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            anchor_spl::token::Transfer {
+                from: ctx.accounts.funder_token_account.to_account_info(),
+                to: ctx.accounts.vault_token_account.to_account_info(),
+                authority: ctx.accounts.admin.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    ctx.accounts.vault_state.credit(VaultBucket::Insurance, amount)?;
+    ctx.accounts.vault_token_account.reload()?;
+    ctx.accounts
+        .vault_state
+        .assert_backed(ctx.accounts.vault_token_account.amount)?;
+
+    let clock = Clock::get()?;
+    emit!(InsuranceFunded {
+        vault: ctx.accounts.vault_state.key(),
+        funder: ctx.accounts.admin.key(),
+        amount,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
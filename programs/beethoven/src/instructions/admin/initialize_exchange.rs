@@ -2,7 +2,7 @@ use anchor_lang::prelude::*;
 use crate::constants::*;
 use crate::error::ErrorCode;
 use crate::events::ExchangeInitialized;
-use crate::state::Exchange;
+use crate::state::{Exchange, FeeTier};
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
 pub struct InitializeExchangeParams {
@@ -69,7 +69,11 @@ pub fn handler(
     exchange.total_perp_markets = 0;
     exchange.total_lending_pools = 0;
     exchange.total_users = 0;
-    exchange._reserved = [0u8; 128];
+    exchange.fee_tier_count = 0;
+    exchange.fee_tiers = [FeeTier::default(); MAX_FEE_TIERS];
+    exchange.referral_share_bps = 0;
+    exchange.referee_discount_bps = 0;
+    exchange._reserved = [0u8; 15];
 
     let clock = Clock::get()?;
     emit!(ExchangeInitialized {
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::error::ErrorCode;
+use crate::state::{Exchange, ProtocolEntry, ProtocolRegistry};
+
+#[derive(Accounts)]
+pub struct InitializeProtocolRegistry<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [EXCHANGE_SEED],
+        bump = exchange.bump,
+        constraint = exchange.admin == admin.key() @ ErrorCode::Unauthorized,
+    )]
+    pub exchange: Account<'info, Exchange>,
+
+    #[account(
+        init,
+        payer = admin,
+        space = ProtocolRegistry::LEN,
+        seeds = [PROTOCOL_REGISTRY_SEED],
+        bump,
+    )]
+    pub protocol_registry: Box<Account<'info, ProtocolRegistry>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<InitializeProtocolRegistry>) -> Result<()> {
+    let registry = &mut ctx.accounts.protocol_registry;
+    registry.exchange = ctx.accounts.exchange.key();
+    registry.bump = ctx.bumps.protocol_registry;
+    registry.entries = [ProtocolEntry::default(); MAX_PROTOCOL_ENTRIES];
+    registry.entry_count = 0;
+    registry._reserved = [0u8; 32];
+
+    Ok(())
+}
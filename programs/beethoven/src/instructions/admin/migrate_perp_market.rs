@@ -0,0 +1,109 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use crate::constants::*;
+use crate::error::ErrorCode;
+use crate::events::AccountMigrated;
+use crate::math::liquidation::position_notional;
+use crate::math::oracle::get_price;
+use crate::state::{grow_account, Exchange, PerpLpPool, PerpMarket};
+
+/// Grow a perp market created before fields were appended after its
+/// `_reserved` bytes. Until then the market no longer deserializes, so
+/// trading on it is blocked until this runs.
+///
+/// Markets from before LP pools are also registered with the pool for their
+/// collateral mint, so the pool's NAV prices them.
+#[derive(Accounts)]
+pub struct MigratePerpMarket<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [EXCHANGE_SEED],
+        bump = exchange.bump,
+        constraint = exchange.admin == admin.key() @ ErrorCode::Unauthorized,
+    )]
+    pub exchange: Account<'info, Exchange>,
+
+    /// CHECK: Older layout that no longer deserializes; owner and
+    /// discriminator are checked in grow_account
+    #[account(mut)]
+    pub perp_market: UncheckedAccount<'info>,
+
+    /// LP pool for the market's collateral mint; checked in the handler once
+    /// the market deserializes
+    #[account(mut)]
+    pub lp_pool: Box<Account<'info, PerpLpPool>>,
+
+    /// CHECK: Market oracle, checked against the migrated market and parsed
+    /// by get_price
+    pub oracle: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<MigratePerpMarket>) -> Result<()> {
+    let account = ctx.accounts.perp_market.to_account_info();
+    let old_len = account.data_len();
+
+    grow_account(
+        &account,
+        PerpMarket::DISCRIMINATOR,
+        PerpMarket::LEN,
+        &ctx.accounts.admin.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
+
+    // Markets from before contract types read the carved-out bytes as a
+    // linear market with no collateral mint
+    let clock = Clock::get()?;
+    let mut market = PerpMarket::try_deserialize(&mut &account.try_borrow_data()?[..])?;
+    if market.collateral_mint == Pubkey::default() {
+        market.collateral_mint = market
+            .contract_type
+            .collateral_mint(market.base_mint, market.quote_mint);
+
+        // The market predates LP pools: count it in its pool so NAV prices it
+        let pool = &mut ctx.accounts.lp_pool;
+        require_keys_eq!(
+            pool.quote_mint,
+            market.collateral_mint,
+            ErrorCode::PoolMarketsMismatch
+        );
+        pool.market_count = pool
+            .market_count
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        // Its open interest was never tracked at entry, so value it at the
+        // current price and accrue funding from now on. PnL and funding from
+        // before the migration stay out of the pool's NAV.
+        require_keys_eq!(
+            ctx.accounts.oracle.key(),
+            market.oracle,
+            ErrorCode::OracleAccountMismatch
+        );
+        let oracle_price = get_price(&ctx.accounts.oracle.to_account_info(), &clock)?;
+        market.long_entry_notional =
+            position_notional(market.contract_type, market.long_open_interest, oracle_price.price)?;
+        market.short_entry_notional =
+            position_notional(market.contract_type, market.short_open_interest, oracle_price.price)?;
+        market.long_funding_basis = (market.long_open_interest as i128)
+            .checked_mul(market.cumulative_funding_long)
+            .ok_or(ErrorCode::MathOverflow)?;
+        market.short_funding_basis = (market.short_open_interest as i128)
+            .checked_mul(market.cumulative_funding_short)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        market.try_serialize(&mut &mut account.try_borrow_mut_data()?[..])?;
+    }
+
+    emit!(AccountMigrated {
+        account: account.key(),
+        old_len: old_len as u32,
+        new_len: PerpMarket::LEN as u32,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
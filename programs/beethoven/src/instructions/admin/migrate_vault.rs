@@ -0,0 +1,122 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::error::ErrorCode;
+use crate::events::VaultMigrated;
+use crate::state::{Exchange, VaultBucket, VaultKind, VaultState};
+
+use anchor_spl::token::{TokenAccount, Token};
+
+/// Seed the buckets of a vault created before bucketed accounting, and move
+/// the perp margin it still holds into the separate perp vault. Such vaults
+/// decode as lending vaults with only fees and insurance accounted, so
+/// `perp_margin` is the collateral of open positions, summed off-chain.
+#[derive(Accounts)]
+pub struct MigrateVault<'info> {
+    #[account(mut)]
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [EXCHANGE_SEED],
+        bump = exchange.bump,
+        constraint = exchange.admin == admin.key() @ ErrorCode::Unauthorized,
+    )]
+    pub exchange: Account<'info, Exchange>,
+
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, vault_state.mint.as_ref()],
+        bump = vault_state.bump,
+        constraint = vault_state.kind == VaultKind::Lending @ ErrorCode::InvalidMigrationAccount,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        constraint = vault_token_account.key() == vault_state.token_account @ ErrorCode::InvalidParameter,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [PERP_VAULT_SEED, vault_state.mint.as_ref()],
+        bump = perp_vault_state.bump,
+        constraint = perp_vault_state.kind == VaultKind::Perp @ ErrorCode::InvalidMigrationAccount,
+    )]
+    pub perp_vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        constraint = perp_vault_token_account.key() == perp_vault_state.token_account @ ErrorCode::InvalidParameter,
+    )]
+    pub perp_vault_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn handler(ctx: Context<MigrateVault>, perp_margin: u64) -> Result<()> {
+    let vault_state = &mut ctx.accounts.vault_state;
+
+    // Only the legacy fee and insurance buckets may be set
+    require!(
+        vault_state.perp_margin_balance == 0
+            && vault_state.lending_liquidity_balance == 0
+            && vault_state.perp_liquidity_balance == 0
+            && vault_state.referral_balance == 0,
+        ErrorCode::AccountAlreadyMigrated
+    );
+
+    // Everything not owed to fees, insurance or traders is lending liquidity
+    let lending_liquidity = ctx
+        .accounts
+        .vault_token_account
+        .amount
+        .checked_sub(vault_state.accounted_balance()?)
+        .and_then(|rest| rest.checked_sub(perp_margin))
+        .ok_or(ErrorCode::InsufficientVaultBalance)?;
+    vault_state.credit(VaultBucket::LendingLiquidity, lending_liquidity)?;
+
+    if perp_margin > 0 {
+        let mint_key = vault_state.mint;
+        let seeds = &[
+            vault_state.seed_prefix(),
+            mint_key.as_ref(),
+            &[vault_state.bump],
+        ];
+        let signer_seeds = &[&seeds[..]];
+
+        anchor_spl::token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                anchor_spl::token::Transfer {
+                    from: ctx.accounts.vault_token_account.to_account_info(),
+                    to: ctx.accounts.perp_vault_token_account.to_account_info(),
+                    authority: vault_state.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            perp_margin,
+        )?;
+        ctx.accounts
+            .perp_vault_state
+            .credit(VaultBucket::PerpMargin, perp_margin)?;
+    }
+
+    ctx.accounts.vault_token_account.reload()?;
+    ctx.accounts.perp_vault_token_account.reload()?;
+    ctx.accounts
+        .vault_state
+        .assert_backed(ctx.accounts.vault_token_account.amount)?;
+    ctx.accounts
+        .perp_vault_state
+        .assert_backed(ctx.accounts.perp_vault_token_account.amount)?;
+
+    emit!(VaultMigrated {
+        vault: ctx.accounts.vault_state.key(),
+        perp_vault: ctx.accounts.perp_vault_state.key(),
+        lending_liquidity,
+        perp_margin,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
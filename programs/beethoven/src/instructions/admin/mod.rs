@@ -1,11 +1,47 @@
 pub mod initialize_exchange;
 pub mod create_perp_market;
+pub mod create_perp_lp_pool;
+pub mod update_perp_market_limits;
+pub mod set_margin_tiers;
+pub mod set_circuit_breaker;
+pub mod set_fee_tiers;
+pub mod set_referral_params;
+pub mod initialize_protocol_registry;
+pub mod set_protocol_entry;
+pub mod create_spot_market;
+pub mod create_swap_pool;
 pub mod create_lending_pool;
 pub mod update_funding_rate;
+pub mod update_circuit_breaker;
+pub mod consume_spot_events;
 pub mod collect_fees;
+pub mod collect_swap_pool_fees;
+pub mod fund_insurance;
+pub mod create_price_accumulator;
+pub mod update_price_accumulator;
+pub mod migrate_perp_market;
+pub mod migrate_vault;
 
 pub use initialize_exchange::*;
 pub use create_perp_market::*;
+pub use create_perp_lp_pool::*;
+pub use update_perp_market_limits::*;
+pub use set_margin_tiers::*;
+pub use set_circuit_breaker::*;
+pub use set_fee_tiers::*;
+pub use set_referral_params::*;
+pub use initialize_protocol_registry::*;
+pub use set_protocol_entry::*;
+pub use create_spot_market::*;
+pub use create_swap_pool::*;
 pub use create_lending_pool::*;
 pub use update_funding_rate::*;
+pub use update_circuit_breaker::*;
+pub use consume_spot_events::*;
 pub use collect_fees::*;
+pub use collect_swap_pool_fees::*;
+pub use fund_insurance::*;
+pub use create_price_accumulator::*;
+pub use update_price_accumulator::*;
+pub use migrate_perp_market::*;
+pub use migrate_vault::*;
@@ -0,0 +1,67 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::error::ErrorCode;
+use crate::events::CircuitBreakerUpdated;
+use crate::state::{CircuitBreaker, Exchange, PerpMarket};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SetCircuitBreakerParams {
+    pub threshold_bps: u64, // 0 disables the breaker
+    pub window: i64,
+    pub cooldown: i64,
+    pub liquidation_grace: i64,
+}
+
+#[derive(Accounts)]
+pub struct SetCircuitBreaker<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [EXCHANGE_SEED],
+        bump = exchange.bump,
+        constraint = exchange.admin == admin.key() @ ErrorCode::Unauthorized,
+    )]
+    pub exchange: Account<'info, Exchange>,
+
+    #[account(
+        mut,
+        seeds = [PERP_MARKET_SEED, &perp_market.market_index.to_le_bytes()],
+        bump = perp_market.bump,
+    )]
+    pub perp_market: Box<Account<'info, PerpMarket>>,
+}
+
+pub fn handler(ctx: Context<SetCircuitBreaker>, params: SetCircuitBreakerParams) -> Result<()> {
+    if params.threshold_bps > 0 {
+        require!(
+            params.threshold_bps <= BPS_DENOMINATOR
+                && params.window > 0
+                && params.cooldown > 0
+                && params.liquidation_grace >= 0
+                && params.liquidation_grace <= params.cooldown,
+            ErrorCode::InvalidCircuitBreaker
+        );
+    }
+
+    let market_key = ctx.accounts.perp_market.key();
+
+    // Reconfiguring clears any active trip and starts a fresh window
+    ctx.accounts.perp_market.circuit_breaker = CircuitBreaker {
+        threshold_bps: params.threshold_bps,
+        window: params.window,
+        cooldown: params.cooldown,
+        liquidation_grace: params.liquidation_grace,
+        ..CircuitBreaker::default()
+    };
+
+    emit!(CircuitBreakerUpdated {
+        market: market_key,
+        threshold_bps: params.threshold_bps,
+        window: params.window,
+        cooldown: params.cooldown,
+        liquidation_grace: params.liquidation_grace,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::error::ErrorCode;
+use crate::events::FeeTiersUpdated;
+use crate::state::{Exchange, FeeTier};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SetFeeTiersParams {
+    pub tiers: Vec<FeeTier>, // Ascending by min_volume; empty removes all discounts
+}
+
+#[derive(Accounts)]
+pub struct SetFeeTiers<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [EXCHANGE_SEED],
+        bump = exchange.bump,
+        constraint = exchange.admin == admin.key() @ ErrorCode::Unauthorized,
+    )]
+    pub exchange: Box<Account<'info, Exchange>>,
+}
+
+pub fn handler(ctx: Context<SetFeeTiers>, params: SetFeeTiersParams) -> Result<()> {
+    require!(
+        params.tiers.len() <= MAX_FEE_TIERS,
+        ErrorCode::InvalidFeeTiers
+    );
+
+    let mut prev: Option<&FeeTier> = None;
+    for tier in params.tiers.iter() {
+        require!(
+            tier.discount_bps <= BPS_DENOMINATOR,
+            ErrorCode::InvalidFeeTiers
+        );
+
+        // More volume: strictly higher threshold, no smaller discount
+        if let Some(prev) = prev {
+            require!(
+                tier.min_volume > prev.min_volume && tier.discount_bps >= prev.discount_bps,
+                ErrorCode::InvalidFeeTiers
+            );
+        }
+        prev = Some(tier);
+    }
+
+    let exchange = &mut ctx.accounts.exchange;
+    exchange.fee_tiers = [FeeTier::default(); MAX_FEE_TIERS];
+    exchange.fee_tiers[..params.tiers.len()].copy_from_slice(&params.tiers);
+    exchange.fee_tier_count = params.tiers.len() as u8;
+
+    emit!(FeeTiersUpdated {
+        tier_count: exchange.fee_tier_count,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::error::ErrorCode;
+use crate::events::MarginTiersUpdated;
+use crate::state::{Exchange, MarginTier, PerpMarket};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SetMarginTiersParams {
+    pub tiers: Vec<MarginTier>, // Ascending by max_notional; empty resets to flat margin
+}
+
+#[derive(Accounts)]
+pub struct SetMarginTiers<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [EXCHANGE_SEED],
+        bump = exchange.bump,
+        constraint = exchange.admin == admin.key() @ ErrorCode::Unauthorized,
+    )]
+    pub exchange: Account<'info, Exchange>,
+
+    #[account(
+        mut,
+        seeds = [PERP_MARKET_SEED, &perp_market.market_index.to_le_bytes()],
+        bump = perp_market.bump,
+    )]
+    pub perp_market: Box<Account<'info, PerpMarket>>,
+}
+
+pub fn handler(ctx: Context<SetMarginTiers>, params: SetMarginTiersParams) -> Result<()> {
+    require!(
+        params.tiers.len() <= MAX_MARGIN_TIERS,
+        ErrorCode::InvalidMarginTiers
+    );
+
+    let market_key = ctx.accounts.perp_market.key();
+    let market = &mut ctx.accounts.perp_market;
+
+    let mut prev: Option<&MarginTier> = None;
+    for tier in params.tiers.iter() {
+        require!(
+            tier.max_leverage >= MIN_LEVERAGE && tier.max_leverage <= market.max_leverage,
+            ErrorCode::InvalidMarginTiers
+        );
+        require!(tier.maintenance_margin_bps > 0, ErrorCode::InvalidMarginTiers);
+
+        // Maintenance must sit below initial margin, or a max-leverage
+        // position would be liquidatable the moment it opens
+        let initial_margin_bps = BPS_DENOMINATOR / tier.max_leverage;
+        require!(
+            tier.maintenance_margin_bps < initial_margin_bps,
+            ErrorCode::InvalidMarginTiers
+        );
+
+        // Larger notional: strictly higher bound, no more leverage, no less maintenance
+        if let Some(prev) = prev {
+            require!(
+                tier.max_notional > prev.max_notional
+                    && tier.max_leverage <= prev.max_leverage
+                    && tier.maintenance_margin_bps >= prev.maintenance_margin_bps,
+                ErrorCode::InvalidMarginTiers
+            );
+        }
+        prev = Some(tier);
+    }
+
+    // Tiers only gate new exposure and liquidations; open positions keep
+    // their stored liquidation price until they next change
+    market.margin_tiers = [MarginTier::default(); MAX_MARGIN_TIERS];
+    market.margin_tiers[..params.tiers.len()].copy_from_slice(&params.tiers);
+    market.margin_tier_count = params.tiers.len() as u8;
+
+    emit!(MarginTiersUpdated {
+        market: market_key,
+        tier_count: market.margin_tier_count,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+use crate::adapters::registry::is_compiled_adapter;
+use crate::constants::*;
+use crate::error::ErrorCode;
+use crate::events::ProtocolEntryUpdated;
+use crate::state::{Exchange, ProtocolEntry, ProtocolRegistry};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SetProtocolEntryParams {
+    pub adapter_id: Pubkey, // Compiled program id of the swap or deposit adapter
+    pub program_id: Pubkey, // Program id to route to it on this cluster
+    pub enabled: bool,
+    pub volume_cap: u64,    // Max input atoms of volume_mint per window; 0 = uncapped
+    pub volume_mint: Pubkey, // Mint the cap is counted in
+}
+
+#[derive(Accounts)]
+pub struct SetProtocolEntry<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [EXCHANGE_SEED],
+        bump = exchange.bump,
+        constraint = exchange.admin == admin.key() @ ErrorCode::Unauthorized,
+    )]
+    pub exchange: Account<'info, Exchange>,
+
+    #[account(
+        mut,
+        seeds = [PROTOCOL_REGISTRY_SEED],
+        bump = protocol_registry.bump,
+    )]
+    pub protocol_registry: Box<Account<'info, ProtocolRegistry>>,
+}
+
+pub fn handler(ctx: Context<SetProtocolEntry>, params: SetProtocolEntryParams) -> Result<()> {
+    require!(
+        is_compiled_adapter(&params.adapter_id),
+        ErrorCode::UnsupportedProtocol
+    );
+
+    // A program id may only route to one adapter, and may not shadow
+    // another adapter's compiled id
+    let registry = &mut ctx.accounts.protocol_registry;
+    require!(
+        params.program_id == params.adapter_id || !is_compiled_adapter(&params.program_id),
+        ErrorCode::DuplicateProtocolProgram
+    );
+    require!(
+        registry
+            .entries()
+            .iter()
+            .all(|entry| entry.adapter_id == params.adapter_id || entry.program_id != params.program_id),
+        ErrorCode::DuplicateProtocolProgram
+    );
+
+    let index = match registry.entry_index(&params.adapter_id) {
+        Some(index) => index,
+        None => {
+            require!(
+                (registry.entry_count as usize) < MAX_PROTOCOL_ENTRIES,
+                ErrorCode::ProtocolRegistryFull
+            );
+            registry.entry_count += 1;
+            registry.entry_count as usize - 1
+        }
+    };
+
+    // A cap is counted in one mint; volume counted in another mint is reset
+    require!(
+        params.volume_cap == 0 || params.volume_mint != Pubkey::default(),
+        ErrorCode::InvalidParameter
+    );
+    let clock = Clock::get()?;
+    let entry = &mut registry.entries[index];
+    let window_volume = if entry.volume_mint == params.volume_mint {
+        entry.window_volume
+    } else {
+        0
+    };
+    *entry = ProtocolEntry {
+        adapter_id: params.adapter_id,
+        program_id: params.program_id,
+        enabled: params.enabled,
+        volume_cap: params.volume_cap,
+        volume_mint: params.volume_mint,
+        window_volume,
+        window_start: entry.window_start,
+    };
+
+    emit!(ProtocolEntryUpdated {
+        adapter_id: params.adapter_id,
+        program_id: params.program_id,
+        enabled: params.enabled,
+        volume_cap: params.volume_cap,
+        volume_mint: params.volume_mint,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::error::ErrorCode;
+use crate::events::ReferralParamsUpdated;
+use crate::state::Exchange;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SetReferralParamsParams {
+    pub referral_share_bps: u64,
+    pub referee_discount_bps: u64,
+}
+
+#[derive(Accounts)]
+pub struct SetReferralParams<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [EXCHANGE_SEED],
+        bump = exchange.bump,
+        constraint = exchange.admin == admin.key() @ ErrorCode::Unauthorized,
+    )]
+    pub exchange: Box<Account<'info, Exchange>>,
+}
+
+pub fn handler(ctx: Context<SetReferralParams>, params: SetReferralParamsParams) -> Result<()> {
+    require!(
+        params.referral_share_bps <= MAX_REFERRAL_SHARE_BPS
+            && params.referee_discount_bps <= MAX_REFEREE_DISCOUNT_BPS,
+        ErrorCode::InvalidReferralParams
+    );
+
+    let exchange = &mut ctx.accounts.exchange;
+    exchange.referral_share_bps = params.referral_share_bps;
+    exchange.referee_discount_bps = params.referee_discount_bps;
+
+    emit!(ReferralParamsUpdated {
+        referral_share_bps: params.referral_share_bps,
+        referee_discount_bps: params.referee_discount_bps,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::error::ErrorCode;
+use crate::math::oracle::get_price;
+use crate::state::PerpMarket;
+
+#[derive(Accounts)]
+pub struct UpdateCircuitBreaker<'info> {
+    /// Anyone can crank the circuit breaker (permissionless)
+    pub cranker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PERP_MARKET_SEED, &perp_market.market_index.to_le_bytes()],
+        bump = perp_market.bump,
+    )]
+    pub perp_market: Box<Account<'info, PerpMarket>>,
+
+    /// CHECK: Pyth oracle price feed
+    #[account(
+        constraint = oracle.key() == perp_market.oracle @ ErrorCode::OracleAccountMismatch,
+    )]
+    pub oracle: UncheckedAccount<'info>,
+}
+
+/// Refresh the breaker without trading, so a trip and an expired cooldown
+/// are recorded even when no position is touched.
+pub fn handler(ctx: Context<UpdateCircuitBreaker>) -> Result<()> {
+    let clock = Clock::get()?;
+    let oracle_price = get_price(&ctx.accounts.oracle.to_account_info(), &clock)?;
+
+    let market_key = ctx.accounts.perp_market.key();
+    ctx.accounts.perp_market.refresh_circuit_breaker(
+        market_key,
+        oracle_price.price,
+        clock.unix_timestamp,
+    )?;
+
+    Ok(())
+}
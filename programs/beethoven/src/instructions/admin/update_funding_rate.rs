@@ -3,7 +3,8 @@ use crate::constants::*;
 use crate::error::ErrorCode;
 use crate::events::FundingRateUpdated;
 use crate::math::funding::calculate_funding_rate;
-use crate::state::PerpMarket;
+use crate::math::twap::observe_oracle_price;
+use crate::state::{PerpMarket, PriceAccumulator};
 
 #[derive(Accounts)]
 pub struct UpdateFundingRate<'info> {
@@ -16,6 +17,20 @@ pub struct UpdateFundingRate<'info> {
         bump = perp_market.bump,
     )]
     pub perp_market: Account<'info, PerpMarket>,
+
+    /// CHECK: Pyth oracle price feed
+    #[account(
+        constraint = oracle.key() == perp_market.oracle @ ErrorCode::OracleAccountMismatch,
+    )]
+    pub oracle: UncheckedAccount<'info>,
+
+    /// TWAP/EMA accumulator for the market oracle, observed on every crank
+    #[account(
+        mut,
+        seeds = [PRICE_ACCUMULATOR_SEED, perp_market.oracle.as_ref()],
+        bump = price_accumulator.bump,
+    )]
+    pub price_accumulator: Box<Account<'info, PriceAccumulator>>,
 }
 
 pub fn handler(ctx: Context<UpdateFundingRate>) -> Result<()> {
@@ -34,6 +49,12 @@ pub fn handler(ctx: Context<UpdateFundingRate>) -> Result<()> {
         ErrorCode::FundingIntervalNotElapsed
     );
 
+    observe_oracle_price(
+        &mut ctx.accounts.price_accumulator,
+        &ctx.accounts.oracle.to_account_info(),
+        &clock,
+    )?;
+
     // Calculate new funding rate
     let new_rate = calculate_funding_rate(market)?;
     market.funding_rate = new_rate;
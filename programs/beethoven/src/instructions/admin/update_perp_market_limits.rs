@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::error::ErrorCode;
+use crate::events::PerpMarketLimitsUpdated;
+use crate::state::{Exchange, PerpMarket};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct UpdatePerpMarketLimitsParams {
+    pub max_open_interest: u64,
+    pub max_oi_notional: u64,
+    pub max_skew_notional: u64,
+    pub max_user_notional: u64,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePerpMarketLimits<'info> {
+    pub admin: Signer<'info>,
+
+    #[account(
+        seeds = [EXCHANGE_SEED],
+        bump = exchange.bump,
+        constraint = exchange.admin == admin.key() @ ErrorCode::Unauthorized,
+    )]
+    pub exchange: Account<'info, Exchange>,
+
+    #[account(
+        mut,
+        seeds = [PERP_MARKET_SEED, &perp_market.market_index.to_le_bytes()],
+        bump = perp_market.bump,
+    )]
+    pub perp_market: Account<'info, PerpMarket>,
+}
+
+pub fn handler(
+    ctx: Context<UpdatePerpMarketLimits>,
+    params: UpdatePerpMarketLimitsParams,
+) -> Result<()> {
+    let market_key = ctx.accounts.perp_market.key();
+    let market = &mut ctx.accounts.perp_market;
+
+    // Lowering a limit never touches open positions; it only blocks new exposure
+    market.max_open_interest = params.max_open_interest;
+    market.max_oi_notional = params.max_oi_notional;
+    market.max_skew_notional = params.max_skew_notional;
+    market.max_user_notional = params.max_user_notional;
+
+    emit!(PerpMarketLimitsUpdated {
+        market: market_key,
+        max_open_interest: params.max_open_interest,
+        max_oi_notional: params.max_oi_notional,
+        max_skew_notional: params.max_skew_notional,
+        max_user_notional: params.max_user_notional,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
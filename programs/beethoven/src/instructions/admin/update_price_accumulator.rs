@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::error::ErrorCode;
+use crate::math::twap::observe_oracle_price;
+use crate::state::PriceAccumulator;
+
+#[derive(Accounts)]
+pub struct UpdatePriceAccumulator<'info> {
+    /// Anyone can crank the accumulator (permissionless)
+    pub cranker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PRICE_ACCUMULATOR_SEED, price_accumulator.oracle.as_ref()],
+        bump = price_accumulator.bump,
+    )]
+    pub price_accumulator: Box<Account<'info, PriceAccumulator>>,
+
+    /// CHECK: Pyth oracle price feed
+    #[account(
+        constraint = oracle.key() == price_accumulator.oracle @ ErrorCode::OracleAccountMismatch,
+    )]
+    pub oracle: UncheckedAccount<'info>,
+}
+
+pub fn handler(ctx: Context<UpdatePriceAccumulator>) -> Result<()> {
+    let clock = Clock::get()?;
+    observe_oracle_price(
+        &mut ctx.accounts.price_accumulator,
+        &ctx.accounts.oracle.to_account_info(),
+        &clock,
+    )?;
+    Ok(())
+}
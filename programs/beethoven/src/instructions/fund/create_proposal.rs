@@ -52,6 +52,12 @@ pub struct CreateProposal<'info> {
 }
 
 pub fn handler(ctx: Context<CreateProposal>, params: CreateProposalParams) -> Result<()> {
+    // Fund borrowing has no execution path, so it can't be put to a vote
+    require!(
+        !matches!(params.action_type, ActionType::Borrow | ActionType::Repay),
+        ErrorCode::UnsupportedFundAction
+    );
+
     let clock = Clock::get()?;
     let fund = &ctx.accounts.fund;
     let proposal_index = fund.total_proposals;
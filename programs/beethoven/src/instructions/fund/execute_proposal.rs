@@ -3,8 +3,9 @@ use anchor_spl::token::{Token, TokenAccount};
 use crate::constants::*;
 use crate::error::ErrorCode;
 use crate::events::ProposalExecuted;
-use crate::state::{Exchange, Fund, Proposal, ProposalStatus, ActionType};
+use crate::state::{Exchange, Fund, Proposal, ProposalStatus, RegistryAccount, ActionType};
 use crate::state::proposal::{SwapActionData, PerpActionData, LendingActionData};
+use crate::adapters::deposit_adapter::Depositor;
 use super::fund_actions;
 
 #[derive(Accounts)]
@@ -47,6 +48,12 @@ pub struct ExecuteProposal<'info> {
     )]
     pub fund_vault: Account<'info, TokenAccount>,
 
+    /// CHECK: Runtime protocol allowlist and volume caps, loaded by
+    /// `RegistryAccount`; may be uninitialized, and is only written when
+    /// passed writable for a capped venue
+    #[account(seeds = [PROTOCOL_REGISTRY_SEED], bump)]
+    pub protocol_registry: UncheckedAccount<'info>,
+
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
     // remaining_accounts: action-specific accounts
@@ -67,6 +74,11 @@ pub fn handler<'info>(
     let fund_bump = ctx.accounts.fund.bump;
     let fund_seeds: &[&[u8]] = &[FUND_SEED, &[fund_bump]];
     let fund_key = ctx.accounts.fund.key();
+    let fund_depositor = Depositor {
+        token_account: ctx.accounts.fund_vault.key(),
+        authority: fund_key,
+    };
+    let mut registry = RegistryAccount::load(ctx.accounts.protocol_registry.to_account_info())?;
 
     // Dispatch based on action type
     match proposal.action_type {
@@ -74,6 +86,7 @@ pub fn handler<'info>(
             let action_data = SwapActionData::try_from_slice(&proposal.action_data)
                 .map_err(|_| ErrorCode::InvalidActionData)?;
             fund_actions::execute_fund_swap(
+                &mut registry,
                 &action_data,
                 ctx.remaining_accounts,
                 fund_seeds,
@@ -101,8 +114,11 @@ pub fn handler<'info>(
             let action_data = LendingActionData::try_from_slice(&proposal.action_data)
                 .map_err(|_| ErrorCode::InvalidActionData)?;
             fund_actions::execute_fund_deposit_lending(
+                &mut registry,
                 &action_data,
                 ctx.remaining_accounts,
+                &ctx.accounts.fund_vault.mint,
+                fund_depositor,
                 fund_seeds,
             )?;
         }
@@ -110,28 +126,17 @@ pub fn handler<'info>(
             let action_data = LendingActionData::try_from_slice(&proposal.action_data)
                 .map_err(|_| ErrorCode::InvalidActionData)?;
             fund_actions::execute_fund_withdraw_lending(
+                &registry,
                 &action_data,
                 ctx.remaining_accounts,
+                fund_depositor,
                 fund_seeds,
             )?;
         }
-        ActionType::Borrow => {
-            let action_data = LendingActionData::try_from_slice(&proposal.action_data)
-                .map_err(|_| ErrorCode::InvalidActionData)?;
-            fund_actions::execute_fund_deposit_lending(
-                &action_data,
-                ctx.remaining_accounts,
-                fund_seeds,
-            )?;
-        }
-        ActionType::Repay => {
-            let action_data = LendingActionData::try_from_slice(&proposal.action_data)
-                .map_err(|_| ErrorCode::InvalidActionData)?;
-            fund_actions::execute_fund_withdraw_lending(
-                &action_data,
-                ctx.remaining_accounts,
-                fund_seeds,
-            )?;
+        ActionType::Borrow | ActionType::Repay => {
+            // No fund-side borrow or repay path exists; the lending venues
+            // only take deposits and withdrawals
+            return Err(ErrorCode::UnsupportedFundAction.into());
         }
         ActionType::UpdateParam => {
             // Parameter updates handled separately by admin
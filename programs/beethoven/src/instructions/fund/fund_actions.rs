@@ -1,110 +1,35 @@
 use anchor_lang::prelude::*;
-use crate::adapters::account_bridge::invoke_protocol_cpi_signed;
-use crate::adapters::protocol_ids::{detect_swap_protocol, SwapProtocol};
+use crate::adapters::adapter::SwapRequest;
+use crate::adapters::deposit_adapter::{self, Depositor};
 use crate::error::ErrorCode;
+use crate::adapters::swap_adapter::{self, SwapMode};
+use crate::state::RegistryAccount;
 use crate::state::proposal::{SwapActionData, PerpActionData, LendingActionData};
 
-/// Execute a fund swap using the Beethoven protocol routing pattern.
-/// Uses `invoke_protocol_cpi_signed` with Fund PDA signer seeds.
+/// Execute a fund swap using the Beethoven protocol routing pattern,
+/// with the Fund PDA signing as trader.
 ///
 /// remaining_accounts follows the same pattern as regular swaps:
 ///   [0] = protocol program, [1..] = protocol-specific accounts
 pub fn execute_fund_swap<'info>(
+    registry: &mut RegistryAccount<'info>,
     action_data: &SwapActionData,
-    remaining_accounts: &[AccountInfo<'info>],
+    remaining_accounts: &'info [AccountInfo<'info>],
     fund_seeds: &[&[u8]],
 ) -> Result<()> {
-    require!(
-        !remaining_accounts.is_empty(),
-        ErrorCode::UnsupportedProtocol
-    );
-
-    let protocol_program = &remaining_accounts[0];
-    let protocol = detect_swap_protocol(protocol_program.key);
-
-    require!(
-        protocol != SwapProtocol::Unknown,
-        ErrorCode::UnsupportedProtocol
-    );
-
-    let accounts = &remaining_accounts[1..];
-    let signer_seeds = &[fund_seeds];
-
-    // Build protocol-specific instruction data (same encoding as swap_adapter)
-    // Then invoke with Fund PDA as signer
-    #[allow(unused_variables)]
-    let amount_in = action_data.amount_in;
-    #[allow(unused_variables)]
-    let minimum_amount_out = action_data.minimum_amount_out;
-
-    match protocol {
-        #[cfg(feature = "manifest-swap")]
-        SwapProtocol::Manifest => {
-            require!(accounts.len() >= 7, ErrorCode::InvalidParameter);
-            let mut data = Vec::with_capacity(19);
-            data.push(4u8);
-            data.extend_from_slice(&amount_in.to_le_bytes());
-            data.extend_from_slice(&minimum_amount_out.to_le_bytes());
-            data.push(0u8); // is_base_in
-            data.push(1u8); // is_exact_in
-            invoke_protocol_cpi_signed(protocol_program, accounts, data, signer_seeds)
-        }
-        #[cfg(feature = "perena-swap")]
-        SwapProtocol::Perena => {
-            require!(accounts.len() >= 5, ErrorCode::InvalidParameter);
-            let mut data = Vec::with_capacity(24);
-            data.extend_from_slice(&[0x30, 0x31, 0x36, 0x64, 0x62, 0x39, 0x61, 0x35]);
-            data.extend_from_slice(&amount_in.to_le_bytes());
-            data.extend_from_slice(&minimum_amount_out.to_le_bytes());
-            invoke_protocol_cpi_signed(protocol_program, accounts, data, signer_seeds)
-        }
-        #[cfg(feature = "heaven-swap")]
-        SwapProtocol::Heaven => {
-            require!(accounts.len() >= 5, ErrorCode::InvalidParameter);
-            let mut data = Vec::with_capacity(24);
-            data.extend_from_slice(&[0xe5, 0x17, 0xcb, 0x97, 0x7a, 0xe3, 0xad, 0x2a]);
-            data.extend_from_slice(&amount_in.to_le_bytes());
-            data.extend_from_slice(&minimum_amount_out.to_le_bytes());
-            invoke_protocol_cpi_signed(protocol_program, accounts, data, signer_seeds)
-        }
-        #[cfg(feature = "aldrin-swap")]
-        SwapProtocol::Aldrin => {
-            require!(accounts.len() >= 6, ErrorCode::InvalidParameter);
-            let mut data = Vec::with_capacity(24);
-            data.extend_from_slice(&[0x87, 0x6a, 0xdc, 0x47, 0x11, 0x4e, 0x79, 0xb1]);
-            data.extend_from_slice(&amount_in.to_le_bytes());
-            data.extend_from_slice(&minimum_amount_out.to_le_bytes());
-            invoke_protocol_cpi_signed(protocol_program, accounts, data, signer_seeds)
-        }
-        #[cfg(feature = "gamma-swap")]
-        SwapProtocol::Gamma => {
-            require!(accounts.len() >= 13, ErrorCode::InvalidParameter);
-            let mut data = Vec::with_capacity(24);
-            data.extend_from_slice(&[239, 82, 192, 187, 160, 26, 223, 223]);
-            data.extend_from_slice(&amount_in.to_le_bytes());
-            data.extend_from_slice(&minimum_amount_out.to_le_bytes());
-            invoke_protocol_cpi_signed(protocol_program, accounts, data, signer_seeds)
-        }
-        #[cfg(feature = "solfi-swap")]
-        SwapProtocol::SolFi => {
-            require!(accounts.len() >= 5, ErrorCode::InvalidParameter);
-            let mut data = Vec::with_capacity(24);
-            data.extend_from_slice(&[0xa3, 0xb2, 0xc1, 0xd0, 0xe4, 0xf5, 0x06, 0x17]);
-            data.extend_from_slice(&amount_in.to_le_bytes());
-            data.extend_from_slice(&minimum_amount_out.to_le_bytes());
-            invoke_protocol_cpi_signed(protocol_program, accounts, data, signer_seeds)
-        }
-        #[cfg(feature = "futarchy-swap")]
-        SwapProtocol::Futarchy => {
-            require!(accounts.len() >= 5, ErrorCode::InvalidParameter);
-            let mut data = Vec::with_capacity(24);
-            data.extend_from_slice(&[0xb4, 0xc3, 0xd2, 0xe1, 0xf5, 0x06, 0x17, 0x28]);
-            data.extend_from_slice(&amount_in.to_le_bytes());
-            data.extend_from_slice(&minimum_amount_out.to_le_bytes());
-            invoke_protocol_cpi_signed(protocol_program, accounts, data, signer_seeds)
-        }
-        SwapProtocol::Unknown => Err(ErrorCode::UnsupportedProtocol.into()),
-    }
+    swap_adapter::execute_swap(
+        registry,
+        remaining_accounts,
+        &SwapRequest {
+            in_amount: action_data.amount_in,
+            out_amount: action_data.minimum_amount_out,
+            is_base_in: false,
+            mode: SwapMode::ExactIn,
+            input_mint: action_data.input_mint,
+        },
+        None,
+        &[fund_seeds],
+    )
 }
 
 /// Execute a fund perp open position (internal state modification).
@@ -141,22 +66,61 @@ pub fn execute_fund_close_perp<'info>(
     Ok(())
 }
 
-/// Deposit fund assets into a lending pool.
+/// Deposit fund assets into the proposal's yield venue via the deposit
+/// adapters, with the Fund PDA signing as depositor. The venue must spend
+/// from `fund_vault` and mint receipts to a fund-owned account.
+///
+/// remaining_accounts: [0] = protocol program, [1..] = protocol-specific accounts
 pub fn execute_fund_deposit_lending<'info>(
-    _action_data: &LendingActionData,
-    _remaining_accounts: &[AccountInfo<'info>],
-    _fund_seeds: &[&[u8]],
+    registry: &mut RegistryAccount<'info>,
+    action_data: &LendingActionData,
+    remaining_accounts: &[AccountInfo<'info>],
+    mint: &Pubkey,
+    depositor: Depositor,
+    fund_seeds: &[&[u8]],
 ) -> Result<()> {
-    msg!("Fund lending deposit: not yet implemented");
+    require_proposal_protocol(action_data, remaining_accounts)?;
+    deposit_adapter::execute_deposit(
+        registry,
+        remaining_accounts,
+        mint,
+        action_data.amount,
+        Some(depositor),
+        &[fund_seeds],
+    )?;
     Ok(())
 }
 
-/// Withdraw fund assets from a lending pool.
+/// Withdraw fund assets from the proposal's yield venue back into
+/// `fund_vault`, with the Fund PDA signing.
 pub fn execute_fund_withdraw_lending<'info>(
-    _action_data: &LendingActionData,
-    _remaining_accounts: &[AccountInfo<'info>],
-    _fund_seeds: &[&[u8]],
+    registry: &RegistryAccount<'info>,
+    action_data: &LendingActionData,
+    remaining_accounts: &[AccountInfo<'info>],
+    depositor: Depositor,
+    fund_seeds: &[&[u8]],
 ) -> Result<()> {
-    msg!("Fund lending withdraw: not yet implemented");
+    require_proposal_protocol(action_data, remaining_accounts)?;
+    deposit_adapter::execute_withdraw(
+        registry,
+        remaining_accounts,
+        action_data.amount,
+        Some(depositor),
+        &[fund_seeds],
+    )?;
+    Ok(())
+}
+
+/// The executor's accounts must route to the venue the proposal approved.
+fn require_proposal_protocol(
+    action_data: &LendingActionData,
+    remaining_accounts: &[AccountInfo],
+) -> Result<()> {
+    require!(
+        remaining_accounts
+            .first()
+            .is_some_and(|program| program.key() == action_data.protocol),
+        ErrorCode::ProtocolAccountMismatch
+    );
     Ok(())
 }
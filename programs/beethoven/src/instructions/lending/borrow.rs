@@ -6,7 +6,9 @@ use crate::math::interest::accrue_interest;
 use crate::math::fixed_point::wad_mul;
 use crate::math::liquidation::compute_lending_health_factor;
 use crate::math::oracle::get_price;
-use crate::state::{Exchange, LendingPool, LendingPosition, VaultState};
+use crate::state::{
+    sub_account_seed, Exchange, LendingPool, LendingPosition, UserAccount, VaultBucket, VaultState,
+};
 
 use anchor_spl::token::{TokenAccount, Token};
 
@@ -22,6 +24,17 @@ pub struct Borrow<'info> {
     )]
     pub exchange: Box<Account<'info, Exchange>>,
 
+    #[account(
+        seeds = [
+            USER_ACCOUNT_SEED,
+            owner.key().as_ref(),
+            sub_account_seed(&user_account.sub_account_id.to_le_bytes()),
+        ],
+        bump = user_account.bump,
+        constraint = user_account.owner == owner.key() @ ErrorCode::Unauthorized,
+    )]
+    pub user_account: Box<Account<'info, UserAccount>>,
+
     #[account(
         mut,
         seeds = [LENDING_POOL_SEED, &lending_pool.pool_index.to_le_bytes()],
@@ -32,13 +45,18 @@ pub struct Borrow<'info> {
 
     #[account(
         mut,
-        seeds = [LENDING_POSITION_SEED, owner.key().as_ref(), lending_pool.key().as_ref()],
+        seeds = [
+            LENDING_POSITION_SEED,
+            user_account.position_seed_key(user_account.key()).as_ref(),
+            lending_pool.key().as_ref(),
+        ],
         bump = lending_position.bump,
         constraint = lending_position.owner == owner.key() @ ErrorCode::Unauthorized,
     )]
     pub lending_position: Box<Account<'info, LendingPosition>>,
 
     #[account(
+        mut,
         seeds = [VAULT_SEED, lending_pool.mint.as_ref()],
         bump = vault_state.bump,
     )]
@@ -113,6 +131,10 @@ pub fn handler(ctx: Context<Borrow>, amount: u64) -> Result<()> {
     );
 
     // Transfer tokens from vault to user
+    ctx.accounts
+        .vault_state
+        .debit(VaultBucket::LendingLiquidity, amount)?;
+
     let mint_key = ctx.accounts.vault_state.mint;
     let seeds = &[
         VAULT_SEED,
@@ -134,6 +156,11 @@ pub fn handler(ctx: Context<Borrow>, amount: u64) -> Result<()> {
         amount,
     )?;
 
+    ctx.accounts.vault_token_account.reload()?;
+    ctx.accounts
+        .vault_state
+        .assert_backed(ctx.accounts.vault_token_account.amount)?;
+
     // Update state
     let position = &mut ctx.accounts.lending_position;
     position.borrowed_amount = new_borrow_amount;
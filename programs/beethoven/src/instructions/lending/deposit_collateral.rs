@@ -3,7 +3,9 @@ use crate::constants::*;
 use crate::error::ErrorCode;
 use crate::events::CollateralDeposited;
 use crate::math::interest::accrue_interest;
-use crate::state::{Exchange, LendingPool, LendingPosition, UserAccount};
+use crate::state::{
+    sub_account_seed, Exchange, LendingPool, LendingPosition, UserAccount, VaultBucket, VaultState,
+};
 use anchor_spl::token::{TokenAccount, Token};
 #[derive(Accounts)]
 pub struct DepositCollateral<'info> {
@@ -19,7 +21,11 @@ pub struct DepositCollateral<'info> {
 
     #[account(
         mut,
-        seeds = [USER_ACCOUNT_SEED, owner.key().as_ref()],
+        seeds = [
+            USER_ACCOUNT_SEED,
+            owner.key().as_ref(),
+            sub_account_seed(&user_account.sub_account_id.to_le_bytes()),
+        ],
         bump = user_account.bump,
         constraint = user_account.owner == owner.key() @ ErrorCode::Unauthorized,
     )]
@@ -37,11 +43,22 @@ pub struct DepositCollateral<'info> {
         init_if_needed,
         payer = owner,
         space = LendingPosition::LEN,
-        seeds = [LENDING_POSITION_SEED, owner.key().as_ref(), lending_pool.key().as_ref()],
+        seeds = [
+            LENDING_POSITION_SEED,
+            user_account.position_seed_key(user_account.key()).as_ref(),
+            lending_pool.key().as_ref(),
+        ],
         bump,
     )]
     pub lending_position: Account<'info, LendingPosition>,
 
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, lending_pool.mint.as_ref()],
+        bump = vault_state.bump,
+    )]
+    pub vault_state: Box<Account<'info, VaultState>>,
+
     #[account(
         mut,
         constraint = vault_token_account.key() == lending_pool.vault @ ErrorCode::InvalidParameter,
@@ -93,6 +110,14 @@ pub fn handler(ctx: Context<DepositCollateral>, amount: u64) -> Result<()> {
         amount,
     )?;
 
+    ctx.accounts
+        .vault_state
+        .credit(VaultBucket::LendingLiquidity, amount)?;
+    ctx.accounts.vault_token_account.reload()?;
+    ctx.accounts
+        .vault_state
+        .assert_backed(ctx.accounts.vault_token_account.amount)?;
+
     // Update pool state
     pool.total_deposits = new_total;
 
@@ -104,11 +129,12 @@ pub fn handler(ctx: Context<DepositCollateral>, amount: u64) -> Result<()> {
     if position.owner == Pubkey::default() {
         // First-time init
         position.owner = ctx.accounts.owner.key();
+        position.user_account = ctx.accounts.user_account.key();
         position.pool = pool_key;
         position.bump = ctx.bumps.lending_position;
         position.cumulative_deposit_rate_snapshot = deposit_rate;
         position.cumulative_borrow_rate_snapshot = borrow_rate;
-        position._reserved = [0u8; 64];
+        position._reserved = [0u8; 32];
 
         let user = &mut ctx.accounts.user_account;
         user.open_lending_positions = user
@@ -6,7 +6,9 @@ use crate::math::interest::accrue_interest;
 use crate::math::fixed_point::{wad_mul, bps_mul};
 use crate::math::liquidation::compute_lending_health_factor;
 use crate::math::oracle::get_price;
-use crate::state::{Exchange, LendingPool, LendingPosition, VaultState};
+use crate::state::{
+    sub_account_seed, Exchange, LendingPool, LendingPosition, UserAccount, VaultBucket, VaultState,
+};
 
 use anchor_spl::token::{TokenAccount, Token};
 
@@ -21,6 +23,18 @@ pub struct LiquidateLending<'info> {
     )]
     pub exchange: Box<Account<'info, Exchange>>,
 
+    #[account(
+        seeds = [
+            USER_ACCOUNT_SEED,
+            borrower.key().as_ref(),
+            sub_account_seed(&user_account.sub_account_id.to_le_bytes()),
+        ],
+        bump = user_account.bump,
+        constraint = user_account.owner == borrower.key() @ ErrorCode::Unauthorized,
+    )]
+    /// Borrower's (sub-)account holding the position
+    pub user_account: Box<Account<'info, UserAccount>>,
+
     #[account(
         mut,
         seeds = [LENDING_POOL_SEED, &lending_pool.pool_index.to_le_bytes()],
@@ -30,7 +44,11 @@ pub struct LiquidateLending<'info> {
 
     #[account(
         mut,
-        seeds = [LENDING_POSITION_SEED, borrower.key().as_ref(), lending_pool.key().as_ref()],
+        seeds = [
+            LENDING_POSITION_SEED,
+            user_account.position_seed_key(user_account.key()).as_ref(),
+            lending_pool.key().as_ref(),
+        ],
         bump = lending_position.bump,
         constraint = lending_position.owner == borrower.key() @ ErrorCode::Unauthorized,
     )]
@@ -40,6 +58,7 @@ pub struct LiquidateLending<'info> {
     pub borrower: UncheckedAccount<'info>,
 
     #[account(
+        mut,
         seeds = [VAULT_SEED, lending_pool.mint.as_ref()],
         bump = vault_state.bump,
     )]
@@ -116,6 +135,10 @@ pub fn handler(ctx: Context<LiquidateLending>, repay_amount: u64) -> Result<()>
     )?;
 
     // Transfer seized collateral from vault to liquidator
+    let vault_state = &mut ctx.accounts.vault_state;
+    vault_state.credit(VaultBucket::LendingLiquidity, actual_repay)?;
+    vault_state.debit(VaultBucket::LendingLiquidity, collateral_to_seize)?;
+
     let mint_key = ctx.accounts.vault_state.mint;
     let seeds = &[
         VAULT_SEED,
@@ -137,6 +160,11 @@ pub fn handler(ctx: Context<LiquidateLending>, repay_amount: u64) -> Result<()>
         collateral_to_seize,
     )?;
 
+    ctx.accounts.vault_token_account.reload()?;
+    ctx.accounts
+        .vault_state
+        .assert_backed(ctx.accounts.vault_token_account.amount)?;
+
     // Update position
     let position = &mut ctx.accounts.lending_position;
     position.borrowed_amount = position
@@ -1,11 +1,13 @@
 pub mod deposit_collateral;
 pub mod withdraw_collateral;
+pub mod transfer_lending_deposit;
 pub mod borrow;
 pub mod repay;
 pub mod liquidate_lending;
 
 pub use deposit_collateral::*;
 pub use withdraw_collateral::*;
+pub use transfer_lending_deposit::*;
 pub use borrow::*;
 pub use repay::*;
 pub use liquidate_lending::*;
@@ -3,7 +3,9 @@ use crate::constants::*;
 use crate::error::ErrorCode;
 use crate::events::RepayExecuted;
 use crate::math::interest::{accrue_interest, get_borrow_balance};
-use crate::state::{LendingPool, LendingPosition};
+use crate::state::{
+    sub_account_seed, LendingPool, LendingPosition, UserAccount, VaultBucket, VaultState,
+};
 
 use anchor_spl::token::{TokenAccount, Token};
 
@@ -12,6 +14,17 @@ pub struct Repay<'info> {
     #[account(mut)]
     pub owner: Signer<'info>,
 
+    #[account(
+        seeds = [
+            USER_ACCOUNT_SEED,
+            owner.key().as_ref(),
+            sub_account_seed(&user_account.sub_account_id.to_le_bytes()),
+        ],
+        bump = user_account.bump,
+        constraint = user_account.owner == owner.key() @ ErrorCode::Unauthorized,
+    )]
+    pub user_account: Box<Account<'info, UserAccount>>,
+
     #[account(
         mut,
         seeds = [LENDING_POOL_SEED, &lending_pool.pool_index.to_le_bytes()],
@@ -21,12 +34,23 @@ pub struct Repay<'info> {
 
     #[account(
         mut,
-        seeds = [LENDING_POSITION_SEED, owner.key().as_ref(), lending_pool.key().as_ref()],
+        seeds = [
+            LENDING_POSITION_SEED,
+            user_account.position_seed_key(user_account.key()).as_ref(),
+            lending_pool.key().as_ref(),
+        ],
         bump = lending_position.bump,
         constraint = lending_position.owner == owner.key() @ ErrorCode::Unauthorized,
     )]
     pub lending_position: Account<'info, LendingPosition>,
 
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, lending_pool.mint.as_ref()],
+        bump = vault_state.bump,
+    )]
+    pub vault_state: Box<Account<'info, VaultState>>,
+
     #[account(
         mut,
         constraint = vault_token_account.key() == lending_pool.vault @ ErrorCode::InvalidParameter,
@@ -76,6 +100,14 @@ pub fn handler(ctx: Context<Repay>, amount: u64) -> Result<()> {
         repay_amount,
     )?;
 
+    ctx.accounts
+        .vault_state
+        .credit(VaultBucket::LendingLiquidity, repay_amount)?;
+    ctx.accounts.vault_token_account.reload()?;
+    ctx.accounts
+        .vault_state
+        .assert_backed(ctx.accounts.vault_token_account.amount)?;
+
     // Update position
     if repay_amount >= current_debt {
         position.borrowed_amount = 0;
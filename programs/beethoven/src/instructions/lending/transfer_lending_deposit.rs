@@ -0,0 +1,147 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::error::ErrorCode;
+use crate::events::LendingDepositTransferred;
+use crate::math::interest::accrue_interest;
+use crate::math::liquidation::check_lending_withdrawal;
+use crate::math::oracle::get_price;
+use crate::state::{sub_account_seed, Exchange, LendingPool, LendingPosition, UserAccount};
+
+/// Move a lending-pool deposit between two of the owner's sub-accounts in the
+/// same pool. Tokens stay in the vault; only positions change. Perp margin is
+/// posted per position and is not transferable here.
+#[derive(Accounts)]
+pub struct TransferLendingDeposit<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [EXCHANGE_SEED],
+        bump = exchange.bump,
+        constraint = !exchange.lending_paused @ ErrorCode::ExchangePaused,
+    )]
+    pub exchange: Box<Account<'info, Exchange>>,
+
+    #[account(
+        seeds = [
+            USER_ACCOUNT_SEED,
+            owner.key().as_ref(),
+            sub_account_seed(&from_user_account.sub_account_id.to_le_bytes()),
+        ],
+        bump = from_user_account.bump,
+        constraint = from_user_account.owner == owner.key() @ ErrorCode::Unauthorized,
+    )]
+    pub from_user_account: Box<Account<'info, UserAccount>>,
+
+    #[account(
+        mut,
+        seeds = [
+            USER_ACCOUNT_SEED,
+            owner.key().as_ref(),
+            sub_account_seed(&to_user_account.sub_account_id.to_le_bytes()),
+        ],
+        bump = to_user_account.bump,
+        constraint = to_user_account.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = to_user_account.key() != from_user_account.key() @ ErrorCode::SameSubAccount,
+    )]
+    pub to_user_account: Box<Account<'info, UserAccount>>,
+
+    #[account(
+        mut,
+        seeds = [LENDING_POOL_SEED, &lending_pool.pool_index.to_le_bytes()],
+        bump = lending_pool.bump,
+        constraint = !lending_pool.paused @ ErrorCode::ExchangePaused,
+    )]
+    pub lending_pool: Box<Account<'info, LendingPool>>,
+
+    #[account(
+        mut,
+        seeds = [
+            LENDING_POSITION_SEED,
+            from_user_account.position_seed_key(from_user_account.key()).as_ref(),
+            lending_pool.key().as_ref(),
+        ],
+        bump = from_position.bump,
+    )]
+    pub from_position: Box<Account<'info, LendingPosition>>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = LendingPosition::LEN,
+        seeds = [
+            LENDING_POSITION_SEED,
+            to_user_account.position_seed_key(to_user_account.key()).as_ref(),
+            lending_pool.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub to_position: Box<Account<'info, LendingPosition>>,
+
+    /// CHECK: Pyth oracle price feed
+    #[account(
+        constraint = oracle.key() == lending_pool.oracle @ ErrorCode::OracleAccountMismatch,
+    )]
+    pub oracle: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<TransferLendingDeposit>, amount: u64) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    // Capture keys before mutable borrows
+    let pool_key = ctx.accounts.lending_pool.key();
+    let to_user_key = ctx.accounts.to_user_account.key();
+    let clock = Clock::get()?;
+
+    let pool = &mut ctx.accounts.lending_pool;
+    accrue_interest(pool, clock.unix_timestamp)?;
+
+    // The source sub-account must stay healthy without the collateral
+    let from = &mut ctx.accounts.from_position;
+    require!(
+        from.deposited_amount >= amount,
+        ErrorCode::InsufficientCollateralValue
+    );
+    if from.borrowed_amount > 0 {
+        let oracle_price = get_price(&ctx.accounts.oracle.to_account_info(), &clock)?;
+        check_lending_withdrawal(pool, from, amount, oracle_price.price)?;
+    }
+    from.deposited_amount -= amount;
+    from.last_updated = clock.unix_timestamp;
+
+    let to = &mut ctx.accounts.to_position;
+    if to.owner == Pubkey::default() {
+        // First-time init
+        to.owner = ctx.accounts.owner.key();
+        to.user_account = to_user_key;
+        to.pool = pool_key;
+        to.bump = ctx.bumps.to_position;
+        to.cumulative_deposit_rate_snapshot = pool.cumulative_deposit_rate;
+        to.cumulative_borrow_rate_snapshot = pool.cumulative_borrow_rate;
+        to._reserved = [0u8; 32];
+
+        let user = &mut ctx.accounts.to_user_account;
+        user.open_lending_positions = user
+            .open_lending_positions
+            .checked_add(1)
+            .ok_or(ErrorCode::MaxLendingPositionsReached)?;
+    }
+    to.deposited_amount = to
+        .deposited_amount
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    to.last_updated = clock.unix_timestamp;
+
+    emit!(LendingDepositTransferred {
+        owner: ctx.accounts.owner.key(),
+        pool: pool_key,
+        from_user_account: ctx.accounts.from_user_account.key(),
+        to_user_account: to_user_key,
+        amount,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -3,10 +3,11 @@ use crate::constants::*;
 use crate::error::ErrorCode;
 use crate::events::CollateralWithdrawn;
 use crate::math::interest::accrue_interest;
-use crate::math::fixed_point::wad_mul;
-use crate::math::liquidation::compute_lending_health_factor;
+use crate::math::liquidation::check_lending_withdrawal;
 use crate::math::oracle::get_price;
-use crate::state::{Exchange, LendingPool, LendingPosition, VaultState};
+use crate::state::{
+    sub_account_seed, Exchange, LendingPool, LendingPosition, UserAccount, VaultBucket, VaultState,
+};
 
 use anchor_spl::token::{TokenAccount, Token};
 
@@ -21,6 +22,17 @@ pub struct WithdrawCollateral<'info> {
     )]
     pub exchange: Box<Account<'info, Exchange>>,
 
+    #[account(
+        seeds = [
+            USER_ACCOUNT_SEED,
+            owner.key().as_ref(),
+            sub_account_seed(&user_account.sub_account_id.to_le_bytes()),
+        ],
+        bump = user_account.bump,
+        constraint = user_account.owner == owner.key() @ ErrorCode::Unauthorized,
+    )]
+    pub user_account: Box<Account<'info, UserAccount>>,
+
     #[account(
         mut,
         seeds = [LENDING_POOL_SEED, &lending_pool.pool_index.to_le_bytes()],
@@ -30,13 +42,18 @@ pub struct WithdrawCollateral<'info> {
 
     #[account(
         mut,
-        seeds = [LENDING_POSITION_SEED, owner.key().as_ref(), lending_pool.key().as_ref()],
+        seeds = [
+            LENDING_POSITION_SEED,
+            user_account.position_seed_key(user_account.key()).as_ref(),
+            lending_pool.key().as_ref(),
+        ],
         bump = lending_position.bump,
         constraint = lending_position.owner == owner.key() @ ErrorCode::Unauthorized,
     )]
     pub lending_position: Box<Account<'info, LendingPosition>>,
 
     #[account(
+        mut,
         seeds = [VAULT_SEED, lending_pool.mint.as_ref()],
         bump = vault_state.bump,
     )]
@@ -77,28 +94,14 @@ pub fn handler(ctx: Context<WithdrawCollateral>, amount: u64) -> Result<()> {
     // If user has borrows, check that withdrawal won't make position unhealthy
     if position.borrowed_amount > 0 {
         let oracle_price = get_price(&ctx.accounts.oracle.to_account_info(), &clock)?;
-        let remaining_deposit = position
-            .deposited_amount
-            .checked_sub(amount)
-            .ok_or(ErrorCode::MathUnderflow)?;
-
-        let collateral_value = (remaining_deposit as u128)
-            .checked_mul(oracle_price.price as u128)
-            .ok_or(ErrorCode::MathOverflow)?;
-        let weighted_collateral = wad_mul(collateral_value, pool.collateral_factor)?;
-
-        let borrow_value = (position.borrowed_amount as u128)
-            .checked_mul(oracle_price.price as u128)
-            .ok_or(ErrorCode::MathOverflow)?;
-
-        let health = compute_lending_health_factor(weighted_collateral, borrow_value)?;
-        require!(
-            health >= LENDING_LIQUIDATION_THRESHOLD,
-            ErrorCode::WithdrawalWouldLiquidate
-        );
+        check_lending_withdrawal(pool, position, amount, oracle_price.price)?;
     }
 
     // Transfer tokens from vault to user
+    ctx.accounts
+        .vault_state
+        .debit(VaultBucket::LendingLiquidity, amount)?;
+
     let mint_key = ctx.accounts.vault_state.mint;
     let seeds = &[
         VAULT_SEED,
@@ -120,6 +123,11 @@ pub fn handler(ctx: Context<WithdrawCollateral>, amount: u64) -> Result<()> {
         amount,
     )?;
 
+    ctx.accounts.vault_token_account.reload()?;
+    ctx.accounts
+        .vault_state
+        .assert_backed(ctx.accounts.vault_token_account.amount)?;
+
     // Update state
     position.deposited_amount = position
         .deposited_amount
@@ -1,13 +1,17 @@
 pub mod admin;
 pub mod user;
 pub mod swap;
+pub mod spot;
 pub mod perp;
 pub mod lending;
 pub mod fund;
+pub mod oracle;
 
 pub use admin::*;
 pub use user::*;
 pub use swap::*;
+pub use spot::*;
 pub use perp::*;
 pub use lending::*;
 pub use fund::*;
+pub use oracle::*;
@@ -0,0 +1,3 @@
+pub mod read_price_accumulator;
+
+pub use read_price_accumulator::*;
@@ -0,0 +1,39 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::set_return_data;
+use crate::constants::*;
+use crate::math::twap::compute_twap;
+use crate::state::PriceAccumulator;
+
+/// Prices read from an accumulator, returned via `set_return_data`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct PriceAccumulatorQuote {
+    pub twap: u64,        // PRICE_PRECISION, over the requested window
+    pub ema_price: u64,   // PRICE_PRECISION
+    pub last_update: i64, // Time of the latest observation
+}
+
+#[derive(Accounts)]
+pub struct ReadPriceAccumulator<'info> {
+    #[account(
+        seeds = [PRICE_ACCUMULATOR_SEED, price_accumulator.oracle.as_ref()],
+        bump = price_accumulator.bump,
+    )]
+    pub price_accumulator: Box<Account<'info, PriceAccumulator>>,
+}
+
+/// View the TWAP over the trailing `window` seconds and the current EMA.
+/// Read-only: simulate it, or CPI and read the return data. Fails with
+/// TwapWindowUnavailable until the ring buffer covers the window.
+pub fn handler(ctx: Context<ReadPriceAccumulator>, window: i64) -> Result<()> {
+    let accumulator = &ctx.accounts.price_accumulator;
+    let now = Clock::get()?.unix_timestamp;
+
+    let quote = PriceAccumulatorQuote {
+        twap: compute_twap(accumulator, window, now)?,
+        ema_price: accumulator.ema_price,
+        last_update: accumulator.last_update,
+    };
+    set_return_data(&quote.try_to_vec()?);
+
+    Ok(())
+}
@@ -2,19 +2,33 @@ use anchor_lang::prelude::*;
 use crate::constants::*;
 use crate::error::ErrorCode;
 use crate::events::PerpPositionClosed;
+use crate::math::fee_tiers::{discounted_fee_bps, record_volume, resolve_fee_tier, rolling_volume};
 use crate::math::fixed_point::bps_mul;
-use crate::math::funding::compute_position_funding;
-use crate::math::liquidation::compute_pnl;
+use crate::math::funding::{compute_position_funding, funding_in_collateral};
+use crate::math::liquidation::{compute_pnl, position_notional};
 use crate::math::oracle::get_price;
-use crate::state::{Exchange, PerpMarket, PerpPosition, UserAccount, VaultState};
+use crate::math::limits::track_user_close;
+use crate::math::perp_pool::{split_trading_fee, track_close_funding, track_close_notional};
+use crate::math::referral::{accrue_referral, referee_fee_bps};
+use crate::math::twap::record_price_observation;
+use crate::state::{
+    sub_account_seed, Exchange, PerpLpPool, PerpMarket, PerpPosition, PriceAccumulator,
+    ReferrerAccount, UserAccount, UserMarketStats, VaultState,
+};
 use crate::state::perp_position::PositionSide;
 
 use anchor_spl::token::{TokenAccount, Token};
 
 #[derive(Accounts)]
 pub struct ClosePosition<'info> {
+    /// Owner or their delegate; pays rent for a missing stats account
     #[account(mut)]
-    pub owner: Signer<'info>,
+    pub authority: Signer<'info>,
+
+    /// CHECK: Account owner, bound by the user_account seeds; receives the
+    /// payout and the position's rent
+    #[account(mut)]
+    pub owner: UncheckedAccount<'info>,
 
     #[account(
         seeds = [EXCHANGE_SEED],
@@ -24,7 +38,11 @@ pub struct ClosePosition<'info> {
 
     #[account(
         mut,
-        seeds = [USER_ACCOUNT_SEED, owner.key().as_ref()],
+        seeds = [
+            USER_ACCOUNT_SEED,
+            owner.key().as_ref(),
+            sub_account_seed(&user_account.sub_account_id.to_le_bytes()),
+        ],
         bump = user_account.bump,
         constraint = user_account.owner == owner.key() @ ErrorCode::Unauthorized,
     )]
@@ -40,23 +58,60 @@ pub struct ClosePosition<'info> {
     #[account(
         mut,
         constraint = perp_position.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = perp_position.belongs_to(&user_account.key(), &user_account) @ ErrorCode::PositionNotFound,
         constraint = perp_position.market == perp_market.key() @ ErrorCode::PositionNotFound,
         close = owner,
     )]
     pub perp_position: Box<Account<'info, PerpPosition>>,
 
+    /// Owner's aggregate exposure in this market; created empty for
+    /// positions opened before per-market stats existed
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = UserMarketStats::LEN,
+        seeds = [USER_MARKET_STATS_SEED, user_account.key().as_ref(), perp_market.key().as_ref()],
+        bump,
+    )]
+    pub user_market_stats: Box<Account<'info, UserMarketStats>>,
+
     /// CHECK: Pyth oracle price feed
     #[account(
         constraint = oracle.key() == perp_market.oracle @ ErrorCode::OracleAccountMismatch,
     )]
     pub oracle: UncheckedAccount<'info>,
 
+    /// TWAP/EMA accumulator for the market oracle, observed on every call
     #[account(
-        seeds = [VAULT_SEED, perp_market.quote_mint.as_ref()],
+        mut,
+        seeds = [PRICE_ACCUMULATOR_SEED, perp_market.oracle.as_ref()],
+        bump = price_accumulator.bump,
+    )]
+    pub price_accumulator: Box<Account<'info, PriceAccumulator>>,
+
+    #[account(
+        mut,
+        seeds = [PERP_VAULT_SEED, perp_market.collateral_mint.as_ref()],
         bump = vault_state.bump,
     )]
     pub vault_state: Box<Account<'info, VaultState>>,
 
+    /// LP pool backing the market; receives its share of trading fees
+    #[account(
+        seeds = [PERP_LP_POOL_SEED, perp_market.collateral_mint.as_ref()],
+        bump = lp_pool.bump,
+    )]
+    pub lp_pool: Box<Account<'info, PerpLpPool>>,
+
+    /// Optional rewards account of the user's referrer in the collateral mint;
+    /// supplying it applies the referee discount
+    #[account(
+        mut,
+        seeds = [REFERRER_SEED, user_account.referrer.as_ref(), perp_market.collateral_mint.as_ref()],
+        bump = referrer_account.bump,
+    )]
+    pub referrer_account: Option<Box<Account<'info, ReferrerAccount>>>,
+
     #[account(
         mut,
         constraint = vault_token_account.key() == vault_state.token_account @ ErrorCode::InvalidParameter,
@@ -70,19 +125,47 @@ pub struct ClosePosition<'info> {
     pub user_token_account: Account<'info, TokenAccount>,
 
     pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
 }
 
 pub fn handler(ctx: Context<ClosePosition>) -> Result<()> {
-    let position = &ctx.accounts.perp_position;
-    let market = &ctx.accounts.perp_market;
-    let exchange = &ctx.accounts.exchange;
     let clock = Clock::get()?;
 
+    let delegate = ctx
+        .accounts
+        .user_account
+        .authorize_trader(&ctx.accounts.authority.key(), clock.unix_timestamp)?;
+    if let Some(delegate) = &delegate {
+        require!(
+            delegate.allows_market(ctx.accounts.perp_market.market_index),
+            ErrorCode::DelegateScopeExceeded
+        );
+    }
+
     // Get current price
     let oracle_price = get_price(&ctx.accounts.oracle.to_account_info(), &clock)?;
 
+    record_price_observation(
+        &mut ctx.accounts.price_accumulator,
+        oracle_price.price,
+        clock.unix_timestamp,
+    )?;
+
+    // Volatility halt: refresh the breaker; closing is allowed while reduce-only
+    let market_key = ctx.accounts.perp_market.key();
+    ctx.accounts.perp_market.refresh_circuit_breaker(
+        market_key,
+        oracle_price.price,
+        clock.unix_timestamp,
+    )?;
+
+    let position = &ctx.accounts.perp_position;
+    let market = &ctx.accounts.perp_market;
+    let exchange = &ctx.accounts.exchange;
+
     // Calculate PnL
     let pnl = compute_pnl(
+        market.contract_type,
         &position.side,
         position.size,
         position.entry_price,
@@ -97,29 +180,49 @@ pub fn handler(ctx: Context<ClosePosition>) -> Result<()> {
         market.cumulative_funding_short,
         position.cumulative_funding_snapshot,
     )?;
+    let funding_payment =
+        funding_in_collateral(market.contract_type, funding_payment, oracle_price.price)?;
 
-    // Calculate fee
-    let notional = (position.size as u128)
-        .checked_mul(oracle_price.price as u128)
-        .ok_or(ErrorCode::MathOverflow)?
-        .checked_div(PRICE_PRECISION as u128)
-        .ok_or(ErrorCode::DivisionByZero)?;
-    let fee = bps_mul(notional as u64, exchange.perp_close_fee_bps)?;
+    // Calculate fee at the user's 30-day volume tier
+    let notional = position_notional(market.contract_type, position.size, oracle_price.price)?;
+    let volume = rolling_volume(&ctx.accounts.user_account, clock.unix_timestamp)?;
+    let (fee_tier, discount_bps) = resolve_fee_tier(exchange, volume);
+    let fee_bps = discounted_fee_bps(exchange.perp_close_fee_bps, discount_bps)?;
+    let fee_bps = referee_fee_bps(exchange, fee_bps, ctx.accounts.referrer_account.is_some())?;
+    let fee = bps_mul(notional, fee_bps)?;
 
-    // Net payout = collateral + pnl - funding - fee
-    let payout = (position.collateral as i64)
+    // Net equity = collateral + pnl - funding
+    let equity = (position.collateral as i64)
         .checked_add(pnl)
         .ok_or(ErrorCode::MathOverflow)?
         .checked_sub(funding_payment)
         .ok_or(ErrorCode::MathOverflow)?
-        .checked_sub(fee as i64)
-        .ok_or(ErrorCode::MathOverflow)?;
+        .max(0) as u64;
+
+    // Fee is only collected out of remaining equity
+    let fee_paid = fee.min(equity);
+    let payout = equity - fee_paid;
+
+    // Referrer share comes off the top; the LP fee share stays with the
+    // pool along with trader losses
+    let referral_fee = match ctx.accounts.referrer_account.as_mut() {
+        Some(referrer) => accrue_referral(exchange, referrer, fee_paid)?,
+        None => 0,
+    };
+    let (_, protocol_fee) =
+        split_trading_fee(fee_paid - referral_fee, ctx.accounts.lp_pool.fee_share_bps)?;
+    ctx.accounts.vault_state.settle_perp_margin(
+        position.collateral,
+        protocol_fee,
+        referral_fee,
+        payout,
+    )?;
 
     // Transfer payout if positive
     if payout > 0 {
-        let mint_key = market.quote_mint;
+        let mint_key = market.collateral_mint;
         let seeds = &[
-            VAULT_SEED,
+            PERP_VAULT_SEED,
             mint_key.as_ref(),
             &[ctx.accounts.vault_state.bump],
         ];
@@ -135,12 +238,24 @@ pub fn handler(ctx: Context<ClosePosition>) -> Result<()> {
                 },
                 signer_seeds,
             ),
-            payout as u64,
+            payout,
         )?;
     }
 
+    ctx.accounts.vault_token_account.reload()?;
+    ctx.accounts
+        .vault_state
+        .assert_backed(ctx.accounts.vault_token_account.amount)?;
+
     // Update market OI
     let market = &mut ctx.accounts.perp_market;
+    track_close_notional(market, &position.side, position.size, position.entry_price)?;
+    track_close_funding(
+        market,
+        &position.side,
+        position.size,
+        position.cumulative_funding_snapshot,
+    )?;
     match position.side {
         PositionSide::Long => {
             market.long_open_interest = market
@@ -154,6 +269,17 @@ pub fn handler(ctx: Context<ClosePosition>) -> Result<()> {
         }
     }
 
+    // Update per-market exposure
+    let stats = &mut ctx.accounts.user_market_stats;
+    if stats.owner == Pubkey::default() {
+        stats.owner = ctx.accounts.owner.key();
+        stats.market = market_key;
+        stats.bump = ctx.bumps.user_market_stats;
+        stats._reserved = [0u8; 32];
+    }
+    track_user_close(stats, &position.side, position.size);
+    stats.last_updated = clock.unix_timestamp;
+
     // Update user account
     let user = &mut ctx.accounts.user_account;
     user.open_perp_positions = user.open_perp_positions.saturating_sub(1);
@@ -163,8 +289,9 @@ pub fn handler(ctx: Context<ClosePosition>) -> Result<()> {
         .ok_or(ErrorCode::MathOverflow)?;
     user.total_fees_paid = user
         .total_fees_paid
-        .checked_add(fee)
+        .checked_add(fee_paid)
         .ok_or(ErrorCode::MathOverflow)?;
+    record_volume(user, notional, clock.unix_timestamp)?;
     user.last_activity = clock.unix_timestamp;
 
     emit!(PerpPositionClosed {
@@ -174,7 +301,8 @@ pub fn handler(ctx: Context<ClosePosition>) -> Result<()> {
         size_closed: position.size,
         exit_price: oracle_price.price,
         pnl,
-        fee,
+        fee: fee_paid,
+        fee_tier,
         timestamp: clock.unix_timestamp,
     });
 
@@ -0,0 +1,158 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount, Transfer};
+use crate::constants::*;
+use crate::error::ErrorCode;
+use crate::events::PerpLiquidityDeposited;
+use crate::math::perp_pool::{check_deposit_cap, compute_pool_nav, load_pool_markets, pool_nav_per_share};
+use crate::state::{Exchange, PerpLpPool, VaultBucket, VaultState};
+
+#[derive(Accounts)]
+pub struct DepositPerpLiquidity<'info> {
+    #[account(mut)]
+    pub depositor: Signer<'info>,
+
+    #[account(
+        seeds = [EXCHANGE_SEED],
+        bump = exchange.bump,
+        constraint = !exchange.perp_paused @ ErrorCode::ExchangePaused,
+    )]
+    pub exchange: Box<Account<'info, Exchange>>,
+
+    #[account(
+        mut,
+        seeds = [PERP_LP_POOL_SEED, lp_pool.quote_mint.as_ref()],
+        bump = lp_pool.bump,
+    )]
+    pub lp_pool: Box<Account<'info, PerpLpPool>>,
+
+    #[account(
+        mut,
+        seeds = [PERP_VAULT_SEED, lp_pool.quote_mint.as_ref()],
+        bump = vault_state.bump,
+    )]
+    pub vault_state: Box<Account<'info, VaultState>>,
+
+    #[account(
+        mut,
+        constraint = vault_token_account.key() == vault_state.token_account @ ErrorCode::InvalidParameter,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = lp_mint.key() == lp_pool.lp_mint @ ErrorCode::InvalidParameter,
+    )]
+    pub lp_mint: Account<'info, Mint>,
+
+    /// Depositor's quote token account
+    #[account(
+        mut,
+        constraint = user_token_account.mint == lp_pool.quote_mint @ ErrorCode::InvalidParameter,
+        constraint = user_token_account.owner == depositor.key() @ ErrorCode::Unauthorized,
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+
+    /// Depositor's LP token account
+    #[account(
+        mut,
+        constraint = user_lp_account.mint == lp_pool.lp_mint @ ErrorCode::InvalidParameter,
+        constraint = user_lp_account.owner == depositor.key() @ ErrorCode::Unauthorized,
+    )]
+    pub user_lp_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// remaining_accounts: [PerpMarket, Oracle] for every market in the pool.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DepositPerpLiquidity<'info>>,
+    amount: u64,
+) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    let clock = Clock::get()?;
+    let pool = &ctx.accounts.lp_pool;
+
+    // Price the pool before the deposit
+    let snapshot = load_pool_markets(pool, ctx.remaining_accounts, &clock)?;
+    let nav = compute_pool_nav(ctx.accounts.vault_state.perp_liquidity_balance, snapshot.trader_pnl)?;
+
+    let nav_after = nav.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+    check_deposit_cap(pool, nav_after, snapshot.max_oi_notional)?;
+
+    // If no shares exist, 1:1 ratio. Otherwise: shares = amount * total_shares / nav
+    let shares_to_mint = if pool.total_shares == 0 {
+        amount
+    } else {
+        require!(nav > 0, ErrorCode::PerpLpPoolInsolvent);
+        let shares = (amount as u128)
+            .checked_mul(pool.total_shares as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(nav as u128)
+            .ok_or(ErrorCode::DivisionByZero)?;
+        u64::try_from(shares).map_err(|_| ErrorCode::MathOverflow)?
+    };
+    require!(shares_to_mint > 0, ErrorCode::InvalidAmount);
+
+    // Transfer quote from depositor to the perp vault
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.user_token_account.to_account_info(),
+                to: ctx.accounts.vault_token_account.to_account_info(),
+                authority: ctx.accounts.depositor.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    ctx.accounts.vault_state.credit(VaultBucket::PerpLiquidity, amount)?;
+    ctx.accounts.vault_token_account.reload()?;
+    ctx.accounts
+        .vault_state
+        .assert_backed(ctx.accounts.vault_token_account.amount)?;
+
+    // Mint LP shares (pool PDA signs as mint authority)
+    let quote_mint_key = ctx.accounts.lp_pool.quote_mint;
+    let pool_seeds = &[
+        PERP_LP_POOL_SEED,
+        quote_mint_key.as_ref(),
+        &[ctx.accounts.lp_pool.bump],
+    ];
+    let signer_seeds = &[&pool_seeds[..]];
+
+    token::mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.lp_mint.to_account_info(),
+                to: ctx.accounts.user_lp_account.to_account_info(),
+                authority: ctx.accounts.lp_pool.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        shares_to_mint,
+    )?;
+
+    // Update pool state
+    let pool = &mut ctx.accounts.lp_pool;
+    pool.total_shares = pool
+        .total_shares
+        .checked_add(shares_to_mint)
+        .ok_or(ErrorCode::MathOverflow)?;
+    pool.total_nav = nav_after;
+    pool.nav_per_share = pool_nav_per_share(nav_after, pool.total_shares)?;
+    pool.last_nav_update = clock.unix_timestamp;
+
+    emit!(PerpLiquidityDeposited {
+        pool: pool.key(),
+        depositor: ctx.accounts.depositor.key(),
+        amount,
+        shares_minted: shares_to_mint,
+        nav_per_share: pool.nav_per_share,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -3,10 +3,19 @@ use crate::constants::*;
 use crate::error::ErrorCode;
 use crate::events::PerpLiquidated;
 use crate::math::fixed_point::bps_mul;
-use crate::math::funding::compute_position_funding;
-use crate::math::liquidation::{compute_pnl, compute_perp_health_factor, is_perp_liquidatable};
+use crate::math::funding::{compute_position_funding, funding_in_collateral};
+use crate::math::liquidation::{
+    compute_pnl, compute_perp_health_factor, is_perp_liquidatable, position_notional,
+    resolve_margin_tier,
+};
 use crate::math::oracle::get_price;
-use crate::state::{Exchange, PerpMarket, PerpPosition, UserAccount, VaultState};
+use crate::math::limits::track_user_close;
+use crate::math::perp_pool::{track_close_funding, track_close_notional};
+use crate::math::twap::record_price_observation;
+use crate::state::{
+    sub_account_seed, Exchange, PerpMarket, PerpPosition, PriceAccumulator, UserAccount,
+    UserMarketStats, VaultState,
+};
 use crate::state::perp_position::PositionSide;
 
 use anchor_spl::token::{TokenAccount, Token};
@@ -24,7 +33,11 @@ pub struct LiquidatePerp<'info> {
 
     #[account(
         mut,
-        seeds = [USER_ACCOUNT_SEED, position_owner.key().as_ref()],
+        seeds = [
+            USER_ACCOUNT_SEED,
+            position_owner.key().as_ref(),
+            sub_account_seed(&user_account.sub_account_id.to_le_bytes()),
+        ],
         bump = user_account.bump,
     )]
     pub user_account: Account<'info, UserAccount>,
@@ -39,11 +52,23 @@ pub struct LiquidatePerp<'info> {
     #[account(
         mut,
         constraint = perp_position.owner == position_owner.key() @ ErrorCode::Unauthorized,
+        constraint = perp_position.belongs_to(&user_account.key(), &user_account) @ ErrorCode::PositionNotFound,
         constraint = perp_position.market == perp_market.key() @ ErrorCode::PositionNotFound,
         close = position_owner,
     )]
     pub perp_position: Box<Account<'info, PerpPosition>>,
 
+    /// Owner's aggregate exposure in this market; created empty for
+    /// positions opened before per-market stats existed
+    #[account(
+        init_if_needed,
+        payer = liquidator,
+        space = UserMarketStats::LEN,
+        seeds = [USER_MARKET_STATS_SEED, user_account.key().as_ref(), perp_market.key().as_ref()],
+        bump,
+    )]
+    pub user_market_stats: Box<Account<'info, UserMarketStats>>,
+
     /// CHECK: The owner of the position being liquidated
     #[account(mut)]
     pub position_owner: UncheckedAccount<'info>,
@@ -54,8 +79,17 @@ pub struct LiquidatePerp<'info> {
     )]
     pub oracle: UncheckedAccount<'info>,
 
+    /// TWAP/EMA accumulator for the market oracle, observed on every call
+    #[account(
+        mut,
+        seeds = [PRICE_ACCUMULATOR_SEED, perp_market.oracle.as_ref()],
+        bump = price_accumulator.bump,
+    )]
+    pub price_accumulator: Box<Account<'info, PriceAccumulator>>,
+
     #[account(
-        seeds = [VAULT_SEED, perp_market.quote_mint.as_ref()],
+        mut,
+        seeds = [PERP_VAULT_SEED, perp_market.collateral_mint.as_ref()],
         bump = vault_state.bump,
     )]
     pub vault_state: Box<Account<'info, VaultState>>,
@@ -71,19 +105,52 @@ pub struct LiquidatePerp<'info> {
     pub liquidator_token_account: Account<'info, TokenAccount>,
 
     pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
 }
 
 pub fn handler(ctx: Context<LiquidatePerp>) -> Result<()> {
-    let position = &ctx.accounts.perp_position;
-    let market = &ctx.accounts.perp_market;
-    let exchange = &ctx.accounts.exchange;
     let clock = Clock::get()?;
 
     // Get current price
     let oracle_price = get_price(&ctx.accounts.oracle.to_account_info(), &clock)?;
 
+    record_price_observation(
+        &mut ctx.accounts.price_accumulator,
+        oracle_price.price,
+        clock.unix_timestamp,
+    )?;
+
+    // Volatility halt: refresh the breaker before any risk checks
+    let market_key = ctx.accounts.perp_market.key();
+    let breaker = ctx.accounts.perp_market.refresh_circuit_breaker(
+        market_key,
+        oracle_price.price,
+        clock.unix_timestamp,
+    )?;
+    if breaker.tripped {
+        // Keep the trip, which an error would roll back, so the grace
+        // period starts now; refund a stats account created for this call
+        if ctx.accounts.user_market_stats.owner == Pubkey::default() {
+            let liquidator = ctx.accounts.liquidator.to_account_info();
+            ctx.accounts.user_market_stats.close(liquidator)?;
+        }
+        return Ok(());
+    }
+    require!(
+        !ctx.accounts
+            .perp_market
+            .circuit_breaker
+            .in_liquidation_grace(clock.unix_timestamp),
+        ErrorCode::LiquidationGracePeriod
+    );
+
+    let position = &ctx.accounts.perp_position;
+    let market = &ctx.accounts.perp_market;
+    let exchange = &ctx.accounts.exchange;
+
     // Compute PnL
     let pnl = compute_pnl(
+        market.contract_type,
         &position.side,
         position.size,
         position.entry_price,
@@ -98,16 +165,25 @@ pub fn handler(ctx: Context<LiquidatePerp>) -> Result<()> {
         market.cumulative_funding_short,
         position.cumulative_funding_snapshot,
     )?;
+    let funding_payment =
+        funding_in_collateral(market.contract_type, funding_payment, oracle_price.price)?;
 
     // Check health factor
     let health = compute_perp_health_factor(
+        market.contract_type,
         position.collateral,
         pnl,
         funding_payment,
         position.size,
         oracle_price.price,
     )?;
-    require!(is_perp_liquidatable(health), ErrorCode::NotLiquidatable);
+    // Maintenance margin follows the tier for the position's current notional
+    let notional = position_notional(market.contract_type, position.size, oracle_price.price)?;
+    let tier = resolve_margin_tier(market, notional);
+    require!(
+        is_perp_liquidatable(health, tier.maintenance_margin_bps),
+        ErrorCode::NotLiquidatable
+    );
 
     // Calculate liquidation bonus
     let bonus = bps_mul(position.collateral, exchange.liquidation_bonus_bps)?;
@@ -125,11 +201,16 @@ pub fn handler(ctx: Context<LiquidatePerp>) -> Result<()> {
         0
     };
 
+    // Margin beyond the reward goes to the LP pool
+    ctx.accounts
+        .vault_state
+        .settle_perp_margin(position.collateral, 0, 0, liquidator_reward)?;
+
     // Transfer reward to liquidator
     if liquidator_reward > 0 {
-        let mint_key = market.quote_mint;
+        let mint_key = market.collateral_mint;
         let seeds = &[
-            VAULT_SEED,
+            PERP_VAULT_SEED,
             mint_key.as_ref(),
             &[ctx.accounts.vault_state.bump],
         ];
@@ -149,8 +230,20 @@ pub fn handler(ctx: Context<LiquidatePerp>) -> Result<()> {
         )?;
     }
 
+    ctx.accounts.vault_token_account.reload()?;
+    ctx.accounts
+        .vault_state
+        .assert_backed(ctx.accounts.vault_token_account.amount)?;
+
     // Update market OI
     let market = &mut ctx.accounts.perp_market;
+    track_close_notional(market, &position.side, position.size, position.entry_price)?;
+    track_close_funding(
+        market,
+        &position.side,
+        position.size,
+        position.cumulative_funding_snapshot,
+    )?;
     match position.side {
         PositionSide::Long => {
             market.long_open_interest = market
@@ -164,6 +257,17 @@ pub fn handler(ctx: Context<LiquidatePerp>) -> Result<()> {
         }
     }
 
+    // Update per-market exposure
+    let stats = &mut ctx.accounts.user_market_stats;
+    if stats.owner == Pubkey::default() {
+        stats.owner = ctx.accounts.position_owner.key();
+        stats.market = market_key;
+        stats.bump = ctx.bumps.user_market_stats;
+        stats._reserved = [0u8; 32];
+    }
+    track_user_close(stats, &position.side, position.size);
+    stats.last_updated = clock.unix_timestamp;
+
     // Update user account
     let user = &mut ctx.accounts.user_account;
     user.open_perp_positions = user.open_perp_positions.saturating_sub(1);
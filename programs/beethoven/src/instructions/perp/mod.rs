@@ -1,7 +1,13 @@
 pub mod open_position;
 pub mod close_position;
+pub mod remove_margin;
 pub mod liquidate_perp;
+pub mod deposit_perp_liquidity;
+pub mod withdraw_perp_liquidity;
 
 pub use open_position::*;
 pub use close_position::*;
+pub use remove_margin::*;
 pub use liquidate_perp::*;
+pub use deposit_perp_liquidity::*;
+pub use withdraw_perp_liquidity::*;
@@ -2,11 +2,20 @@ use anchor_lang::prelude::*;
 use crate::constants::*;
 use crate::error::ErrorCode;
 use crate::events::PerpPositionOpened;
-use crate::math::liquidation::compute_liquidation_price;
+use crate::math::fee_tiers::{discounted_fee_bps, record_volume, resolve_fee_tier, rolling_volume};
+use crate::math::fixed_point::bps_mul;
+use crate::math::limits::{check_open_interest_limits, check_user_notional, track_user_open};
+use crate::math::liquidation::{compute_liquidation_price, position_notional, resolve_margin_tier};
 use crate::math::oracle::get_price;
-use crate::state::{Exchange, PerpMarket, PerpPosition, UserAccount};
+use crate::math::perp_pool::{split_trading_fee, track_open_funding, track_open_notional};
+use crate::math::referral::{accrue_referral, referee_fee_bps};
+use crate::math::twap::record_price_observation;
+use crate::state::{
+    sub_account_seed, Exchange, PerpLpPool, PerpMarket, PerpPosition, PriceAccumulator,
+    ReferrerAccount, UserAccount, UserMarketStats,
+};
 use crate::state::perp_position::PositionSide;
-use crate::state::VaultState;
+use crate::state::{VaultBucket, VaultState};
 
 use anchor_spl::token::{TokenAccount, Token};
 
@@ -19,8 +28,13 @@ pub struct OpenPositionParams {
 
 #[derive(Accounts)]
 pub struct OpenPosition<'info> {
+    /// Owner or their delegate; pays rent for new accounts
     #[account(mut)]
-    pub owner: Signer<'info>,
+    pub authority: Signer<'info>,
+
+    /// CHECK: Account owner, bound by the user_account seeds; positions and
+    /// collateral are always in their name
+    pub owner: UncheckedAccount<'info>,
 
     #[account(
         seeds = [EXCHANGE_SEED],
@@ -31,7 +45,11 @@ pub struct OpenPosition<'info> {
 
     #[account(
         mut,
-        seeds = [USER_ACCOUNT_SEED, owner.key().as_ref()],
+        seeds = [
+            USER_ACCOUNT_SEED,
+            owner.key().as_ref(),
+            sub_account_seed(&user_account.sub_account_id.to_le_bytes()),
+        ],
         bump = user_account.bump,
         constraint = user_account.owner == owner.key() @ ErrorCode::Unauthorized,
     )]
@@ -47,11 +65,11 @@ pub struct OpenPosition<'info> {
 
     #[account(
         init,
-        payer = owner,
+        payer = authority,
         space = PerpPosition::LEN,
         seeds = [
             PERP_POSITION_SEED,
-            owner.key().as_ref(),
+            user_account.position_seed_key(user_account.key()).as_ref(),
             perp_market.key().as_ref(),
             &user_account.open_perp_positions.to_le_bytes(),
         ],
@@ -59,12 +77,30 @@ pub struct OpenPosition<'info> {
     )]
     pub perp_position: Account<'info, PerpPosition>,
 
+    /// Owner's aggregate exposure in this market
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = UserMarketStats::LEN,
+        seeds = [USER_MARKET_STATS_SEED, user_account.key().as_ref(), perp_market.key().as_ref()],
+        bump,
+    )]
+    pub user_market_stats: Box<Account<'info, UserMarketStats>>,
+
     /// CHECK: Pyth oracle price feed
     #[account(
         constraint = oracle.key() == perp_market.oracle @ ErrorCode::OracleAccountMismatch,
     )]
     pub oracle: UncheckedAccount<'info>,
 
+    /// TWAP/EMA accumulator for the market oracle, observed on every call
+    #[account(
+        mut,
+        seeds = [PRICE_ACCUMULATOR_SEED, perp_market.oracle.as_ref()],
+        bump = price_accumulator.bump,
+    )]
+    pub price_accumulator: Box<Account<'info, PriceAccumulator>>,
+
     /// User's quote token account (collateral source)
     #[account(
         mut,
@@ -72,15 +108,35 @@ pub struct OpenPosition<'info> {
     )]
     pub user_token_account: Account<'info, TokenAccount>,
 
-    /// Vault token account (collateral destination)
-    #[account(mut)]
+    /// Perp vault token account (collateral destination)
+    #[account(
+        mut,
+        constraint = vault_token_account.key() == vault_state.token_account @ ErrorCode::InvalidParameter,
+    )]
     pub vault_token_account: Account<'info, TokenAccount>,
 
     #[account(
-        seeds = [VAULT_SEED, perp_market.quote_mint.as_ref()],
-        bump,
+        mut,
+        seeds = [PERP_VAULT_SEED, perp_market.collateral_mint.as_ref()],
+        bump = vault_state.bump,
+    )]
+    pub vault_state: Box<Account<'info, VaultState>>,
+
+    /// LP pool backing the market; receives its share of trading fees
+    #[account(
+        seeds = [PERP_LP_POOL_SEED, perp_market.collateral_mint.as_ref()],
+        bump = lp_pool.bump,
     )]
-    pub vault_state: Account<'info, VaultState>,
+    pub lp_pool: Box<Account<'info, PerpLpPool>>,
+
+    /// Optional rewards account of the user's referrer in the collateral mint;
+    /// supplying it applies the referee discount
+    #[account(
+        mut,
+        seeds = [REFERRER_SEED, user_account.referrer.as_ref(), perp_market.collateral_mint.as_ref()],
+        bump = referrer_account.bump,
+    )]
+    pub referrer_account: Option<Box<Account<'info, ReferrerAccount>>>,
 
     pub token_program: Program<'info, Token>,
     pub system_program: Program<'info, System>,
@@ -90,10 +146,19 @@ pub fn handler(ctx: Context<OpenPosition>, params: OpenPositionParams) -> Result
     require!(params.size > 0, ErrorCode::PositionTooSmall);
     require!(params.collateral > 0, ErrorCode::InsufficientCollateral);
 
-    let market = &ctx.accounts.perp_market;
-    let exchange = &ctx.accounts.exchange;
     let clock = Clock::get()?;
 
+    let delegate = ctx
+        .accounts
+        .user_account
+        .authorize_trader(&ctx.accounts.authority.key(), clock.unix_timestamp)?;
+    if let Some(delegate) = &delegate {
+        require!(
+            delegate.allows_market(ctx.accounts.perp_market.market_index),
+            ErrorCode::DelegateScopeExceeded
+        );
+    }
+
     // Check position limits
     require!(
         ctx.accounts.user_account.open_perp_positions < MAX_PERP_POSITIONS,
@@ -103,18 +168,51 @@ pub fn handler(ctx: Context<OpenPosition>, params: OpenPositionParams) -> Result
     // Get oracle price
     let oracle_price = get_price(&ctx.accounts.oracle.to_account_info(), &clock)?;
 
-    // Calculate leverage: leverage = (size * price / PRICE_PRECISION) / collateral
-    let notional = (params.size as u128)
-        .checked_mul(oracle_price.price as u128)
-        .ok_or(ErrorCode::MathOverflow)?
-        .checked_div(PRICE_PRECISION as u128)
-        .ok_or(ErrorCode::DivisionByZero)?;
+    record_price_observation(
+        &mut ctx.accounts.price_accumulator,
+        oracle_price.price,
+        clock.unix_timestamp,
+    )?;
 
-    let leverage = notional
+    // Volatility halt: refresh the breaker before any risk checks
+    let market_key = ctx.accounts.perp_market.key();
+    let breaker = ctx.accounts.perp_market.refresh_circuit_breaker(
+        market_key,
+        oracle_price.price,
+        clock.unix_timestamp,
+    )?;
+    if breaker.tripped {
+        // Keep the trip, which an error would roll back: refund the accounts
+        // created for this open and return without opening
+        let authority = ctx.accounts.authority.to_account_info();
+        ctx.accounts.perp_position.close(authority.clone())?;
+        if ctx.accounts.user_market_stats.owner == Pubkey::default() {
+            ctx.accounts.user_market_stats.close(authority)?;
+        }
+        return Ok(());
+    }
+    require!(!breaker.reduce_only, ErrorCode::MarketReduceOnly);
+
+    let market = &ctx.accounts.perp_market;
+    let exchange = &ctx.accounts.exchange;
+
+    // Calculate leverage: leverage = notional / collateral, both in collateral units
+    let notional = position_notional(market.contract_type, params.size, oracle_price.price)?;
+    if let Some(delegate) = &delegate {
+        delegate.check_notional(notional)?;
+    }
+
+    let leverage = (notional as u128)
         .checked_div(params.collateral as u128)
         .ok_or(ErrorCode::DivisionByZero)?;
 
-    let max_lev = market.max_leverage.min(exchange.max_leverage);
+    // Leverage and maintenance margin come from the tier for this notional
+    let tier = resolve_margin_tier(market, notional);
+    require!(
+        notional <= tier.max_notional,
+        ErrorCode::PositionExceedsMarginTiers
+    );
+    let max_lev = tier.max_leverage.min(exchange.max_leverage);
     require!(leverage <= max_lev as u128, ErrorCode::ExcessiveLeverage);
     require!(leverage >= MIN_LEVERAGE as u128, ErrorCode::ExcessiveLeverage);
 
@@ -130,26 +228,79 @@ pub fn handler(ctx: Context<OpenPosition>, params: OpenPositionParams) -> Result
     } else {
         PositionSide::Short
     };
+    check_open_interest_limits(market, &side, params.size, oracle_price.price)?;
+    check_user_notional(
+        market,
+        &ctx.accounts.user_market_stats,
+        params.size,
+        oracle_price.price,
+    )?;
+
+    // Open fee is charged on top of the collateral
+    let volume = rolling_volume(&ctx.accounts.user_account, clock.unix_timestamp)?;
+    let (fee_tier, discount_bps) = resolve_fee_tier(exchange, volume);
+    let fee_bps = discounted_fee_bps(exchange.perp_open_fee_bps, discount_bps)?;
+    let fee_bps = referee_fee_bps(exchange, fee_bps, ctx.accounts.referrer_account.is_some())?;
+    let fee = bps_mul(notional, fee_bps)?;
+
+    // Referrer share comes off the top; the rest is split between LPs and protocol
+    let referral_fee = match ctx.accounts.referrer_account.as_mut() {
+        Some(referrer) => accrue_referral(exchange, referrer, fee)?,
+        None => 0,
+    };
+    let (lp_fee, protocol_fee) =
+        split_trading_fee(fee - referral_fee, ctx.accounts.lp_pool.fee_share_bps)?;
+    let total_in = params
+        .collateral
+        .checked_add(fee)
+        .ok_or(ErrorCode::MathOverflow)?;
 
-    // Transfer collateral from user to vault
+    // Transfer collateral and fee from user to vault. Delegates spend through
+    // the user account PDA, which the owner approves as SPL token delegate
+    let sub_account_id = ctx.accounts.user_account.sub_account_id.to_le_bytes();
+    let owner_key = ctx.accounts.owner.key();
+    let user_seeds = &[
+        USER_ACCOUNT_SEED,
+        owner_key.as_ref(),
+        sub_account_seed(&sub_account_id),
+        &[ctx.accounts.user_account.bump],
+    ];
+    let signer_seeds = &[&user_seeds[..]];
+    let transfer_authority = match delegate {
+        Some(_) => ctx.accounts.user_account.to_account_info(),
+        None => ctx.accounts.authority.to_account_info(),
+    };
     anchor_spl::token::transfer(
-        CpiContext::new(
+        CpiContext::new_with_signer(
             ctx.accounts.token_program.to_account_info(),
             anchor_spl::token::Transfer {
                 from: ctx.accounts.user_token_account.to_account_info(),
                 to: ctx.accounts.vault_token_account.to_account_info(),
-                authority: ctx.accounts.owner.to_account_info(),
+                authority: transfer_authority,
             },
+            signer_seeds,
         ),
-        params.collateral,
+        total_in,
     )?;
 
+    let vault_state = &mut ctx.accounts.vault_state;
+    vault_state.credit(VaultBucket::PerpMargin, params.collateral)?;
+    vault_state.credit(VaultBucket::PerpLiquidity, lp_fee)?;
+    vault_state.credit(VaultBucket::Fees, protocol_fee)?;
+    vault_state.credit(VaultBucket::Referral, referral_fee)?;
+    ctx.accounts.vault_token_account.reload()?;
+    ctx.accounts
+        .vault_state
+        .assert_backed(ctx.accounts.vault_token_account.amount)?;
+
     // Compute liquidation price
     let liq_price = compute_liquidation_price(
+        market.contract_type,
         &side,
         oracle_price.price,
         params.collateral,
         params.size,
+        tier.maintenance_margin_bps,
     )?;
 
     // Update market OI
@@ -179,15 +330,19 @@ pub fn handler(ctx: Context<OpenPosition>, params: OpenPositionParams) -> Result
         }
     }
 
+    track_open_notional(market, &side, params.size, oracle_price.price)?;
+
     // Funding snapshot
     let funding_snapshot = match side {
         PositionSide::Long => market.cumulative_funding_long,
         PositionSide::Short => market.cumulative_funding_short,
     };
+    track_open_funding(market, &side, params.size, funding_snapshot)?;
 
     // Initialize position
     let position = &mut ctx.accounts.perp_position;
     position.owner = ctx.accounts.owner.key();
+    position.user_account = ctx.accounts.user_account.key();
     position.market = ctx.accounts.perp_market.key();
     position.bump = ctx.bumps.perp_position;
     position.side = side;
@@ -201,7 +356,19 @@ pub fn handler(ctx: Context<OpenPosition>, params: OpenPositionParams) -> Result
     position.unrealized_pnl = 0;
     position.opened_at = clock.unix_timestamp;
     position.last_updated = clock.unix_timestamp;
-    position._reserved = [0u8; 64];
+    position._reserved = [0u8; 32];
+
+    // Update per-market exposure
+    let market_key = ctx.accounts.perp_market.key();
+    let stats = &mut ctx.accounts.user_market_stats;
+    if stats.owner == Pubkey::default() {
+        stats.owner = ctx.accounts.owner.key();
+        stats.market = market_key;
+        stats.bump = ctx.bumps.user_market_stats;
+        stats._reserved = [0u8; 32];
+    }
+    track_user_open(stats, &side, params.size)?;
+    stats.last_updated = clock.unix_timestamp;
 
     // Update user account
     let user = &mut ctx.accounts.user_account;
@@ -213,10 +380,11 @@ pub fn handler(ctx: Context<OpenPosition>, params: OpenPositionParams) -> Result
         .total_trades
         .checked_add(1)
         .ok_or(ErrorCode::MathOverflow)?;
-    user.total_volume = user
-        .total_volume
-        .checked_add(notional as u64)
+    user.total_fees_paid = user
+        .total_fees_paid
+        .checked_add(fee)
         .ok_or(ErrorCode::MathOverflow)?;
+    record_volume(user, notional, clock.unix_timestamp)?;
     user.last_activity = clock.unix_timestamp;
 
     emit!(PerpPositionOpened {
@@ -227,6 +395,8 @@ pub fn handler(ctx: Context<OpenPosition>, params: OpenPositionParams) -> Result
         collateral: params.collateral,
         entry_price: oracle_price.price,
         leverage: leverage as u64,
+        fee,
+        fee_tier,
         timestamp: clock.unix_timestamp,
     });
 
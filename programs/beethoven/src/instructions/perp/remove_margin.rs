@@ -0,0 +1,211 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::error::ErrorCode;
+use crate::events::PerpMarginRemoved;
+use crate::math::funding::{compute_position_funding, funding_in_collateral};
+use crate::math::liquidation::{
+    compute_liquidation_price, compute_pnl, position_notional, resolve_margin_tier,
+};
+use crate::math::oracle::get_price;
+use crate::math::twap::record_price_observation;
+use crate::state::{Exchange, PerpMarket, PerpPosition, PriceAccumulator, VaultBucket, VaultState};
+use crate::state::perp_position::PositionSide;
+
+use anchor_spl::token::{TokenAccount, Token};
+
+#[derive(Accounts)]
+pub struct RemoveMargin<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [EXCHANGE_SEED],
+        bump = exchange.bump,
+    )]
+    pub exchange: Box<Account<'info, Exchange>>,
+
+    #[account(
+        mut,
+        seeds = [PERP_MARKET_SEED, &perp_market.market_index.to_le_bytes()],
+        bump = perp_market.bump,
+    )]
+    pub perp_market: Box<Account<'info, PerpMarket>>,
+
+    #[account(
+        mut,
+        constraint = perp_position.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = perp_position.market == perp_market.key() @ ErrorCode::PositionNotFound,
+    )]
+    pub perp_position: Box<Account<'info, PerpPosition>>,
+
+    /// CHECK: Pyth oracle price feed
+    #[account(
+        constraint = oracle.key() == perp_market.oracle @ ErrorCode::OracleAccountMismatch,
+    )]
+    pub oracle: UncheckedAccount<'info>,
+
+    /// TWAP/EMA accumulator for the market oracle, observed on every call
+    #[account(
+        mut,
+        seeds = [PRICE_ACCUMULATOR_SEED, perp_market.oracle.as_ref()],
+        bump = price_accumulator.bump,
+    )]
+    pub price_accumulator: Box<Account<'info, PriceAccumulator>>,
+
+    #[account(
+        mut,
+        seeds = [PERP_VAULT_SEED, perp_market.collateral_mint.as_ref()],
+        bump = vault_state.bump,
+    )]
+    pub vault_state: Box<Account<'info, VaultState>>,
+
+    #[account(
+        mut,
+        constraint = vault_token_account.key() == vault_state.token_account @ ErrorCode::InvalidParameter,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = user_token_account.owner == owner.key() @ ErrorCode::Unauthorized,
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn handler(ctx: Context<RemoveMargin>, amount: u64) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    let clock = Clock::get()?;
+
+    require!(
+        amount < ctx.accounts.perp_position.collateral,
+        ErrorCode::InsufficientCollateral
+    );
+
+    let oracle_price = get_price(&ctx.accounts.oracle.to_account_info(), &clock)?;
+
+    record_price_observation(
+        &mut ctx.accounts.price_accumulator,
+        oracle_price.price,
+        clock.unix_timestamp,
+    )?;
+
+    // Volatility halt: refresh the breaker before any risk checks
+    let market_key = ctx.accounts.perp_market.key();
+    let breaker = ctx.accounts.perp_market.refresh_circuit_breaker(
+        market_key,
+        oracle_price.price,
+        clock.unix_timestamp,
+    )?;
+    if breaker.tripped {
+        // Keep the trip, which an error would roll back
+        return Ok(());
+    }
+    require!(!breaker.reduce_only, ErrorCode::MarketReduceOnly);
+
+    let position = &ctx.accounts.perp_position;
+    let market = &ctx.accounts.perp_market;
+
+    let pnl = compute_pnl(
+        market.contract_type,
+        &position.side,
+        position.size,
+        position.entry_price,
+        oracle_price.price,
+    )?;
+
+    let funding_payment = compute_position_funding(
+        position.size,
+        position.side == PositionSide::Long,
+        market.cumulative_funding_long,
+        market.cumulative_funding_short,
+        position.cumulative_funding_snapshot,
+    )?;
+    let funding_payment =
+        funding_in_collateral(market.contract_type, funding_payment, oracle_price.price)?;
+
+    // Equity left after the withdrawal = remaining collateral + pnl - funding
+    let new_collateral = position.collateral - amount;
+    let new_equity = (new_collateral as i64)
+        .checked_add(pnl)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_sub(funding_payment)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(new_equity > 0, ErrorCode::MarginRemovalExceedsLeverage);
+
+    // Remaining position must satisfy the initial margin of its current tier
+    let notional = position_notional(market.contract_type, position.size, oracle_price.price)?;
+    let tier = resolve_margin_tier(market, notional);
+    require!(
+        notional <= tier.max_notional,
+        ErrorCode::PositionExceedsMarginTiers
+    );
+    let max_lev = tier.max_leverage.min(ctx.accounts.exchange.max_leverage);
+    let new_leverage = notional / new_equity as u64;
+    let max_notional = (new_equity as u128)
+        .checked_mul(max_lev as u128)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(
+        notional as u128 <= max_notional,
+        ErrorCode::MarginRemovalExceedsLeverage
+    );
+
+    let liq_price = compute_liquidation_price(
+        market.contract_type,
+        &position.side,
+        position.entry_price,
+        new_collateral,
+        position.size,
+        tier.maintenance_margin_bps,
+    )?;
+
+    ctx.accounts
+        .vault_state
+        .debit(VaultBucket::PerpMargin, amount)?;
+
+    let mint_key = market.collateral_mint;
+    let seeds = &[
+        PERP_VAULT_SEED,
+        mint_key.as_ref(),
+        &[ctx.accounts.vault_state.bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    anchor_spl::token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            anchor_spl::token::Transfer {
+                from: ctx.accounts.vault_token_account.to_account_info(),
+                to: ctx.accounts.user_token_account.to_account_info(),
+                authority: ctx.accounts.vault_state.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )?;
+
+    ctx.accounts.vault_token_account.reload()?;
+    ctx.accounts
+        .vault_state
+        .assert_backed(ctx.accounts.vault_token_account.amount)?;
+
+    let position_key = ctx.accounts.perp_position.key();
+    let position = &mut ctx.accounts.perp_position;
+    position.collateral = new_collateral;
+    position.leverage = new_leverage;
+    position.liquidation_price = liq_price;
+    position.last_updated = clock.unix_timestamp;
+
+    emit!(PerpMarginRemoved {
+        user: ctx.accounts.owner.key(),
+        market: ctx.accounts.perp_market.key(),
+        position: position_key,
+        amount,
+        new_collateral,
+        new_leverage,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,150 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount, Transfer};
+use crate::constants::*;
+use crate::error::ErrorCode;
+use crate::events::PerpLiquidityWithdrawn;
+use crate::math::perp_pool::{compute_pool_nav, load_pool_markets, pool_nav_per_share};
+use crate::state::{PerpLpPool, VaultBucket, VaultState};
+
+#[derive(Accounts)]
+pub struct WithdrawPerpLiquidity<'info> {
+    #[account(mut)]
+    pub withdrawer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [PERP_LP_POOL_SEED, lp_pool.quote_mint.as_ref()],
+        bump = lp_pool.bump,
+    )]
+    pub lp_pool: Box<Account<'info, PerpLpPool>>,
+
+    #[account(
+        mut,
+        seeds = [PERP_VAULT_SEED, lp_pool.quote_mint.as_ref()],
+        bump = vault_state.bump,
+    )]
+    pub vault_state: Box<Account<'info, VaultState>>,
+
+    #[account(
+        mut,
+        constraint = vault_token_account.key() == vault_state.token_account @ ErrorCode::InvalidParameter,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = lp_mint.key() == lp_pool.lp_mint @ ErrorCode::InvalidParameter,
+    )]
+    pub lp_mint: Account<'info, Mint>,
+
+    /// Withdrawer's LP token account
+    #[account(
+        mut,
+        constraint = user_lp_account.mint == lp_pool.lp_mint @ ErrorCode::InvalidParameter,
+        constraint = user_lp_account.owner == withdrawer.key() @ ErrorCode::Unauthorized,
+    )]
+    pub user_lp_account: Account<'info, TokenAccount>,
+
+    /// Withdrawer's quote token account
+    #[account(
+        mut,
+        constraint = user_token_account.mint == lp_pool.quote_mint @ ErrorCode::InvalidParameter,
+        constraint = user_token_account.owner == withdrawer.key() @ ErrorCode::Unauthorized,
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// remaining_accounts: [PerpMarket, Oracle] for every market in the pool.
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, WithdrawPerpLiquidity<'info>>,
+    shares: u64,
+) -> Result<()> {
+    require!(shares > 0, ErrorCode::InvalidAmount);
+    require!(
+        ctx.accounts.user_lp_account.amount >= shares,
+        ErrorCode::InsufficientShares
+    );
+
+    let clock = Clock::get()?;
+    let pool = &ctx.accounts.lp_pool;
+
+    // Price the pool, netting out unrealized trader PnL
+    let snapshot = load_pool_markets(pool, ctx.remaining_accounts, &clock)?;
+    let nav = compute_pool_nav(ctx.accounts.vault_state.perp_liquidity_balance, snapshot.trader_pnl)?;
+
+    // amount = shares * nav / total_shares
+    let amount = (shares as u128)
+        .checked_mul(nav as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_div(pool.total_shares as u128)
+        .ok_or(ErrorCode::DivisionByZero)?;
+    let amount = u64::try_from(amount).map_err(|_| ErrorCode::MathOverflow)?;
+    require!(amount > 0, ErrorCode::InvalidAmount);
+
+    // Liquidity backing unrealized trader losses cannot leave before they settle
+    ctx.accounts.vault_state.debit(VaultBucket::PerpLiquidity, amount)?;
+
+    // Burn LP shares from withdrawer
+    token::burn(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.lp_mint.to_account_info(),
+                from: ctx.accounts.user_lp_account.to_account_info(),
+                authority: ctx.accounts.withdrawer.to_account_info(),
+            },
+        ),
+        shares,
+    )?;
+
+    // Transfer quote from the perp vault (vault PDA signs)
+    let quote_mint_key = ctx.accounts.lp_pool.quote_mint;
+    let vault_seeds = &[
+        PERP_VAULT_SEED,
+        quote_mint_key.as_ref(),
+        &[ctx.accounts.vault_state.bump],
+    ];
+    let signer_seeds = &[&vault_seeds[..]];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.vault_token_account.to_account_info(),
+                to: ctx.accounts.user_token_account.to_account_info(),
+                authority: ctx.accounts.vault_state.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )?;
+
+    ctx.accounts.vault_token_account.reload()?;
+    ctx.accounts
+        .vault_state
+        .assert_backed(ctx.accounts.vault_token_account.amount)?;
+
+    // Update pool state
+    let nav_after = nav - amount;
+    let pool = &mut ctx.accounts.lp_pool;
+    pool.total_shares = pool
+        .total_shares
+        .checked_sub(shares)
+        .ok_or(ErrorCode::MathUnderflow)?;
+    pool.total_nav = nav_after;
+    pool.nav_per_share = pool_nav_per_share(nav_after, pool.total_shares)?;
+    pool.last_nav_update = clock.unix_timestamp;
+
+    emit!(PerpLiquidityWithdrawn {
+        pool: pool.key(),
+        withdrawer: ctx.accounts.withdrawer.key(),
+        shares_burned: shares,
+        amount_returned: amount,
+        nav_per_share: pool.nav_per_share,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,91 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::error::ErrorCode;
+use crate::events::SpotOrderCancelled;
+use crate::state::{OpenOrders, OrderBookSide, OrderSide, SpotMarket};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct CancelSpotOrderParams {
+    pub side: OrderSide,
+    pub order_id: u128,
+}
+
+#[derive(Accounts)]
+pub struct CancelSpotOrder<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [SPOT_MARKET_SEED, &spot_market.market_index.to_le_bytes()],
+        bump = spot_market.bump,
+    )]
+    pub spot_market: Box<Account<'info, SpotMarket>>,
+
+    #[account(
+        mut,
+        address = spot_market.bids @ ErrorCode::InvalidParameter,
+    )]
+    pub bids: AccountLoader<'info, OrderBookSide>,
+
+    #[account(
+        mut,
+        address = spot_market.asks @ ErrorCode::InvalidParameter,
+    )]
+    pub asks: AccountLoader<'info, OrderBookSide>,
+
+    #[account(
+        mut,
+        seeds = [OPEN_ORDERS_SEED, open_orders.user_account.as_ref(), spot_market.key().as_ref()],
+        bump = open_orders.bump,
+        constraint = open_orders.owner == owner.key() @ ErrorCode::Unauthorized,
+    )]
+    pub open_orders: Box<Account<'info, OpenOrders>>,
+}
+
+/// Remove a resting order and free its locked funds. Fills already queued
+/// for the order are unaffected and still settle through the crank.
+pub fn handler(ctx: Context<CancelSpotOrder>, params: CancelSpotOrderParams) -> Result<()> {
+    let open_orders_key = ctx.accounts.open_orders.key();
+    let base_lot_size = ctx.accounts.spot_market.base_lot_size;
+
+    let order = {
+        let mut book = match params.side {
+            OrderSide::Bid => ctx.accounts.bids.load_mut()?,
+            OrderSide::Ask => ctx.accounts.asks.load_mut()?,
+        };
+        let handle = book
+            .find(params.order_id)
+            .ok_or(ErrorCode::OrderNotFound)?;
+        require!(
+            book.leaf(handle).owner == open_orders_key,
+            ErrorCode::OrderNotFound
+        );
+        book.remove(params.order_id).ok_or(ErrorCode::OrderNotFound)?
+    };
+
+    let (base, quote) = match params.side {
+        OrderSide::Bid => (0, order.locked_quote),
+        OrderSide::Ask => (
+            order
+                .quantity
+                .checked_mul(base_lot_size)
+                .ok_or(ErrorCode::MathOverflow)?,
+            0,
+        ),
+    };
+
+    let open_orders = &mut ctx.accounts.open_orders;
+    open_orders.unlock(base, quote)?;
+    open_orders.order_count = open_orders.order_count.saturating_sub(1);
+
+    emit!(SpotOrderCancelled {
+        owner: ctx.accounts.owner.key(),
+        market: ctx.accounts.spot_market.key(),
+        side: params.side,
+        order_id: params.order_id,
+        base_lots: order.quantity,
+        client_order_id: order.client_order_id,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
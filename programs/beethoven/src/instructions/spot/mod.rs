@@ -0,0 +1,7 @@
+pub mod place_spot_order;
+pub mod cancel_spot_order;
+pub mod settle_spot_funds;
+
+pub use place_spot_order::*;
+pub use cancel_spot_order::*;
+pub use settle_spot_funds::*;
@@ -0,0 +1,379 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::error::ErrorCode;
+use crate::events::SpotOrderPlaced;
+use crate::math::fee_tiers::record_volume;
+use crate::math::fixed_point::bps_mul;
+use crate::math::matching::{match_order, would_cross, MatchParams, MatchResult};
+use crate::state::{
+    order_key, sub_account_seed, EventQueue, Exchange, LeafOrder, OpenOrders, OrderBookSide,
+    OrderSide, OrderType, SpotMarket, UserAccount, VaultBucket, VaultState,
+};
+
+use anchor_spl::token::{Token, TokenAccount};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct PlaceSpotOrderParams {
+    pub side: OrderSide,
+    pub order_type: OrderType,
+    pub price: u64,     // Quote atoms per base lot, a multiple of tick_size
+    pub base_lots: u64,
+    pub client_order_id: u64,
+}
+
+#[derive(Accounts)]
+pub struct PlaceSpotOrder<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [EXCHANGE_SEED],
+        bump = exchange.bump,
+        constraint = !exchange.swap_paused @ ErrorCode::ExchangePaused,
+    )]
+    pub exchange: Box<Account<'info, Exchange>>,
+
+    #[account(
+        mut,
+        seeds = [
+            USER_ACCOUNT_SEED,
+            owner.key().as_ref(),
+            sub_account_seed(&user_account.sub_account_id.to_le_bytes()),
+        ],
+        bump = user_account.bump,
+        constraint = user_account.owner == owner.key() @ ErrorCode::Unauthorized,
+    )]
+    pub user_account: Box<Account<'info, UserAccount>>,
+
+    #[account(
+        mut,
+        seeds = [SPOT_MARKET_SEED, &spot_market.market_index.to_le_bytes()],
+        bump = spot_market.bump,
+        constraint = spot_market.exchange == exchange.key() @ ErrorCode::InvalidParameter,
+        constraint = !spot_market.paused @ ErrorCode::ExchangePaused,
+    )]
+    pub spot_market: Box<Account<'info, SpotMarket>>,
+
+    #[account(
+        mut,
+        address = spot_market.bids @ ErrorCode::InvalidParameter,
+    )]
+    pub bids: AccountLoader<'info, OrderBookSide>,
+
+    #[account(
+        mut,
+        address = spot_market.asks @ ErrorCode::InvalidParameter,
+    )]
+    pub asks: AccountLoader<'info, OrderBookSide>,
+
+    #[account(
+        mut,
+        address = spot_market.event_queue @ ErrorCode::InvalidParameter,
+    )]
+    pub event_queue: AccountLoader<'info, EventQueue>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = OpenOrders::LEN,
+        seeds = [OPEN_ORDERS_SEED, user_account.key().as_ref(), spot_market.key().as_ref()],
+        bump,
+    )]
+    pub open_orders: Box<Account<'info, OpenOrders>>,
+
+    #[account(
+        mut,
+        address = spot_market.base_vault @ ErrorCode::InvalidParameter,
+    )]
+    pub base_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        address = spot_market.quote_vault @ ErrorCode::InvalidParameter,
+    )]
+    pub quote_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = user_base_token_account.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = user_base_token_account.mint == spot_market.base_mint @ ErrorCode::InvalidParameter,
+    )]
+    pub user_base_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = user_quote_token_account.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = user_quote_token_account.mint == spot_market.quote_mint @ ErrorCode::InvalidParameter,
+    )]
+    pub user_quote_token_account: Box<Account<'info, TokenAccount>>,
+
+    /// Quote mint's vault; maker and taker fees go to its fee bucket
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, spot_market.quote_mint.as_ref()],
+        bump = fee_vault_state.bump,
+    )]
+    pub fee_vault_state: Box<Account<'info, VaultState>>,
+
+    #[account(
+        mut,
+        constraint = fee_vault_token_account.key() == fee_vault_state.token_account @ ErrorCode::InvalidParameter,
+    )]
+    pub fee_vault_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<PlaceSpotOrder>, params: PlaceSpotOrderParams) -> Result<()> {
+    let market = &ctx.accounts.spot_market;
+    require!(
+        params.price > 0 && params.price.is_multiple_of(market.tick_size) && params.base_lots > 0,
+        ErrorCode::InvalidOrder
+    );
+
+    // Capture keys before mutable borrows
+    let market_key = ctx.accounts.spot_market.key();
+    let open_orders_key = ctx.accounts.open_orders.key();
+    let base_lot_size = market.base_lot_size;
+    let maker_fee_bps = market.maker_fee_bps;
+    let clock = Clock::get()?;
+
+    let open_orders = &mut ctx.accounts.open_orders;
+    if open_orders.owner == Pubkey::default() {
+        open_orders.owner = ctx.accounts.owner.key();
+        open_orders.user_account = ctx.accounts.user_account.key();
+        open_orders.market = market_key;
+        open_orders.bump = ctx.bumps.open_orders;
+        open_orders._reserved = [0u8; 32];
+    }
+
+    // Take liquidity from the opposite side
+    let result = {
+        let mut book = match params.side {
+            OrderSide::Bid => ctx.accounts.asks.load_mut()?,
+            OrderSide::Ask => ctx.accounts.bids.load_mut()?,
+        };
+        if params.order_type == OrderType::PostOnly {
+            require!(
+                !would_cross(&book, params.side, params.price),
+                ErrorCode::PostOnlyWouldCross
+            );
+            MatchResult::default()
+        } else {
+            let mut queue = ctx.accounts.event_queue.load_mut()?;
+            match_order(
+                &mut book,
+                &mut queue,
+                &MatchParams {
+                    side: params.side,
+                    limit_price: params.price,
+                    base_lots: params.base_lots,
+                    base_lot_size,
+                    maker_fee_bps,
+                    taker_fee_bps: market.taker_fee_bps,
+                    taker: open_orders_key,
+                    timestamp: clock.unix_timestamp,
+                },
+            )?
+        }
+    };
+
+    // Own orders cancelled by self-trade prevention
+    if result.self_trade_orders > 0 {
+        open_orders.unlock(result.self_trade_base, result.self_trade_quote)?;
+        open_orders.order_count = open_orders
+            .order_count
+            .saturating_sub(result.self_trade_orders);
+    }
+
+    // Rest the remainder of limit and post-only orders
+    let resting_lots = params.base_lots - result.base_lots;
+    let mut order_id: u128 = 0;
+    let mut rest_base = 0u64;
+    let mut rest_quote = 0u64;
+    if resting_lots > 0 && params.order_type != OrderType::ImmediateOrCancel {
+        let market = &mut ctx.accounts.spot_market;
+        order_id = order_key(params.side, params.price, market.seq_num);
+        market.seq_num += 1;
+
+        // Bids lock their maker fee with the notional
+        match params.side {
+            OrderSide::Bid => {
+                let notional = resting_lots
+                    .checked_mul(params.price)
+                    .ok_or(ErrorCode::MathOverflow)?;
+                rest_quote = notional
+                    .checked_add(bps_mul(notional, maker_fee_bps)?)
+                    .ok_or(ErrorCode::MathOverflow)?;
+            }
+            OrderSide::Ask => {
+                rest_base = resting_lots
+                    .checked_mul(base_lot_size)
+                    .ok_or(ErrorCode::MathOverflow)?;
+            }
+        }
+
+        let mut book = match params.side {
+            OrderSide::Bid => ctx.accounts.bids.load_mut()?,
+            OrderSide::Ask => ctx.accounts.asks.load_mut()?,
+        };
+        book.insert(&LeafOrder {
+            key: order_id,
+            owner: open_orders_key,
+            quantity: resting_lots,
+            locked_quote: rest_quote,
+            client_order_id: params.client_order_id,
+            timestamp: clock.unix_timestamp,
+        })?;
+
+        open_orders.base_locked = open_orders
+            .base_locked
+            .checked_add(rest_base)
+            .ok_or(ErrorCode::MathOverflow)?;
+        open_orders.quote_locked = open_orders
+            .quote_locked
+            .checked_add(rest_quote)
+            .ok_or(ErrorCode::MathOverflow)?;
+        open_orders.order_count += 1;
+    }
+
+    // Settle the taker: pay in what was bought with or locked, pay out fills
+    let filled_base = result
+        .base_lots
+        .checked_mul(base_lot_size)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let (pay_in, pay_out) = match params.side {
+        OrderSide::Bid => (
+            result
+                .quote_amount
+                .checked_add(result.taker_fee)
+                .and_then(|sum| sum.checked_add(rest_quote))
+                .ok_or(ErrorCode::MathOverflow)?,
+            filled_base,
+        ),
+        OrderSide::Ask => (
+            filled_base
+                .checked_add(rest_base)
+                .ok_or(ErrorCode::MathOverflow)?,
+            result
+                .quote_amount
+                .checked_sub(result.taker_fee)
+                .ok_or(ErrorCode::MathUnderflow)?,
+        ),
+    };
+    let (pay_in_from, pay_in_to, pay_out_from, pay_out_to) = match params.side {
+        OrderSide::Bid => (
+            ctx.accounts.user_quote_token_account.to_account_info(),
+            ctx.accounts.quote_vault.to_account_info(),
+            ctx.accounts.base_vault.to_account_info(),
+            ctx.accounts.user_base_token_account.to_account_info(),
+        ),
+        OrderSide::Ask => (
+            ctx.accounts.user_base_token_account.to_account_info(),
+            ctx.accounts.base_vault.to_account_info(),
+            ctx.accounts.quote_vault.to_account_info(),
+            ctx.accounts.user_quote_token_account.to_account_info(),
+        ),
+    };
+
+    if pay_in > 0 {
+        anchor_spl::token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                anchor_spl::token::Transfer {
+                    from: pay_in_from,
+                    to: pay_in_to,
+                    authority: ctx.accounts.owner.to_account_info(),
+                },
+            ),
+            pay_in,
+        )?;
+    }
+
+    let index_bytes = ctx.accounts.spot_market.market_index.to_le_bytes();
+    let seeds = &[
+        SPOT_MARKET_SEED,
+        index_bytes.as_ref(),
+        &[ctx.accounts.spot_market.bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    if pay_out > 0 {
+        anchor_spl::token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                anchor_spl::token::Transfer {
+                    from: pay_out_from,
+                    to: pay_out_to,
+                    authority: ctx.accounts.spot_market.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            pay_out,
+        )?;
+    }
+
+    // Both fees are in quote and leave the market for the protocol vault
+    let fees = result
+        .taker_fee
+        .checked_add(result.maker_fee)
+        .ok_or(ErrorCode::MathOverflow)?;
+    if fees > 0 {
+        anchor_spl::token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                anchor_spl::token::Transfer {
+                    from: ctx.accounts.quote_vault.to_account_info(),
+                    to: ctx.accounts.fee_vault_token_account.to_account_info(),
+                    authority: ctx.accounts.spot_market.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            fees,
+        )?;
+
+        ctx.accounts.fee_vault_state.credit(VaultBucket::Fees, fees)?;
+        ctx.accounts.fee_vault_token_account.reload()?;
+        ctx.accounts
+            .fee_vault_state
+            .assert_backed(ctx.accounts.fee_vault_token_account.amount)?;
+    }
+
+    // Market and user stats
+    let market = &mut ctx.accounts.spot_market;
+    market.base_volume = market.base_volume.saturating_add(result.base_lots);
+    market.quote_volume = market.quote_volume.saturating_add(result.quote_amount);
+    market.fees_collected = market.fees_collected.saturating_add(fees);
+
+    let user = &mut ctx.accounts.user_account;
+    if result.base_lots > 0 {
+        user.total_trades = user
+            .total_trades
+            .checked_add(1)
+            .ok_or(ErrorCode::MathOverflow)?;
+        user.total_fees_paid = user
+            .total_fees_paid
+            .checked_add(result.taker_fee)
+            .ok_or(ErrorCode::MathOverflow)?;
+        record_volume(user, result.quote_amount, clock.unix_timestamp)?;
+    }
+    user.last_activity = clock.unix_timestamp;
+
+    emit!(SpotOrderPlaced {
+        owner: ctx.accounts.owner.key(),
+        market: market_key,
+        side: params.side,
+        order_type: params.order_type,
+        price: params.price,
+        base_lots: params.base_lots,
+        filled_base_lots: result.base_lots,
+        filled_quote: result.quote_amount,
+        taker_fee: result.taker_fee,
+        order_id,
+        client_order_id: params.client_order_id,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,112 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::error::ErrorCode;
+use crate::events::SpotFundsSettled;
+use crate::state::{OpenOrders, SpotMarket};
+
+use anchor_spl::token::{Token, TokenAccount};
+
+#[derive(Accounts)]
+pub struct SettleSpotFunds<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [SPOT_MARKET_SEED, &spot_market.market_index.to_le_bytes()],
+        bump = spot_market.bump,
+    )]
+    pub spot_market: Box<Account<'info, SpotMarket>>,
+
+    #[account(
+        mut,
+        seeds = [OPEN_ORDERS_SEED, open_orders.user_account.as_ref(), spot_market.key().as_ref()],
+        bump = open_orders.bump,
+        constraint = open_orders.owner == owner.key() @ ErrorCode::Unauthorized,
+    )]
+    pub open_orders: Box<Account<'info, OpenOrders>>,
+
+    #[account(
+        mut,
+        address = spot_market.base_vault @ ErrorCode::InvalidParameter,
+    )]
+    pub base_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        address = spot_market.quote_vault @ ErrorCode::InvalidParameter,
+    )]
+    pub quote_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = user_base_token_account.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = user_base_token_account.mint == spot_market.base_mint @ ErrorCode::InvalidParameter,
+    )]
+    pub user_base_token_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = user_quote_token_account.owner == owner.key() @ ErrorCode::Unauthorized,
+        constraint = user_quote_token_account.mint == spot_market.quote_mint @ ErrorCode::InvalidParameter,
+    )]
+    pub user_quote_token_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Withdraw maker proceeds and refunds of cancelled orders.
+pub fn handler(ctx: Context<SettleSpotFunds>) -> Result<()> {
+    let base_amount = ctx.accounts.open_orders.base_free;
+    let quote_amount = ctx.accounts.open_orders.quote_free;
+
+    let index_bytes = ctx.accounts.spot_market.market_index.to_le_bytes();
+    let seeds = &[
+        SPOT_MARKET_SEED,
+        index_bytes.as_ref(),
+        &[ctx.accounts.spot_market.bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    if base_amount > 0 {
+        anchor_spl::token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                anchor_spl::token::Transfer {
+                    from: ctx.accounts.base_vault.to_account_info(),
+                    to: ctx.accounts.user_base_token_account.to_account_info(),
+                    authority: ctx.accounts.spot_market.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            base_amount,
+        )?;
+    }
+
+    if quote_amount > 0 {
+        anchor_spl::token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                anchor_spl::token::Transfer {
+                    from: ctx.accounts.quote_vault.to_account_info(),
+                    to: ctx.accounts.user_quote_token_account.to_account_info(),
+                    authority: ctx.accounts.spot_market.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            quote_amount,
+        )?;
+    }
+
+    let open_orders = &mut ctx.accounts.open_orders;
+    open_orders.base_free = 0;
+    open_orders.quote_free = 0;
+
+    emit!(SpotFundsSettled {
+        owner: ctx.accounts.owner.key(),
+        market: ctx.accounts.spot_market.key(),
+        base_amount,
+        quote_amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -2,12 +2,13 @@ use anchor_lang::prelude::*;
 use crate::constants::*;
 use crate::error::ErrorCode;
 use crate::events::LiquidityAdded;
-use crate::adapters::deposit_adapter;
-use crate::state::{Exchange, UserAccount};
+use crate::adapters::deposit_adapter::{self, Depositor};
+use crate::state::{sub_account_seed, Exchange, RegistryAccount, UserAccount, YieldPosition};
 
 use anchor_spl::token::{TokenAccount, Token};
 
 #[derive(Accounts)]
+#[instruction(amount: u64, protocol: Pubkey)]
 pub struct AddLiquidity<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
@@ -21,50 +22,115 @@ pub struct AddLiquidity<'info> {
 
     #[account(
         mut,
-        seeds = [USER_ACCOUNT_SEED, user.key().as_ref()],
+        seeds = [
+            USER_ACCOUNT_SEED,
+            user.key().as_ref(),
+            sub_account_seed(&user_account.sub_account_id.to_le_bytes()),
+        ],
         bump = user_account.bump,
         constraint = user_account.owner == user.key() @ ErrorCode::Unauthorized,
     )]
     pub user_account: Account<'info, UserAccount>,
 
     /// User's token account for the deposit
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = user_token_account.owner == user.key() @ ErrorCode::Unauthorized,
+    )]
     pub user_token_account: Account<'info, TokenAccount>,
 
+    /// The user's position in `protocol` for this mint
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = YieldPosition::LEN,
+        seeds = [
+            YIELD_POSITION_SEED,
+            user_account.key().as_ref(),
+            protocol.as_ref(),
+            user_token_account.mint.as_ref(),
+        ],
+        bump,
+    )]
+    pub yield_position: Box<Account<'info, YieldPosition>>,
+
+    /// CHECK: Runtime protocol allowlist and volume caps, loaded by
+    /// `RegistryAccount`; may be uninitialized, and is only written when
+    /// passed writable for a capped venue
+    #[account(seeds = [PROTOCOL_REGISTRY_SEED], bump)]
+    pub protocol_registry: UncheckedAccount<'info>,
+
     pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
 }
 
 pub fn handler<'info>(
     ctx: Context<'_, '_, 'info, 'info, AddLiquidity<'info>>,
     amount: u64,
+    protocol: Pubkey,
 ) -> Result<()> {
     require!(amount > 0, ErrorCode::InvalidAmount);
+    require!(
+        !ctx.remaining_accounts.is_empty(),
+        ErrorCode::UnsupportedProtocol
+    );
+    require_keys_eq!(
+        ctx.remaining_accounts[0].key(),
+        protocol,
+        ErrorCode::ProtocolAccountMismatch
+    );
 
     let clock = Clock::get()?;
+    let pre_balance = ctx.accounts.user_token_account.amount;
+
+    // Execute deposit via protocol adapter, spending from the user and
+    // minting receipts to an account the user owns
+    let mut registry = RegistryAccount::load(ctx.accounts.protocol_registry.to_account_info())?;
+    let receipt_amount = deposit_adapter::execute_deposit(
+        &mut registry,
+        ctx.remaining_accounts,
+        &ctx.accounts.user_token_account.mint,
+        amount,
+        Some(Depositor {
+            token_account: ctx.accounts.user_token_account.key(),
+            authority: ctx.accounts.user.key(),
+        }),
+        &[],
+    )?;
 
-    // Execute deposit via protocol adapter
-    deposit_adapter::execute_deposit(ctx.remaining_accounts, amount)?;
+    // Principal is what actually left the user's account
+    ctx.accounts.user_token_account.reload()?;
+    let deposited = pre_balance
+        .checked_sub(ctx.accounts.user_token_account.amount)
+        .ok_or(ErrorCode::MathUnderflow)?;
+    require!(deposited > 0, ErrorCode::InvalidAmount);
+
+    let position = &mut ctx.accounts.yield_position;
+    if position.owner == Pubkey::default() {
+        position.owner = ctx.accounts.user.key();
+        position.user_account = ctx.accounts.user_account.key();
+        position.protocol = protocol;
+        position.mint = ctx.accounts.user_token_account.mint;
+        position.bump = ctx.bumps.yield_position;
+    }
+    position.record_deposit(deposited, receipt_amount, clock.unix_timestamp)?;
 
     // Update user stats
     let user = &mut ctx.accounts.user_account;
     user.total_volume = user
         .total_volume
-        .checked_add(amount as u64)
+        .checked_add(deposited)
         .ok_or(ErrorCode::MathOverflow)?;
     user.last_activity = clock.unix_timestamp;
 
-    // Get protocol from remaining accounts for event
-    let protocol = if !ctx.remaining_accounts.is_empty() {
-        *ctx.remaining_accounts[0].key
-    } else {
-        Pubkey::default()
-    };
-
     emit!(LiquidityAdded {
         user: ctx.accounts.user.key(),
         mint: ctx.accounts.user_token_account.mint,
-        amount,
+        amount: deposited,
         protocol,
+        position: ctx.accounts.yield_position.key(),
+        receipt_amount,
+        principal: ctx.accounts.yield_position.principal,
         timestamp: clock.unix_timestamp,
     });
 
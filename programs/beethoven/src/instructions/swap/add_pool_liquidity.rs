@@ -0,0 +1,169 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount, Transfer};
+use crate::constants::*;
+use crate::error::ErrorCode;
+use crate::events::PoolLiquidityAdded;
+use crate::math::amm::deposit_shares;
+use crate::state::{Exchange, SwapPool};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct AddPoolLiquidityParams {
+    pub max_amount_a: u64,
+    pub max_amount_b: u64,
+    pub min_shares: u64,
+}
+
+#[derive(Accounts)]
+pub struct AddPoolLiquidity<'info> {
+    pub depositor: Signer<'info>,
+
+    #[account(
+        seeds = [EXCHANGE_SEED],
+        bump = exchange.bump,
+        constraint = !exchange.swap_paused @ ErrorCode::ExchangePaused,
+    )]
+    pub exchange: Box<Account<'info, Exchange>>,
+
+    #[account(
+        mut,
+        seeds = [SWAP_POOL_SEED, pool.mint_a.as_ref(), pool.mint_b.as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, SwapPool>>,
+
+    #[account(
+        mut,
+        address = pool.vault_a @ ErrorCode::PoolAccountMismatch,
+    )]
+    pub vault_a: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        address = pool.vault_b @ ErrorCode::PoolAccountMismatch,
+    )]
+    pub vault_b: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        address = pool.lp_mint @ ErrorCode::PoolAccountMismatch,
+    )]
+    pub lp_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        mut,
+        constraint = user_token_a.mint == pool.mint_a @ ErrorCode::InvalidParameter,
+        constraint = user_token_a.owner == depositor.key() @ ErrorCode::Unauthorized,
+    )]
+    pub user_token_a: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = user_token_b.mint == pool.mint_b @ ErrorCode::InvalidParameter,
+        constraint = user_token_b.owner == depositor.key() @ ErrorCode::Unauthorized,
+    )]
+    pub user_token_b: Box<Account<'info, TokenAccount>>,
+
+    /// Depositor's LP token account
+    #[account(
+        mut,
+        constraint = user_lp_account.mint == pool.lp_mint @ ErrorCode::InvalidParameter,
+        constraint = user_lp_account.owner == depositor.key() @ ErrorCode::Unauthorized,
+    )]
+    pub user_lp_account: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn handler(ctx: Context<AddPoolLiquidity>, params: AddPoolLiquidityParams) -> Result<()> {
+    require!(
+        params.max_amount_a > 0 && params.max_amount_b > 0,
+        ErrorCode::InvalidAmount
+    );
+
+    let pool = &ctx.accounts.pool;
+    let first_deposit = pool.total_shares == 0;
+    let (shares, amount_a, amount_b) = deposit_shares(
+        params.max_amount_a,
+        params.max_amount_b,
+        pool.reserve_a,
+        pool.reserve_b,
+        pool.total_shares,
+    )?;
+
+    // The first deposit locks MINIMUM_POOL_LIQUIDITY shares in the pool for good
+    let shares_to_mint = if first_deposit {
+        shares - MINIMUM_POOL_LIQUIDITY
+    } else {
+        shares
+    };
+    require!(shares_to_mint > 0, ErrorCode::InvalidAmount);
+    require!(
+        shares_to_mint >= params.min_shares,
+        ErrorCode::SlippageExceeded
+    );
+
+    for (from, to, amount) in [
+        (&ctx.accounts.user_token_a, &ctx.accounts.vault_a, amount_a),
+        (&ctx.accounts.user_token_b, &ctx.accounts.vault_b, amount_b),
+    ] {
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: from.to_account_info(),
+                    to: to.to_account_info(),
+                    authority: ctx.accounts.depositor.to_account_info(),
+                },
+            ),
+            amount,
+        )?;
+    }
+
+    // Mint LP shares (pool PDA signs as mint authority)
+    let seeds = &[
+        SWAP_POOL_SEED,
+        pool.mint_a.as_ref(),
+        pool.mint_b.as_ref(),
+        &[pool.bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    token::mint_to(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.lp_mint.to_account_info(),
+                to: ctx.accounts.user_lp_account.to_account_info(),
+                authority: ctx.accounts.pool.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        shares_to_mint,
+    )?;
+
+    // Update pool state
+    let pool = &mut ctx.accounts.pool;
+    pool.reserve_a = pool
+        .reserve_a
+        .checked_add(amount_a)
+        .ok_or(ErrorCode::MathOverflow)?;
+    pool.reserve_b = pool
+        .reserve_b
+        .checked_add(amount_b)
+        .ok_or(ErrorCode::MathOverflow)?;
+    pool.total_shares = pool
+        .total_shares
+        .checked_add(shares)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    emit!(PoolLiquidityAdded {
+        pool: pool.key(),
+        depositor: ctx.accounts.depositor.key(),
+        amount_a,
+        amount_b,
+        shares_minted: shares_to_mint,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,281 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::error::ErrorCode;
+use crate::events::SwapExecuted;
+use crate::math::fixed_point::bps_mul;
+use crate::math::twap::observe_oracle_price;
+use crate::adapters::adapter::SwapRequest;
+use crate::adapters::swap_adapter::{self, SwapMode};
+use crate::instructions::swap::swap_fees::{
+    collect_swap_fee, record_swap, resolve_swap_fee_bps, SwapFeeAccounts,
+};
+use crate::instructions::swap::swap_trader::SwapTrader;
+use crate::state::{
+    sub_account_seed, Exchange, PriceAccumulator, RegistryAccount, ReferrerAccount, UserAccount,
+    VaultState,
+};
+
+use anchor_spl::token::{TokenAccount, Token};
+
+/// One hop of a route. Its accounts are the next `account_count` entries of
+/// remaining_accounts: the protocol program followed by the protocol's own
+/// swap accounts, as execute_swap expects them.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct RouteLeg {
+    pub protocol: Pubkey,  // Protocol program; must match the first account of the slice
+    pub account_count: u8, // Accounts in this leg's slice, program included
+    pub output_index: u8,  // Position in the slice of the user's token account receiving the leg's output
+    pub is_base_in: bool,  // Direction on order-book venues (sell base when true)
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct ExecuteRouteParams {
+    pub amount_in: u64,          // Input spent, fee included
+    pub minimum_amount_out: u64, // Slippage floor on the final leg's output
+    pub legs: Vec<RouteLeg>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteRoute<'info> {
+    /// Owner or their delegate
+    pub authority: Signer<'info>,
+
+    /// CHECK: Account owner, bound by the user_account seeds
+    pub user: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [EXCHANGE_SEED],
+        bump = exchange.bump,
+        constraint = !exchange.swap_paused @ ErrorCode::ExchangePaused,
+    )]
+    pub exchange: Account<'info, Exchange>,
+
+    #[account(
+        mut,
+        seeds = [
+            USER_ACCOUNT_SEED,
+            user.key().as_ref(),
+            sub_account_seed(&user_account.sub_account_id.to_le_bytes()),
+        ],
+        bump = user_account.bump,
+        constraint = user_account.owner == user.key() @ ErrorCode::Unauthorized,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    /// User's token account for the route's first input
+    #[account(
+        mut,
+        constraint = user_input_token_account.owner == user.key() @ ErrorCode::Unauthorized,
+    )]
+    pub user_input_token_account: Account<'info, TokenAccount>,
+
+    /// User's token account for the route's final output
+    #[account(
+        mut,
+        constraint = user_output_token_account.owner == user.key() @ ErrorCode::Unauthorized,
+    )]
+    pub user_output_token_account: Account<'info, TokenAccount>,
+
+    /// Fee vault of the input mint
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, user_input_token_account.mint.as_ref()],
+        bump = vault_state.bump,
+        constraint = vault_state.mint == user_input_token_account.mint @ ErrorCode::FeeVaultMintMismatch,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        constraint = vault_token_account.key() == vault_state.token_account @ ErrorCode::InvalidParameter,
+        constraint = vault_token_account.mint == vault_state.mint @ ErrorCode::FeeVaultMintMismatch,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    /// TWAP/EMA accumulator of the input asset, observed on every swap
+    #[account(
+        mut,
+        seeds = [PRICE_ACCUMULATOR_SEED, accumulator_oracle.key().as_ref()],
+        bump = price_accumulator.bump,
+    )]
+    pub price_accumulator: Box<Account<'info, PriceAccumulator>>,
+
+    /// CHECK: Oracle backing `price_accumulator`, bound by its seeds and parsed by get_price
+    pub accumulator_oracle: UncheckedAccount<'info>,
+
+    /// Optional rewards account of the user's referrer in the fee mint;
+    /// supplying it applies the referee discount
+    #[account(
+        mut,
+        seeds = [REFERRER_SEED, user_account.referrer.as_ref(), user_input_token_account.mint.as_ref()],
+        bump = referrer_account.bump,
+    )]
+    pub referrer_account: Option<Box<Account<'info, ReferrerAccount>>>,
+
+    /// CHECK: Runtime protocol allowlist and volume caps, loaded by
+    /// `RegistryAccount`; may be uninitialized, and is only written when
+    /// passed writable for a capped venue
+    #[account(seeds = [PROTOCOL_REGISTRY_SEED], bump)]
+    pub protocol_registry: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ExecuteRoute<'info>>,
+    params: ExecuteRouteParams,
+) -> Result<()> {
+    require!(params.amount_in > 0, ErrorCode::InvalidAmount);
+    require!(
+        !params.legs.is_empty() && params.legs.len() <= MAX_ROUTE_LEGS,
+        ErrorCode::InvalidRoute
+    );
+    let total_accounts = params
+        .legs
+        .iter()
+        .map(|leg| leg.account_count as usize)
+        .sum::<usize>();
+    require!(
+        total_accounts == ctx.remaining_accounts.len(),
+        ErrorCode::InvalidRoute
+    );
+
+    let clock = Clock::get()?;
+
+    // Delegates trade through the user account PDA, as in execute_swap
+    let trader = SwapTrader::authorize(
+        &ctx.accounts.user_account,
+        &ctx.accounts.authority.key(),
+        clock.unix_timestamp,
+    )?;
+    let user_key = ctx.accounts.user.key();
+    let user_seeds = trader.seeds();
+    let delegate_seeds = [&user_seeds[..]];
+    let signer_seeds = trader.signer_seeds(&delegate_seeds);
+    let transfer_authority =
+        trader.transfer_authority(&ctx.accounts.user_account, &ctx.accounts.authority);
+
+    // Record a price observation for the input asset
+    observe_oracle_price(
+        &mut ctx.accounts.price_accumulator,
+        &ctx.accounts.accumulator_oracle.to_account_info(),
+        &clock,
+    )?;
+
+    let exchange = &ctx.accounts.exchange;
+
+    // One fee for the whole route, taken off the top of the first leg
+    let (fee_tier, fee_bps) = resolve_swap_fee_bps(
+        exchange,
+        &ctx.accounts.user_account,
+        ctx.accounts.referrer_account.is_some(),
+        clock.unix_timestamp,
+    )?;
+    let fee = bps_mul(params.amount_in, fee_bps)?;
+    let mut leg_amount_in = params
+        .amount_in
+        .checked_sub(fee)
+        .ok_or(ErrorCode::MathUnderflow)?;
+
+    let output_key = ctx.accounts.user_output_token_account.key();
+    let mut leg_input_mint = ctx.accounts.user_input_token_account.mint;
+    let mut leg_input_key = ctx.accounts.user_input_token_account.key();
+    let last_leg = params.legs.len() - 1;
+    let mut offset = 0;
+    let mut registry = RegistryAccount::load(ctx.accounts.protocol_registry.to_account_info())?;
+
+    for (i, leg) in params.legs.iter().enumerate() {
+        let leg_accounts = &ctx.remaining_accounts[offset..offset + leg.account_count as usize];
+        offset += leg.account_count as usize;
+
+        require!(
+            (1..leg_accounts.len()).contains(&(leg.output_index as usize)),
+            ErrorCode::InvalidRoute
+        );
+        require!(
+            leg_accounts[0].key() == leg.protocol,
+            ErrorCode::InvalidRoute
+        );
+
+        // Each leg's output lands in a user token account, measured by its
+        // balance delta; the final leg must pay into the output account
+        let output_info = &leg_accounts[leg.output_index as usize];
+        if i == last_leg {
+            require!(output_info.key() == output_key, ErrorCode::InvalidRoute);
+        }
+        let leg_pre = Account::<TokenAccount>::try_from(output_info)?;
+        require!(leg_pre.owner == user_key, ErrorCode::Unauthorized);
+
+        // Each leg must spend from and pay into the user's accounts
+        let trader_accounts = Some((leg_input_key, output_info.key()));
+
+        // Intermediate legs run without a floor; the route floor is checked once below
+        swap_adapter::execute_swap(
+            &mut registry,
+            leg_accounts,
+            &SwapRequest {
+                in_amount: leg_amount_in,
+                out_amount: 0,
+                is_base_in: leg.is_base_in,
+                mode: SwapMode::ExactIn,
+                input_mint: leg_input_mint,
+            },
+            trader_accounts,
+            signer_seeds,
+        )?;
+
+        let leg_post = Account::<TokenAccount>::try_from(output_info)?;
+        leg_amount_in = leg_post
+            .amount
+            .checked_sub(leg_pre.amount)
+            .ok_or(ErrorCode::MathUnderflow)?;
+        require!(leg_amount_in > 0, ErrorCode::SwapOutputZero);
+        leg_input_mint = leg_post.mint;
+        leg_input_key = output_info.key();
+    }
+
+    // The final leg's delta, so routes that return to the input mint measure
+    // only what the last hop paid out
+    let amount_out = leg_amount_in;
+    require!(
+        amount_out >= params.minimum_amount_out,
+        ErrorCode::SlippageExceeded
+    );
+
+    // Collect fee: transfer fee to vault
+    collect_swap_fee(
+        exchange,
+        SwapFeeAccounts {
+            token_program: &ctx.accounts.token_program,
+            from: &ctx.accounts.user_input_token_account,
+            authority: transfer_authority,
+            vault_state: &mut ctx.accounts.vault_state,
+            vault_token_account: &mut ctx.accounts.vault_token_account,
+            referrer_account: ctx.accounts.referrer_account.as_deref_mut(),
+        },
+        fee,
+        signer_seeds,
+    )?;
+
+    // Update user stats
+    record_swap(
+        &mut ctx.accounts.user_account,
+        params.amount_in,
+        fee,
+        clock.unix_timestamp,
+    )?;
+
+    emit!(SwapExecuted {
+        user: user_key,
+        input_mint: ctx.accounts.user_input_token_account.mint,
+        output_mint: ctx.accounts.user_output_token_account.mint,
+        amount_in: params.amount_in,
+        amount_out,
+        fee,
+        fee_tier,
+        protocol: params.legs[0].protocol,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
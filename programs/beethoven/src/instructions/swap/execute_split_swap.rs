@@ -0,0 +1,315 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::error::ErrorCode;
+use crate::events::{RouteExecuted, RouteLegFill};
+use crate::math::fixed_point::bps_mul;
+use crate::math::twap::observe_oracle_price;
+use crate::adapters::adapter::SwapRequest;
+use crate::adapters::swap_adapter::{self, SwapMode};
+use crate::instructions::swap::swap_fees::{
+    collect_swap_fee, record_swap, resolve_swap_fee_bps, SwapFeeAccounts,
+};
+use crate::instructions::swap::swap_trader::SwapTrader;
+use crate::state::{
+    sub_account_seed, Exchange, PriceAccumulator, RegistryAccount, ReferrerAccount, UserAccount,
+    VaultState,
+};
+
+use anchor_spl::token::{TokenAccount, Token};
+
+/// One venue of a split swap. Its accounts are the next `account_count`
+/// entries of remaining_accounts: the protocol program followed by the
+/// protocol's own swap accounts, as execute_swap expects them.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct SplitLeg {
+    pub protocol: Pubkey,  // Protocol program; must match the first account of the slice
+    pub account_count: u8, // Accounts in this leg's slice, program included
+    pub weight_bps: u64,   // Share of the post-fee input routed to this leg
+    pub is_base_in: bool,  // Direction on order-book venues (sell base when true)
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct ExecuteSplitSwapParams {
+    pub amount_in: u64,          // Input spent across all legs, fee included
+    pub minimum_amount_out: u64, // Slippage floor on the combined output
+    pub legs: Vec<SplitLeg>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteSplitSwap<'info> {
+    /// Owner or their delegate
+    pub authority: Signer<'info>,
+
+    /// CHECK: Account owner, bound by the user_account seeds
+    pub user: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [EXCHANGE_SEED],
+        bump = exchange.bump,
+        constraint = !exchange.swap_paused @ ErrorCode::ExchangePaused,
+    )]
+    pub exchange: Account<'info, Exchange>,
+
+    #[account(
+        mut,
+        seeds = [
+            USER_ACCOUNT_SEED,
+            user.key().as_ref(),
+            sub_account_seed(&user_account.sub_account_id.to_le_bytes()),
+        ],
+        bump = user_account.bump,
+        constraint = user_account.owner == user.key() @ ErrorCode::Unauthorized,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    /// User's input token account, spent by every leg
+    #[account(
+        mut,
+        constraint = user_input_token_account.owner == user.key() @ ErrorCode::Unauthorized,
+    )]
+    pub user_input_token_account: Account<'info, TokenAccount>,
+
+    /// User's output token account, credited by every leg
+    #[account(
+        mut,
+        constraint = user_output_token_account.owner == user.key() @ ErrorCode::Unauthorized,
+        constraint = user_output_token_account.mint != user_input_token_account.mint @ ErrorCode::IdenticalSwapMints,
+    )]
+    pub user_output_token_account: Account<'info, TokenAccount>,
+
+    /// Fee vault of the input mint
+    #[account(
+        mut,
+        seeds = [VAULT_SEED, user_input_token_account.mint.as_ref()],
+        bump = vault_state.bump,
+        constraint = vault_state.mint == user_input_token_account.mint @ ErrorCode::FeeVaultMintMismatch,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        constraint = vault_token_account.key() == vault_state.token_account @ ErrorCode::InvalidParameter,
+        constraint = vault_token_account.mint == vault_state.mint @ ErrorCode::FeeVaultMintMismatch,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    /// TWAP/EMA accumulator of the input asset, observed on every swap
+    #[account(
+        mut,
+        seeds = [PRICE_ACCUMULATOR_SEED, accumulator_oracle.key().as_ref()],
+        bump = price_accumulator.bump,
+    )]
+    pub price_accumulator: Box<Account<'info, PriceAccumulator>>,
+
+    /// CHECK: Oracle backing `price_accumulator`, bound by its seeds and parsed by get_price
+    pub accumulator_oracle: UncheckedAccount<'info>,
+
+    /// Optional rewards account of the user's referrer in the fee mint;
+    /// supplying it applies the referee discount
+    #[account(
+        mut,
+        seeds = [REFERRER_SEED, user_account.referrer.as_ref(), user_input_token_account.mint.as_ref()],
+        bump = referrer_account.bump,
+    )]
+    pub referrer_account: Option<Box<Account<'info, ReferrerAccount>>>,
+
+    /// CHECK: Runtime protocol allowlist and volume caps, loaded by
+    /// `RegistryAccount`; may be uninitialized, and is only written when
+    /// passed writable for a capped venue
+    #[account(seeds = [PROTOCOL_REGISTRY_SEED], bump)]
+    pub protocol_registry: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ExecuteSplitSwap<'info>>,
+    params: ExecuteSplitSwapParams,
+) -> Result<()> {
+    require!(params.amount_in > 0, ErrorCode::InvalidAmount);
+    require!(
+        !params.legs.is_empty() && params.legs.len() <= MAX_ROUTE_LEGS,
+        ErrorCode::InvalidRoute
+    );
+    let total_accounts = params
+        .legs
+        .iter()
+        .map(|leg| leg.account_count as usize)
+        .sum::<usize>();
+    require!(
+        total_accounts == ctx.remaining_accounts.len(),
+        ErrorCode::InvalidRoute
+    );
+    let total_weight = params
+        .legs
+        .iter()
+        .try_fold(0u64, |sum, leg| sum.checked_add(leg.weight_bps))
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(
+        params.legs.iter().all(|leg| leg.weight_bps > 0) && total_weight == BPS_DENOMINATOR,
+        ErrorCode::InvalidSplitWeights
+    );
+
+    let clock = Clock::get()?;
+
+    // Delegates trade through the user account PDA, as in execute_swap
+    let trader = SwapTrader::authorize(
+        &ctx.accounts.user_account,
+        &ctx.accounts.authority.key(),
+        clock.unix_timestamp,
+    )?;
+    let user_key = ctx.accounts.user.key();
+    let user_seeds = trader.seeds();
+    let delegate_seeds = [&user_seeds[..]];
+    let signer_seeds = trader.signer_seeds(&delegate_seeds);
+    let transfer_authority =
+        trader.transfer_authority(&ctx.accounts.user_account, &ctx.accounts.authority);
+
+    // The venue must debit and credit the validated user accounts
+    let trader_accounts = Some((
+        ctx.accounts.user_input_token_account.key(),
+        ctx.accounts.user_output_token_account.key(),
+    ));
+
+    // Record a price observation for the input asset
+    observe_oracle_price(
+        &mut ctx.accounts.price_accumulator,
+        &ctx.accounts.accumulator_oracle.to_account_info(),
+        &clock,
+    )?;
+
+    let exchange = &ctx.accounts.exchange;
+
+    // One fee on the whole order, taken off the top before splitting
+    let (fee_tier, fee_bps) = resolve_swap_fee_bps(
+        exchange,
+        &ctx.accounts.user_account,
+        ctx.accounts.referrer_account.is_some(),
+        clock.unix_timestamp,
+    )?;
+    let fee = bps_mul(params.amount_in, fee_bps)?;
+    let swap_amount = params
+        .amount_in
+        .checked_sub(fee)
+        .ok_or(ErrorCode::MathUnderflow)?;
+
+    let input_mint = ctx.accounts.user_input_token_account.mint;
+    let pre_input = ctx.accounts.user_input_token_account.amount;
+    let pre_output = ctx.accounts.user_output_token_account.amount;
+    let last_leg = params.legs.len() - 1;
+    let mut allocated = 0u64;
+    let mut offset = 0;
+    let mut fills = Vec::with_capacity(params.legs.len());
+    let mut registry = RegistryAccount::load(ctx.accounts.protocol_registry.to_account_info())?;
+
+    for (i, leg) in params.legs.iter().enumerate() {
+        let leg_accounts = &ctx.remaining_accounts[offset..offset + leg.account_count as usize];
+        offset += leg.account_count as usize;
+        require!(
+            !leg_accounts.is_empty() && leg_accounts[0].key() == leg.protocol,
+            ErrorCode::InvalidRoute
+        );
+
+        // The last leg takes the rounding remainder so the legs spend exactly swap_amount
+        let leg_amount = if i == last_leg {
+            swap_amount - allocated
+        } else {
+            bps_mul(swap_amount, leg.weight_bps)?
+        };
+        allocated = allocated
+            .checked_add(leg_amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        let leg_pre_input = ctx.accounts.user_input_token_account.amount;
+        let leg_pre_output = ctx.accounts.user_output_token_account.amount;
+
+        // Legs run without a floor; the combined floor is checked once below
+        swap_adapter::execute_swap(
+            &mut registry,
+            leg_accounts,
+            &SwapRequest {
+                in_amount: leg_amount,
+                out_amount: 0,
+                is_base_in: leg.is_base_in,
+                mode: SwapMode::ExactIn,
+                input_mint,
+            },
+            trader_accounts,
+            signer_seeds,
+        )?;
+
+        ctx.accounts.user_input_token_account.reload()?;
+        ctx.accounts.user_output_token_account.reload()?;
+        let leg_in = leg_pre_input
+            .checked_sub(ctx.accounts.user_input_token_account.amount)
+            .ok_or(ErrorCode::MathUnderflow)?;
+        let leg_out = ctx
+            .accounts
+            .user_output_token_account
+            .amount
+            .checked_sub(leg_pre_output)
+            .ok_or(ErrorCode::MathUnderflow)?;
+        require!(leg_out > 0, ErrorCode::SwapOutputZero);
+
+        fills.push(RouteLegFill {
+            protocol: leg.protocol,
+            amount_in: leg_in,
+            amount_out: leg_out,
+        });
+    }
+
+    // Venues may not pull more than they were allotted
+    let spent = pre_input
+        .checked_sub(ctx.accounts.user_input_token_account.amount)
+        .ok_or(ErrorCode::MathUnderflow)?;
+    require!(spent <= swap_amount, ErrorCode::SlippageExceeded);
+
+    let amount_out = ctx
+        .accounts
+        .user_output_token_account
+        .amount
+        .checked_sub(pre_output)
+        .ok_or(ErrorCode::MathUnderflow)?;
+    require!(
+        amount_out >= params.minimum_amount_out,
+        ErrorCode::SlippageExceeded
+    );
+
+    // Collect fee: transfer fee to vault
+    collect_swap_fee(
+        exchange,
+        SwapFeeAccounts {
+            token_program: &ctx.accounts.token_program,
+            from: &ctx.accounts.user_input_token_account,
+            authority: transfer_authority,
+            vault_state: &mut ctx.accounts.vault_state,
+            vault_token_account: &mut ctx.accounts.vault_token_account,
+            referrer_account: ctx.accounts.referrer_account.as_deref_mut(),
+        },
+        fee,
+        signer_seeds,
+    )?;
+
+    // Update user stats
+    let amount_in = spent.checked_add(fee).ok_or(ErrorCode::MathOverflow)?;
+    record_swap(
+        &mut ctx.accounts.user_account,
+        amount_in,
+        fee,
+        clock.unix_timestamp,
+    )?;
+
+    emit!(RouteExecuted {
+        user: user_key,
+        input_mint: ctx.accounts.user_input_token_account.mint,
+        output_mint: ctx.accounts.user_output_token_account.mint,
+        amount_in,
+        amount_out,
+        fee,
+        fee_tier,
+        legs: fills,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
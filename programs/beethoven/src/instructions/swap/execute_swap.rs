@@ -3,21 +3,39 @@ use crate::constants::*;
 use crate::error::ErrorCode;
 use crate::events::SwapExecuted;
 use crate::math::fixed_point::bps_mul;
-use crate::adapters::swap_adapter;
-use crate::state::{Exchange, UserAccount, VaultState};
+use crate::math::oracle::{check_oracle_band, get_price, PricedAmount};
+use crate::math::twap::observe_oracle_price;
+use crate::adapters::adapter::SwapRequest;
+use crate::adapters::swap_adapter::{self, SwapMode};
+use crate::instructions::swap::swap_fees::{
+    collect_swap_fee, record_swap, resolve_swap_fee_bps, SwapFeeAccounts,
+};
+use crate::instructions::swap::swap_trader::SwapTrader;
+use crate::state::{
+    sub_account_seed, Exchange, PriceAccumulator, RegistryAccount, ReferrerAccount, UserAccount,
+    VaultState,
+};
 
-use anchor_spl::token::{TokenAccount, Token};
+use anchor_spl::token::{Mint, TokenAccount, Token};
 
 #[derive(AnchorSerialize, AnchorDeserialize)]
 pub struct ExecuteSwapParams {
-    pub amount_in: u64,
-    pub minimum_amount_out: u64,
+    pub amount_in: u64,          // Exact-in: input spent, fee included
+    pub minimum_amount_out: u64, // Exact-in: slippage floor
+    pub is_base_in: bool,        // Direction on order-book venues (sell base when true)
+    pub mode: SwapMode,
+    pub amount_out: u64,         // Exact-out: output to receive
+    pub maximum_amount_in: u64,  // Exact-out: input ceiling, fee included
+    pub max_oracle_deviation_bps: u64, // Oracle guard: worst fill below the oracle cross rate
 }
 
 #[derive(Accounts)]
 pub struct ExecuteSwap<'info> {
-    #[account(mut)]
-    pub user: Signer<'info>,
+    /// Owner or their delegate
+    pub authority: Signer<'info>,
+
+    /// CHECK: Account owner, bound by the user_account seeds
+    pub user: UncheckedAccount<'info>,
 
     #[account(
         seeds = [EXCHANGE_SEED],
@@ -28,34 +46,91 @@ pub struct ExecuteSwap<'info> {
 
     #[account(
         mut,
-        seeds = [USER_ACCOUNT_SEED, user.key().as_ref()],
+        seeds = [
+            USER_ACCOUNT_SEED,
+            user.key().as_ref(),
+            sub_account_seed(&user_account.sub_account_id.to_le_bytes()),
+        ],
         bump = user_account.bump,
         constraint = user_account.owner == user.key() @ ErrorCode::Unauthorized,
     )]
     pub user_account: Account<'info, UserAccount>,
 
     /// User's input token account
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = user_input_token_account.owner == user.key() @ ErrorCode::Unauthorized,
+    )]
     pub user_input_token_account: Account<'info, TokenAccount>,
 
     /// User's output token account
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = user_output_token_account.owner == user.key() @ ErrorCode::Unauthorized,
+        constraint = user_output_token_account.mint != user_input_token_account.mint @ ErrorCode::IdenticalSwapMints,
+    )]
     pub user_output_token_account: Account<'info, TokenAccount>,
 
-    /// Vault for fee collection on input token
+    /// Fee vault of the input mint
     #[account(
         mut,
-        seeds = [VAULT_SEED, vault_state.mint.as_ref()],
+        seeds = [VAULT_SEED, user_input_token_account.mint.as_ref()],
         bump = vault_state.bump,
+        constraint = vault_state.mint == user_input_token_account.mint @ ErrorCode::FeeVaultMintMismatch,
     )]
     pub vault_state: Account<'info, VaultState>,
 
     #[account(
         mut,
         constraint = vault_token_account.key() == vault_state.token_account @ ErrorCode::InvalidParameter,
+        constraint = vault_token_account.mint == vault_state.mint @ ErrorCode::FeeVaultMintMismatch,
     )]
     pub vault_token_account: Account<'info, TokenAccount>,
 
+    /// TWAP/EMA accumulator of the input asset, observed on every swap
+    #[account(
+        mut,
+        seeds = [PRICE_ACCUMULATOR_SEED, accumulator_oracle.key().as_ref()],
+        bump = price_accumulator.bump,
+    )]
+    pub price_accumulator: Box<Account<'info, PriceAccumulator>>,
+
+    /// CHECK: Oracle backing `price_accumulator`, bound by its seeds and parsed by get_price
+    pub accumulator_oracle: UncheckedAccount<'info>,
+
+    /// Optional rewards account of the user's referrer in the fee mint;
+    /// supplying it applies the referee discount
+    #[account(
+        mut,
+        seeds = [REFERRER_SEED, user_account.referrer.as_ref(), user_input_token_account.mint.as_ref()],
+        bump = referrer_account.bump,
+    )]
+    pub referrer_account: Option<Box<Account<'info, ReferrerAccount>>>,
+
+    /// CHECK: Optional oracle guard: price feed of the input mint, parsed by get_price
+    pub input_oracle: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Optional oracle guard: price feed of the output mint, parsed by get_price
+    pub output_oracle: Option<UncheckedAccount<'info>>,
+
+    /// Input mint, for the oracle guard's decimals
+    #[account(
+        constraint = input_mint.key() == user_input_token_account.mint @ ErrorCode::InvalidParameter,
+    )]
+    pub input_mint: Option<Box<Account<'info, Mint>>>,
+
+    /// Output mint, for the oracle guard's decimals
+    #[account(
+        constraint = output_mint.key() == user_output_token_account.mint @ ErrorCode::InvalidParameter,
+    )]
+    pub output_mint: Option<Box<Account<'info, Mint>>>,
+
+    /// CHECK: Runtime protocol allowlist and volume caps, loaded by
+    /// `RegistryAccount`; may be uninitialized, and is only written when
+    /// passed writable for a capped venue
+    #[account(seeds = [PROTOCOL_REGISTRY_SEED], bump)]
+    pub protocol_registry: UncheckedAccount<'info>,
+
     pub token_program: Program<'info, Token>,
 }
 
@@ -63,78 +138,167 @@ pub fn handler<'info>(
     ctx: Context<'_, '_, 'info, 'info, ExecuteSwap<'info>>,
     params: ExecuteSwapParams,
 ) -> Result<()> {
-    require!(params.amount_in > 0, ErrorCode::InvalidAmount);
+    match params.mode {
+        SwapMode::ExactIn => require!(params.amount_in > 0, ErrorCode::InvalidAmount),
+        SwapMode::ExactOut => require!(
+            params.amount_out > 0 && params.maximum_amount_in > 0,
+            ErrorCode::InvalidAmount
+        ),
+    }
+    require!(
+        params.max_oracle_deviation_bps <= BPS_DENOMINATOR,
+        ErrorCode::InvalidParameter
+    );
 
-    let exchange = &ctx.accounts.exchange;
     let clock = Clock::get()?;
 
-    // Calculate fee
-    let fee = bps_mul(params.amount_in, exchange.swap_fee_bps)?;
-    let amount_after_fee = params.amount_in
-        .checked_sub(fee)
-        .ok_or(ErrorCode::MathUnderflow)?;
+    // Delegates trade through the user account PDA, which the owner approves
+    // as SPL token delegate and passes as the trader in remaining_accounts
+    let trader = SwapTrader::authorize(
+        &ctx.accounts.user_account,
+        &ctx.accounts.authority.key(),
+        clock.unix_timestamp,
+    )?;
+    let user_seeds = trader.seeds();
+    let delegate_seeds = [&user_seeds[..]];
+    let signer_seeds = trader.signer_seeds(&delegate_seeds);
+    let transfer_authority =
+        trader.transfer_authority(&ctx.accounts.user_account, &ctx.accounts.authority);
 
-    // Collect fee: transfer fee to vault
-    if fee > 0 {
-        anchor_spl::token::transfer(
-            CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                anchor_spl::token::Transfer {
-                    from: ctx.accounts.user_input_token_account.to_account_info(),
-                    to: ctx.accounts.vault_token_account.to_account_info(),
-                    authority: ctx.accounts.user.to_account_info(),
-                },
-            ),
-            fee,
-        )?;
-
-        // Track fees
-        let vault_state = &mut ctx.accounts.vault_state;
-        vault_state.collected_fees = vault_state
-            .collected_fees
-            .checked_add(fee)
-            .ok_or(ErrorCode::MathOverflow)?;
-    }
+    // The venue must debit and credit the validated user accounts
+    let trader_accounts = Some((
+        ctx.accounts.user_input_token_account.key(),
+        ctx.accounts.user_output_token_account.key(),
+    ));
+
+    let exchange = &ctx.accounts.exchange;
+
+    // Record a price observation for the input asset
+    observe_oracle_price(
+        &mut ctx.accounts.price_accumulator,
+        &ctx.accounts.accumulator_oracle.to_account_info(),
+        &clock,
+    )?;
 
-    // Record pre-swap output balance for slippage check
-    let pre_balance = ctx.accounts.user_output_token_account.amount;
+    // Calculate fee at the user's 30-day volume tier
+    let (fee_tier, fee_bps) = resolve_swap_fee_bps(
+        exchange,
+        &ctx.accounts.user_account,
+        ctx.accounts.referrer_account.is_some(),
+        clock.unix_timestamp,
+    )?;
 
-    // Execute swap via protocol adapter (remaining_accounts carries protocol program + accounts)
+    // Record pre-swap balances for the slippage checks
+    let pre_input = ctx.accounts.user_input_token_account.amount;
+    let pre_output = ctx.accounts.user_output_token_account.amount;
+
+    // Execute swap via protocol adapter (remaining_accounts carries protocol program + accounts).
+    // Exact-in takes the fee off the top; exact-out leaves room for it under the ceiling
+    let (in_amount, out_amount) = match params.mode {
+        SwapMode::ExactIn => (
+            params
+                .amount_in
+                .checked_sub(bps_mul(params.amount_in, fee_bps)?)
+                .ok_or(ErrorCode::MathUnderflow)?,
+            params.minimum_amount_out,
+        ),
+        SwapMode::ExactOut => (
+            params
+                .maximum_amount_in
+                .checked_sub(bps_mul(params.maximum_amount_in, fee_bps)?)
+                .ok_or(ErrorCode::MathUnderflow)?,
+            params.amount_out,
+        ),
+    };
+    let mut registry = RegistryAccount::load(ctx.accounts.protocol_registry.to_account_info())?;
     swap_adapter::execute_swap(
+        &mut registry,
         ctx.remaining_accounts,
-        amount_after_fee,
-        params.minimum_amount_out,
+        &SwapRequest {
+            in_amount,
+            out_amount,
+            is_base_in: params.is_base_in,
+            mode: params.mode,
+            input_mint: ctx.accounts.user_input_token_account.mint,
+        },
+        trader_accounts,
+        signer_seeds,
     )?;
 
-    // Reload output token account to check post-swap balance
+    // Reload token accounts to measure what the protocol actually moved
+    ctx.accounts.user_input_token_account.reload()?;
     ctx.accounts.user_output_token_account.reload()?;
-    let post_balance = ctx.accounts.user_output_token_account.amount;
-
-    let amount_out = post_balance
-        .checked_sub(pre_balance)
+    let spent = pre_input
+        .checked_sub(ctx.accounts.user_input_token_account.amount)
+        .ok_or(ErrorCode::MathUnderflow)?;
+    let amount_out = ctx
+        .accounts
+        .user_output_token_account
+        .amount
+        .checked_sub(pre_output)
         .ok_or(ErrorCode::MathUnderflow)?;
 
     require!(amount_out > 0, ErrorCode::SwapOutputZero);
-    require!(
-        amount_out >= params.minimum_amount_out,
-        ErrorCode::SlippageExceeded
-    );
+
+    // Fee on the input actually spent, in both modes. The venue is bound to
+    // the user's input account, and an exact-in venue must spend all of it
+    let (minimum_out, maximum_in) = match params.mode {
+        SwapMode::ExactIn => {
+            require!(spent == in_amount, ErrorCode::SwapInputMismatch);
+            (params.minimum_amount_out, params.amount_in)
+        }
+        SwapMode::ExactOut => (params.amount_out, params.maximum_amount_in),
+    };
+    require!(amount_out >= minimum_out, ErrorCode::SlippageExceeded);
+    let fee = bps_mul(spent, fee_bps)?;
+    let amount_in = spent.checked_add(fee).ok_or(ErrorCode::MathOverflow)?;
+    require!(amount_in <= maximum_in, ErrorCode::SlippageExceeded);
+
+    // Reject fills outside the caller's oracle band, fee included
+    match (
+        ctx.accounts.input_oracle.as_ref(),
+        ctx.accounts.output_oracle.as_ref(),
+        ctx.accounts.input_mint.as_ref(),
+        ctx.accounts.output_mint.as_ref(),
+    ) {
+        (Some(input_oracle), Some(output_oracle), Some(input_mint), Some(output_mint)) => {
+            let input_price = get_price(&input_oracle.to_account_info(), &clock)?;
+            let output_price = get_price(&output_oracle.to_account_info(), &clock)?;
+            check_oracle_band(
+                &PricedAmount {
+                    amount: amount_in,
+                    decimals: input_mint.decimals,
+                    price: input_price.price,
+                },
+                &PricedAmount {
+                    amount: amount_out,
+                    decimals: output_mint.decimals,
+                    price: output_price.price,
+                },
+                params.max_oracle_deviation_bps,
+            )?;
+        }
+        (None, None, None, None) => {}
+        _ => return Err(ErrorCode::OracleAccountMismatch.into()),
+    }
+
+    // Collect fee: transfer fee to vault
+    collect_swap_fee(
+        exchange,
+        SwapFeeAccounts {
+            token_program: &ctx.accounts.token_program,
+            from: &ctx.accounts.user_input_token_account,
+            authority: transfer_authority,
+            vault_state: &mut ctx.accounts.vault_state,
+            vault_token_account: &mut ctx.accounts.vault_token_account,
+            referrer_account: ctx.accounts.referrer_account.as_deref_mut(),
+        },
+        fee,
+        signer_seeds,
+    )?;
 
     // Update user stats
-    let user = &mut ctx.accounts.user_account;
-    user.total_trades = user
-        .total_trades
-        .checked_add(1)
-        .ok_or(ErrorCode::MathOverflow)?;
-    user.total_volume = user
-        .total_volume
-        .checked_add(params.amount_in as u64)
-        .ok_or(ErrorCode::MathOverflow)?;
-    user.total_fees_paid = user
-        .total_fees_paid
-        .checked_add(fee)
-        .ok_or(ErrorCode::MathOverflow)?;
-    user.last_activity = clock.unix_timestamp;
+    record_swap(&mut ctx.accounts.user_account, amount_in, fee, clock.unix_timestamp)?;
 
     // Get protocol from remaining accounts for event
     let protocol = if !ctx.remaining_accounts.is_empty() {
@@ -147,9 +311,10 @@ pub fn handler<'info>(
         user: ctx.accounts.user.key(),
         input_mint: ctx.accounts.user_input_token_account.mint,
         output_mint: ctx.accounts.user_output_token_account.mint,
-        amount_in: params.amount_in,
+        amount_in,
         amount_out,
         fee,
+        fee_tier,
         protocol,
         timestamp: clock.unix_timestamp,
     });
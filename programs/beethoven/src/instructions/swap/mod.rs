@@ -1,7 +1,17 @@
 pub mod execute_swap;
+pub mod execute_route;
+pub mod execute_split_swap;
 pub mod add_liquidity;
 pub mod remove_liquidity;
+pub mod add_pool_liquidity;
+pub mod remove_pool_liquidity;
+pub mod swap_fees;
+pub mod swap_trader;
 
 pub use execute_swap::*;
+pub use execute_route::*;
+pub use execute_split_swap::*;
 pub use add_liquidity::*;
 pub use remove_liquidity::*;
+pub use add_pool_liquidity::*;
+pub use remove_pool_liquidity::*;
@@ -2,12 +2,13 @@ use anchor_lang::prelude::*;
 use crate::constants::*;
 use crate::error::ErrorCode;
 use crate::events::LiquidityRemoved;
-use crate::adapters::deposit_adapter;
-use crate::state::{Exchange, UserAccount};
+use crate::adapters::deposit_adapter::{self, Depositor};
+use crate::state::{sub_account_seed, Exchange, RegistryAccount, UserAccount, YieldPosition};
 
 use anchor_spl::token::{TokenAccount, Token};
 
 #[derive(Accounts)]
+#[instruction(amount: u64, protocol: Pubkey)]
 pub struct RemoveLiquidity<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
@@ -20,46 +21,105 @@ pub struct RemoveLiquidity<'info> {
 
     #[account(
         mut,
-        seeds = [USER_ACCOUNT_SEED, user.key().as_ref()],
+        seeds = [
+            USER_ACCOUNT_SEED,
+            user.key().as_ref(),
+            sub_account_seed(&user_account.sub_account_id.to_le_bytes()),
+        ],
         bump = user_account.bump,
         constraint = user_account.owner == user.key() @ ErrorCode::Unauthorized,
     )]
     pub user_account: Account<'info, UserAccount>,
 
     /// User's token account for receiving the withdrawal
-    #[account(mut)]
+    #[account(
+        mut,
+        constraint = user_token_account.owner == user.key() @ ErrorCode::Unauthorized,
+    )]
     pub user_token_account: Account<'info, TokenAccount>,
 
+    /// The user's position in `protocol` for this mint
+    #[account(
+        mut,
+        seeds = [
+            YIELD_POSITION_SEED,
+            user_account.key().as_ref(),
+            protocol.as_ref(),
+            user_token_account.mint.as_ref(),
+        ],
+        bump = yield_position.bump,
+    )]
+    pub yield_position: Box<Account<'info, YieldPosition>>,
+
+    /// CHECK: Runtime protocol allowlist and volume caps, loaded by
+    /// `RegistryAccount`; may be uninitialized, and is only written when
+    /// passed writable for a capped venue
+    #[account(seeds = [PROTOCOL_REGISTRY_SEED], bump)]
+    pub protocol_registry: UncheckedAccount<'info>,
+
     pub token_program: Program<'info, Token>,
 }
 
 pub fn handler<'info>(
     ctx: Context<'_, '_, 'info, 'info, RemoveLiquidity<'info>>,
     amount: u64,
+    protocol: Pubkey,
 ) -> Result<()> {
     require!(amount > 0, ErrorCode::InvalidAmount);
+    require!(
+        !ctx.remaining_accounts.is_empty(),
+        ErrorCode::UnsupportedProtocol
+    );
+    require_keys_eq!(
+        ctx.remaining_accounts[0].key(),
+        protocol,
+        ErrorCode::ProtocolAccountMismatch
+    );
 
     let clock = Clock::get()?;
+    let pre_balance = ctx.accounts.user_token_account.amount;
+
+    // Execute withdrawal via protocol adapter, paying the user and burning
+    // receipts from an account the user owns
+    let registry = RegistryAccount::load(ctx.accounts.protocol_registry.to_account_info())?;
+    let receipt_burned = deposit_adapter::execute_withdraw(
+        &registry,
+        ctx.remaining_accounts,
+        amount,
+        Some(Depositor {
+            token_account: ctx.accounts.user_token_account.key(),
+            authority: ctx.accounts.user.key(),
+        }),
+        &[],
+    )?;
 
-    // Execute withdrawal via protocol adapter
-    deposit_adapter::execute_withdraw(ctx.remaining_accounts, amount)?;
+    // Measure what actually came back
+    ctx.accounts.user_token_account.reload()?;
+    let returned = ctx
+        .accounts
+        .user_token_account
+        .amount
+        .checked_sub(pre_balance)
+        .ok_or(ErrorCode::MathUnderflow)?;
+
+    let position = &mut ctx.accounts.yield_position;
+    let (cost_basis, realized_yield) =
+        position.record_withdrawal(returned, receipt_burned, clock.unix_timestamp)?;
 
     // Update user stats
     let user = &mut ctx.accounts.user_account;
     user.last_activity = clock.unix_timestamp;
 
-    // Get protocol from remaining accounts for event
-    let protocol = if !ctx.remaining_accounts.is_empty() {
-        *ctx.remaining_accounts[0].key
-    } else {
-        Pubkey::default()
-    };
-
     emit!(LiquidityRemoved {
         user: ctx.accounts.user.key(),
         mint: ctx.accounts.user_token_account.mint,
-        amount,
+        amount: returned,
         protocol,
+        position: ctx.accounts.yield_position.key(),
+        receipt_burned,
+        cost_basis,
+        realized_yield,
+        principal: ctx.accounts.yield_position.principal,
         timestamp: clock.unix_timestamp,
     });
 
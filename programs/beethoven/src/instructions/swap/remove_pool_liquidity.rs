@@ -0,0 +1,145 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount, Transfer};
+use crate::constants::*;
+use crate::error::ErrorCode;
+use crate::events::PoolLiquidityRemoved;
+use crate::math::amm::withdraw_amounts;
+use crate::state::SwapPool;
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct RemovePoolLiquidityParams {
+    pub shares: u64,
+    pub min_amount_a: u64,
+    pub min_amount_b: u64,
+}
+
+#[derive(Accounts)]
+pub struct RemovePoolLiquidity<'info> {
+    pub withdrawer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [SWAP_POOL_SEED, pool.mint_a.as_ref(), pool.mint_b.as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Box<Account<'info, SwapPool>>,
+
+    #[account(
+        mut,
+        address = pool.vault_a @ ErrorCode::PoolAccountMismatch,
+    )]
+    pub vault_a: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        address = pool.vault_b @ ErrorCode::PoolAccountMismatch,
+    )]
+    pub vault_b: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        address = pool.lp_mint @ ErrorCode::PoolAccountMismatch,
+    )]
+    pub lp_mint: Box<Account<'info, Mint>>,
+
+    /// Withdrawer's LP token account
+    #[account(
+        mut,
+        constraint = user_lp_account.mint == pool.lp_mint @ ErrorCode::InvalidParameter,
+        constraint = user_lp_account.owner == withdrawer.key() @ ErrorCode::Unauthorized,
+    )]
+    pub user_lp_account: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = user_token_a.mint == pool.mint_a @ ErrorCode::InvalidParameter,
+        constraint = user_token_a.owner == withdrawer.key() @ ErrorCode::Unauthorized,
+    )]
+    pub user_token_a: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        constraint = user_token_b.mint == pool.mint_b @ ErrorCode::InvalidParameter,
+        constraint = user_token_b.owner == withdrawer.key() @ ErrorCode::Unauthorized,
+    )]
+    pub user_token_b: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Withdrawals stay open while swaps are paused.
+pub fn handler(ctx: Context<RemovePoolLiquidity>, params: RemovePoolLiquidityParams) -> Result<()> {
+    require!(params.shares > 0, ErrorCode::InvalidAmount);
+
+    let pool = &ctx.accounts.pool;
+    let (amount_a, amount_b) = withdraw_amounts(
+        params.shares,
+        pool.reserve_a,
+        pool.reserve_b,
+        pool.total_shares,
+    )?;
+    require!(
+        amount_a >= params.min_amount_a && amount_b >= params.min_amount_b,
+        ErrorCode::SlippageExceeded
+    );
+
+    // Burn LP shares from withdrawer
+    token::burn(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.lp_mint.to_account_info(),
+                from: ctx.accounts.user_lp_account.to_account_info(),
+                authority: ctx.accounts.withdrawer.to_account_info(),
+            },
+        ),
+        params.shares,
+    )?;
+
+    // Transfer both tokens out (pool PDA signs)
+    let seeds = &[
+        SWAP_POOL_SEED,
+        pool.mint_a.as_ref(),
+        pool.mint_b.as_ref(),
+        &[pool.bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    for (from, to, amount) in [
+        (&ctx.accounts.vault_a, &ctx.accounts.user_token_a, amount_a),
+        (&ctx.accounts.vault_b, &ctx.accounts.user_token_b, amount_b),
+    ] {
+        if amount == 0 {
+            continue;
+        }
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: from.to_account_info(),
+                    to: to.to_account_info(),
+                    authority: ctx.accounts.pool.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            amount,
+        )?;
+    }
+
+    // Update pool state
+    let pool = &mut ctx.accounts.pool;
+    pool.reserve_a -= amount_a;
+    pool.reserve_b -= amount_b;
+    pool.total_shares -= params.shares;
+
+    emit!(PoolLiquidityRemoved {
+        pool: pool.key(),
+        withdrawer: ctx.accounts.withdrawer.key(),
+        shares_burned: params.shares,
+        amount_a,
+        amount_b,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use crate::error::ErrorCode;
+use crate::math::fee_tiers::{
+    discounted_fee_bps, record_swap_volume, resolve_fee_tier, rolling_swap_volume,
+};
+use crate::math::referral::{accrue_referral, referee_fee_bps};
+use crate::state::{Exchange, ReferrerAccount, UserAccount, VaultBucket, VaultState};
+
+/// Swap fee rate at the user's 30-day swap volume tier, with the referee discount
+/// when the referrer account was supplied. Returns (fee_tier, fee_bps),
+/// with no tier when the volume is below every tier.
+pub fn resolve_swap_fee_bps(
+    exchange: &Exchange,
+    user_account: &UserAccount,
+    has_referrer: bool,
+    now: i64,
+) -> Result<(Option<u8>, u64)> {
+    let volume = rolling_swap_volume(user_account, now)?;
+    let (fee_tier, discount_bps) = resolve_fee_tier(exchange, volume);
+    let fee_bps = discounted_fee_bps(exchange.swap_fee_bps, discount_bps)?;
+    let fee_bps = referee_fee_bps(exchange, fee_bps, has_referrer)?;
+    Ok((fee_tier, fee_bps))
+}
+
+/// Accounts a swap fee moves through.
+pub struct SwapFeeAccounts<'a, 'info> {
+    pub token_program: &'a Program<'info, Token>,
+    pub from: &'a Account<'info, TokenAccount>,
+    pub authority: AccountInfo<'info>,
+    pub vault_state: &'a mut Account<'info, VaultState>,
+    pub vault_token_account: &'a mut Account<'info, TokenAccount>,
+    pub referrer_account: Option<&'a mut Account<'info, ReferrerAccount>>,
+}
+
+/// Move `fee` from the trader into the fee vault, crediting the referrer's
+/// share to the referral bucket and the rest to fees.
+pub fn collect_swap_fee(
+    exchange: &Exchange,
+    accounts: SwapFeeAccounts,
+    fee: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    if fee == 0 {
+        return Ok(());
+    }
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            accounts.token_program.to_account_info(),
+            Transfer {
+                from: accounts.from.to_account_info(),
+                to: accounts.vault_token_account.to_account_info(),
+                authority: accounts.authority,
+            },
+            signer_seeds,
+        ),
+        fee,
+    )?;
+
+    let referral_fee = match accounts.referrer_account {
+        Some(referrer) => accrue_referral(exchange, referrer, fee)?,
+        None => 0,
+    };
+    accounts.vault_state.credit(VaultBucket::Fees, fee - referral_fee)?;
+    accounts.vault_state.credit(VaultBucket::Referral, referral_fee)?;
+    accounts.vault_token_account.reload()?;
+    accounts
+        .vault_state
+        .assert_backed(accounts.vault_token_account.amount)
+}
+
+/// Book a completed swap against the user's trade stats and rolling swap volume.
+pub fn record_swap(user: &mut UserAccount, amount_in: u64, fee: u64, now: i64) -> Result<()> {
+    user.total_trades = user
+        .total_trades
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+    record_swap_volume(user, amount_in, now)?;
+    user.total_fees_paid = user
+        .total_fees_paid
+        .checked_add(fee)
+        .ok_or(ErrorCode::MathOverflow)?;
+    user.last_activity = now;
+    Ok(())
+}
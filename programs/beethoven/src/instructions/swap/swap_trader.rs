@@ -0,0 +1,63 @@
+use anchor_lang::prelude::*;
+use crate::constants::USER_ACCOUNT_SEED;
+use crate::error::ErrorCode;
+use crate::state::{sub_account_seed, UserAccount};
+
+/// Who a swap trades as. Owners sign for themselves; delegates trade
+/// through the user account PDA, which the owner approves as SPL token
+/// delegate and which signs the venue CPIs and the fee transfer.
+pub struct SwapTrader {
+    user: Pubkey,
+    sub_account_id: [u8; 2],
+    bump: [u8; 1],
+    delegated: bool,
+}
+
+impl SwapTrader {
+    /// Check `authority` may swap for `user_account`: the owner, or a
+    /// delegate whose scope allows swaps.
+    pub fn authorize(user_account: &UserAccount, authority: &Pubkey, now: i64) -> Result<Self> {
+        let delegate = user_account.authorize_trader(authority, now)?;
+        if let Some(delegate) = &delegate {
+            require!(delegate.allow_swaps, ErrorCode::DelegateScopeExceeded);
+        }
+        Ok(Self {
+            user: user_account.owner,
+            sub_account_id: user_account.sub_account_id.to_le_bytes(),
+            bump: [user_account.bump],
+            delegated: delegate.is_some(),
+        })
+    }
+
+    /// Seeds of the user account PDA.
+    pub fn seeds(&self) -> [&[u8]; 4] {
+        [
+            USER_ACCOUNT_SEED,
+            self.user.as_ref(),
+            sub_account_seed(&self.sub_account_id),
+            &self.bump,
+        ]
+    }
+
+    /// `delegate_seeds` when a delegate trades, none for the owner.
+    pub fn signer_seeds<'a>(&self, delegate_seeds: &'a [&'a [&'a [u8]]]) -> &'a [&'a [&'a [u8]]] {
+        if self.delegated {
+            delegate_seeds
+        } else {
+            &[]
+        }
+    }
+
+    /// Signer of the fee transfer out of the user's input account.
+    pub fn transfer_authority<'info>(
+        &self,
+        user_account: &Account<'info, UserAccount>,
+        authority: &Signer<'info>,
+    ) -> AccountInfo<'info> {
+        if self.delegated {
+            user_account.to_account_info()
+        } else {
+            authority.to_account_info()
+        }
+    }
+}
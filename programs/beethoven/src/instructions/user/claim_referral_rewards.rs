@@ -0,0 +1,99 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::error::ErrorCode;
+use crate::events::ReferralRewardsClaimed;
+use crate::state::{ReferrerAccount, VaultBucket, VaultState};
+
+use anchor_spl::token::{TokenAccount, Token};
+
+#[derive(Accounts)]
+pub struct ClaimReferralRewards<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [REFERRER_SEED, owner.key().as_ref(), referrer_account.mint.as_ref()],
+        bump = referrer_account.bump,
+        constraint = referrer_account.owner == owner.key() @ ErrorCode::Unauthorized,
+    )]
+    pub referrer_account: Account<'info, ReferrerAccount>,
+
+    /// Any vault of the reward mint holding referral balance (lending or perp)
+    #[account(
+        mut,
+        seeds = [vault_state.seed_prefix(), vault_state.mint.as_ref()],
+        bump = vault_state.bump,
+        constraint = vault_state.mint == referrer_account.mint @ ErrorCode::InvalidParameter,
+    )]
+    pub vault_state: Account<'info, VaultState>,
+
+    #[account(
+        mut,
+        constraint = vault_token_account.key() == vault_state.token_account @ ErrorCode::InvalidParameter,
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = recipient_token_account.mint == referrer_account.mint @ ErrorCode::InvalidParameter,
+    )]
+    pub recipient_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+pub fn handler(ctx: Context<ClaimReferralRewards>) -> Result<()> {
+    // Rewards may be spread over several vaults of the mint; claim what this one holds
+    let amount = ctx
+        .accounts
+        .referrer_account
+        .claimable
+        .min(ctx.accounts.vault_state.referral_balance);
+    require!(amount > 0, ErrorCode::NoReferralRewards);
+
+    let vault_state = &mut ctx.accounts.vault_state;
+    vault_state.debit(VaultBucket::Referral, amount)?;
+
+    let mint_key = vault_state.mint;
+    let seeds = &[
+        vault_state.seed_prefix(),
+        mint_key.as_ref(),
+        &[vault_state.bump],
+    ];
+    let signer_seeds = &[&seeds[..]];
+
+    anchor_spl::token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            anchor_spl::token::Transfer {
+                from: ctx.accounts.vault_token_account.to_account_info(),
+                to: ctx.accounts.recipient_token_account.to_account_info(),
+                authority: vault_state.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        amount,
+    )?;
+
+    ctx.accounts.vault_token_account.reload()?;
+    ctx.accounts
+        .vault_state
+        .assert_backed(ctx.accounts.vault_token_account.amount)?;
+
+    let referrer = &mut ctx.accounts.referrer_account;
+    referrer.claimable -= amount;
+    referrer.total_claimed = referrer
+        .total_claimed
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    emit!(ReferralRewardsClaimed {
+        referrer: ctx.accounts.owner.key(),
+        mint: mint_key,
+        vault: ctx.accounts.vault_state.key(),
+        amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
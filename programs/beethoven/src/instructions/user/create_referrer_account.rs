@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::error::ErrorCode;
+use crate::state::{sub_account_seed, ReferrerAccount, UserAccount};
+
+use anchor_spl::token::Mint;
+
+#[derive(Accounts)]
+pub struct CreateReferrerAccount<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [
+            USER_ACCOUNT_SEED,
+            owner.key().as_ref(),
+            sub_account_seed(&user_account.sub_account_id.to_le_bytes()),
+        ],
+        bump = user_account.bump,
+        constraint = user_account.owner == owner.key() @ ErrorCode::Unauthorized,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    /// Mint the referral rewards accrue in
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = ReferrerAccount::LEN,
+        seeds = [REFERRER_SEED, owner.key().as_ref(), mint.key().as_ref()],
+        bump,
+    )]
+    pub referrer_account: Account<'info, ReferrerAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<CreateReferrerAccount>) -> Result<()> {
+    let referrer = &mut ctx.accounts.referrer_account;
+    referrer.owner = ctx.accounts.owner.key();
+    referrer.mint = ctx.accounts.mint.key();
+    referrer.bump = ctx.bumps.referrer_account;
+    referrer.claimable = 0;
+    referrer.total_earned = 0;
+    referrer.total_claimed = 0;
+    referrer.created_at = Clock::get()?.unix_timestamp;
+    referrer._reserved = [0u8; 32];
+
+    Ok(())
+}
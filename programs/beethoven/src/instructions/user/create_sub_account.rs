@@ -0,0 +1,80 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::error::ErrorCode;
+use crate::events::SubAccountCreated;
+use crate::state::{TradingDelegate, UserAccount};
+
+#[derive(Accounts)]
+#[instruction(sub_account_id: u16)]
+pub struct CreateSubAccount<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    /// The wallet's primary account; numbers its sub-accounts
+    #[account(
+        mut,
+        seeds = [USER_ACCOUNT_SEED, owner.key().as_ref()],
+        bump = primary_account.bump,
+        constraint = primary_account.owner == owner.key() @ ErrorCode::Unauthorized,
+    )]
+    pub primary_account: Account<'info, UserAccount>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = UserAccount::LEN,
+        seeds = [USER_ACCOUNT_SEED, owner.key().as_ref(), &sub_account_id.to_le_bytes()],
+        bump,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<CreateSubAccount>, sub_account_id: u16) -> Result<()> {
+    let primary = &mut ctx.accounts.primary_account;
+    require!(
+        primary.sub_account_count < MAX_SUB_ACCOUNTS,
+        ErrorCode::MaxSubAccountsReached
+    );
+
+    // Sub-accounts are numbered sequentially from 1
+    require!(
+        sub_account_id == primary.sub_account_count + 1,
+        ErrorCode::InvalidSubAccountId
+    );
+    primary.sub_account_count = sub_account_id;
+    let referrer = primary.referrer;
+
+    let user = &mut ctx.accounts.user_account;
+    let clock = Clock::get()?;
+
+    user.owner = ctx.accounts.owner.key();
+    user.bump = ctx.bumps.user_account;
+    user.sub_account_id = sub_account_id;
+    user.sub_account_count = 0;
+    user.open_perp_positions = 0;
+    user.open_lending_positions = 0;
+    user.total_trades = 0;
+    user.total_pnl = 0;
+    user.total_volume = 0;
+    user.total_fees_paid = 0;
+    user.daily_volume = [0u64; VOLUME_WINDOW_DAYS];
+    user.last_volume_day = 0;
+    user.daily_swap_volume = [0u64; VOLUME_WINDOW_DAYS];
+    user.last_swap_volume_day = 0;
+    user.referrer = referrer;
+    user.delegate = TradingDelegate::default();
+    user.created_at = clock.unix_timestamp;
+    user.last_activity = clock.unix_timestamp;
+    user._reserved = [0u8; 44];
+
+    emit!(SubAccountCreated {
+        owner: ctx.accounts.owner.key(),
+        user_account: ctx.accounts.user_account.key(),
+        sub_account_id,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
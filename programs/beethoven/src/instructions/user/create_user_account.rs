@@ -1,7 +1,7 @@
 use anchor_lang::prelude::*;
 use crate::constants::*;
 use crate::error::ErrorCode;
-use crate::state::{Exchange, UserAccount};
+use crate::state::{Exchange, TradingDelegate, UserAccount};
 
 #[derive(Accounts)]
 pub struct CreateUserAccount<'info> {
@@ -24,25 +24,56 @@ pub struct CreateUserAccount<'info> {
     )]
     pub user_account: Account<'info, UserAccount>,
 
+    /// The referrer's user account; required when a referrer is given
+    pub referrer_user_account: Option<Account<'info, UserAccount>>,
+
     pub system_program: Program<'info, System>,
 }
 
 pub fn handler(ctx: Context<CreateUserAccount>, referrer: Option<Pubkey>) -> Result<()> {
+    let owner_key = ctx.accounts.owner.key();
+
+    // Referrers are fixed at creation and must already have an account, so a
+    // referral chain can only point at older accounts and never loops back
+    if let Some(referrer) = referrer {
+        require!(referrer != owner_key, ErrorCode::SelfReferral);
+        let referrer_account = ctx
+            .accounts
+            .referrer_user_account
+            .as_ref()
+            .ok_or(ErrorCode::InvalidReferrer)?;
+        require!(
+            referrer_account.owner == referrer,
+            ErrorCode::InvalidReferrer
+        );
+        require!(
+            referrer_account.referrer != owner_key,
+            ErrorCode::ReferralCycle
+        );
+    }
+
     let user = &mut ctx.accounts.user_account;
     let clock = Clock::get()?;
 
-    user.owner = ctx.accounts.owner.key();
+    user.owner = owner_key;
     user.bump = ctx.bumps.user_account;
+    user.sub_account_id = 0;
+    user.sub_account_count = 0;
     user.open_perp_positions = 0;
     user.open_lending_positions = 0;
     user.total_trades = 0;
     user.total_pnl = 0;
     user.total_volume = 0;
     user.total_fees_paid = 0;
+    user.daily_volume = [0u64; VOLUME_WINDOW_DAYS];
+    user.last_volume_day = 0;
+    user.daily_swap_volume = [0u64; VOLUME_WINDOW_DAYS];
+    user.last_swap_volume_day = 0;
     user.referrer = referrer.unwrap_or_default();
+    user.delegate = TradingDelegate::default();
     user.created_at = clock.unix_timestamp;
     user.last_activity = clock.unix_timestamp;
-    user._reserved = [0u8; 64];
+    user._reserved = [0u8; 44];
 
     let exchange = &mut ctx.accounts.exchange;
     exchange.total_users = exchange
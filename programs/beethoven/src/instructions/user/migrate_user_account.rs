@@ -0,0 +1,43 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use crate::events::AccountMigrated;
+use crate::state::{grow_account, UserAccount};
+
+/// Grow a user account created before fields were appended after its
+/// `_reserved` bytes. Until then the account no longer deserializes, so
+/// this runs before any other instruction touches it. Permissionless: the
+/// payer only covers the extra rent.
+#[derive(Accounts)]
+pub struct MigrateUserAccount<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Older layout that no longer deserializes; owner and
+    /// discriminator are checked in grow_account
+    #[account(mut)]
+    pub user_account: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn handler(ctx: Context<MigrateUserAccount>) -> Result<()> {
+    let account = ctx.accounts.user_account.to_account_info();
+    let old_len = account.data_len();
+
+    grow_account(
+        &account,
+        UserAccount::DISCRIMINATOR,
+        UserAccount::LEN,
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+    )?;
+
+    emit!(AccountMigrated {
+        account: account.key(),
+        old_len: old_len as u32,
+        new_len: UserAccount::LEN as u32,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -1,3 +1,15 @@
 pub mod create_user_account;
+pub mod create_sub_account;
+pub mod create_referrer_account;
+pub mod claim_referral_rewards;
+pub mod set_delegate;
+pub mod revoke_delegate;
+pub mod migrate_user_account;
 
 pub use create_user_account::*;
+pub use create_sub_account::*;
+pub use create_referrer_account::*;
+pub use claim_referral_rewards::*;
+pub use set_delegate::*;
+pub use revoke_delegate::*;
+pub use migrate_user_account::*;
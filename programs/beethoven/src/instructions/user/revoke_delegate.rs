@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::error::ErrorCode;
+use crate::events::DelegateRevoked;
+use crate::state::{sub_account_seed, TradingDelegate, UserAccount};
+
+#[derive(Accounts)]
+pub struct RevokeDelegate<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            USER_ACCOUNT_SEED,
+            owner.key().as_ref(),
+            sub_account_seed(&user_account.sub_account_id.to_le_bytes()),
+        ],
+        bump = user_account.bump,
+        constraint = user_account.owner == owner.key() @ ErrorCode::Unauthorized,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+}
+
+pub fn handler(ctx: Context<RevokeDelegate>) -> Result<()> {
+    let user = &mut ctx.accounts.user_account;
+    require!(user.delegate.is_set(), ErrorCode::NoDelegate);
+
+    let delegate = user.delegate.authority;
+    user.delegate = TradingDelegate::default();
+
+    emit!(DelegateRevoked {
+        owner: ctx.accounts.owner.key(),
+        delegate,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+use crate::constants::*;
+use crate::error::ErrorCode;
+use crate::events::DelegateUpdated;
+use crate::state::{sub_account_seed, TradingDelegate, UserAccount};
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct SetDelegateParams {
+    pub delegate: Pubkey,
+    pub expires_at: i64,   // 0 = never expires
+    pub max_notional: u64, // Per-position cap in collateral units (0 = unlimited)
+    pub allow_swaps: bool,
+    pub markets: Vec<u16>, // Allowed perp market indexes (empty = all)
+}
+
+#[derive(Accounts)]
+pub struct SetDelegate<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            USER_ACCOUNT_SEED,
+            owner.key().as_ref(),
+            sub_account_seed(&user_account.sub_account_id.to_le_bytes()),
+        ],
+        bump = user_account.bump,
+        constraint = user_account.owner == owner.key() @ ErrorCode::Unauthorized,
+    )]
+    pub user_account: Account<'info, UserAccount>,
+}
+
+pub fn handler(ctx: Context<SetDelegate>, params: SetDelegateParams) -> Result<()> {
+    let clock = Clock::get()?;
+
+    require!(
+        params.delegate != Pubkey::default() && params.delegate != ctx.accounts.owner.key(),
+        ErrorCode::InvalidDelegate
+    );
+    require!(
+        params.expires_at == 0 || params.expires_at > clock.unix_timestamp,
+        ErrorCode::InvalidDelegate
+    );
+    require!(
+        params.markets.len() <= MAX_DELEGATE_MARKETS,
+        ErrorCode::InvalidDelegate
+    );
+
+    let mut markets = [0u16; MAX_DELEGATE_MARKETS];
+    markets[..params.markets.len()].copy_from_slice(&params.markets);
+
+    let user = &mut ctx.accounts.user_account;
+    user.delegate = TradingDelegate {
+        authority: params.delegate,
+        expires_at: params.expires_at,
+        max_notional: params.max_notional,
+        allow_swaps: params.allow_swaps,
+        market_count: params.markets.len() as u8,
+        markets,
+    };
+
+    emit!(DelegateUpdated {
+        owner: ctx.accounts.owner.key(),
+        delegate: params.delegate,
+        expires_at: params.expires_at,
+        max_notional: params.max_notional,
+        allow_swaps: params.allow_swaps,
+        markets: params.markets,
+        timestamp: clock.unix_timestamp,
+    });
+
+    Ok(())
+}
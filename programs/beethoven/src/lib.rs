@@ -29,6 +29,13 @@ pub mod beethoven {
         instructions::admin::initialize_exchange::handler(ctx, params)
     }
 
+    pub fn create_perp_lp_pool(
+        ctx: Context<CreatePerpLpPool>,
+        params: instructions::admin::create_perp_lp_pool::CreatePerpLpPoolParams,
+    ) -> Result<()> {
+        instructions::admin::create_perp_lp_pool::handler(ctx, params)
+    }
+
     pub fn create_perp_market(
         ctx: Context<CreatePerpMarket>,
         params: instructions::admin::create_perp_market::CreatePerpMarketParams,
@@ -36,6 +43,66 @@ pub mod beethoven {
         instructions::admin::create_perp_market::handler(ctx, params)
     }
 
+    pub fn update_perp_market_limits(
+        ctx: Context<UpdatePerpMarketLimits>,
+        params: instructions::admin::update_perp_market_limits::UpdatePerpMarketLimitsParams,
+    ) -> Result<()> {
+        instructions::admin::update_perp_market_limits::handler(ctx, params)
+    }
+
+    pub fn set_margin_tiers(
+        ctx: Context<SetMarginTiers>,
+        params: instructions::admin::set_margin_tiers::SetMarginTiersParams,
+    ) -> Result<()> {
+        instructions::admin::set_margin_tiers::handler(ctx, params)
+    }
+
+    pub fn set_circuit_breaker(
+        ctx: Context<SetCircuitBreaker>,
+        params: instructions::admin::set_circuit_breaker::SetCircuitBreakerParams,
+    ) -> Result<()> {
+        instructions::admin::set_circuit_breaker::handler(ctx, params)
+    }
+
+    pub fn set_fee_tiers(
+        ctx: Context<SetFeeTiers>,
+        params: instructions::admin::set_fee_tiers::SetFeeTiersParams,
+    ) -> Result<()> {
+        instructions::admin::set_fee_tiers::handler(ctx, params)
+    }
+
+    pub fn set_referral_params(
+        ctx: Context<SetReferralParams>,
+        params: instructions::admin::set_referral_params::SetReferralParamsParams,
+    ) -> Result<()> {
+        instructions::admin::set_referral_params::handler(ctx, params)
+    }
+
+    pub fn initialize_protocol_registry(ctx: Context<InitializeProtocolRegistry>) -> Result<()> {
+        instructions::admin::initialize_protocol_registry::handler(ctx)
+    }
+
+    pub fn set_protocol_entry(
+        ctx: Context<SetProtocolEntry>,
+        params: instructions::admin::set_protocol_entry::SetProtocolEntryParams,
+    ) -> Result<()> {
+        instructions::admin::set_protocol_entry::handler(ctx, params)
+    }
+
+    pub fn create_spot_market(
+        ctx: Context<CreateSpotMarket>,
+        params: instructions::admin::create_spot_market::CreateSpotMarketParams,
+    ) -> Result<()> {
+        instructions::admin::create_spot_market::handler(ctx, params)
+    }
+
+    pub fn create_swap_pool(
+        ctx: Context<CreateSwapPool>,
+        params: instructions::admin::create_swap_pool::CreateSwapPoolParams,
+    ) -> Result<()> {
+        instructions::admin::create_swap_pool::handler(ctx, params)
+    }
+
     pub fn create_lending_pool(
         ctx: Context<CreateLendingPool>,
         params: instructions::admin::create_lending_pool::CreateLendingPoolParams,
@@ -47,10 +114,48 @@ pub mod beethoven {
         instructions::admin::update_funding_rate::handler(ctx)
     }
 
+    pub fn update_circuit_breaker(ctx: Context<UpdateCircuitBreaker>) -> Result<()> {
+        instructions::admin::update_circuit_breaker::handler(ctx)
+    }
+
+    pub fn consume_spot_events<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ConsumeSpotEvents<'info>>,
+        limit: u16,
+    ) -> Result<()> {
+        instructions::admin::consume_spot_events::handler(ctx, limit)
+    }
+
     pub fn collect_fees(ctx: Context<CollectFees>, amount: u64) -> Result<()> {
         instructions::admin::collect_fees::handler(ctx, amount)
     }
 
+    pub fn collect_swap_pool_fees(ctx: Context<CollectSwapPoolFees>) -> Result<()> {
+        instructions::admin::collect_swap_pool_fees::handler(ctx)
+    }
+
+    pub fn fund_insurance(ctx: Context<FundInsurance>, amount: u64) -> Result<()> {
+        instructions::admin::fund_insurance::handler(ctx, amount)
+    }
+
+    pub fn create_price_accumulator(
+        ctx: Context<CreatePriceAccumulator>,
+        params: instructions::admin::create_price_accumulator::CreatePriceAccumulatorParams,
+    ) -> Result<()> {
+        instructions::admin::create_price_accumulator::handler(ctx, params)
+    }
+
+    pub fn update_price_accumulator(ctx: Context<UpdatePriceAccumulator>) -> Result<()> {
+        instructions::admin::update_price_accumulator::handler(ctx)
+    }
+
+    pub fn migrate_perp_market(ctx: Context<MigratePerpMarket>) -> Result<()> {
+        instructions::admin::migrate_perp_market::handler(ctx)
+    }
+
+    pub fn migrate_vault(ctx: Context<MigrateVault>, perp_margin: u64) -> Result<()> {
+        instructions::admin::migrate_vault::handler(ctx, perp_margin)
+    }
+
     // ── User ────────────────────────────────────────────────
 
     pub fn create_user_account(
@@ -60,6 +165,33 @@ pub mod beethoven {
         instructions::user::create_user_account::handler(ctx, referrer)
     }
 
+    pub fn create_sub_account(ctx: Context<CreateSubAccount>, sub_account_id: u16) -> Result<()> {
+        instructions::user::create_sub_account::handler(ctx, sub_account_id)
+    }
+
+    pub fn create_referrer_account(ctx: Context<CreateReferrerAccount>) -> Result<()> {
+        instructions::user::create_referrer_account::handler(ctx)
+    }
+
+    pub fn claim_referral_rewards(ctx: Context<ClaimReferralRewards>) -> Result<()> {
+        instructions::user::claim_referral_rewards::handler(ctx)
+    }
+
+    pub fn set_delegate(
+        ctx: Context<SetDelegate>,
+        params: instructions::user::set_delegate::SetDelegateParams,
+    ) -> Result<()> {
+        instructions::user::set_delegate::handler(ctx, params)
+    }
+
+    pub fn revoke_delegate(ctx: Context<RevokeDelegate>) -> Result<()> {
+        instructions::user::revoke_delegate::handler(ctx)
+    }
+
+    pub fn migrate_user_account(ctx: Context<MigrateUserAccount>) -> Result<()> {
+        instructions::user::migrate_user_account::handler(ctx)
+    }
+
     // ── Swap ────────────────────────────────────────────────
 
     pub fn execute_swap<'info>(
@@ -69,18 +201,68 @@ pub mod beethoven {
         instructions::swap::execute_swap::handler(ctx, params)
     }
 
+    pub fn execute_route<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteRoute<'info>>,
+        params: instructions::swap::execute_route::ExecuteRouteParams,
+    ) -> Result<()> {
+        instructions::swap::execute_route::handler(ctx, params)
+    }
+
+    pub fn execute_split_swap<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ExecuteSplitSwap<'info>>,
+        params: instructions::swap::execute_split_swap::ExecuteSplitSwapParams,
+    ) -> Result<()> {
+        instructions::swap::execute_split_swap::handler(ctx, params)
+    }
+
     pub fn add_liquidity<'info>(
         ctx: Context<'_, '_, 'info, 'info, AddLiquidity<'info>>,
         amount: u64,
+        protocol: Pubkey,
     ) -> Result<()> {
-        instructions::swap::add_liquidity::handler(ctx, amount)
+        instructions::swap::add_liquidity::handler(ctx, amount, protocol)
     }
 
     pub fn remove_liquidity<'info>(
         ctx: Context<'_, '_, 'info, 'info, RemoveLiquidity<'info>>,
         amount: u64,
+        protocol: Pubkey,
+    ) -> Result<()> {
+        instructions::swap::remove_liquidity::handler(ctx, amount, protocol)
+    }
+
+    pub fn add_pool_liquidity(
+        ctx: Context<AddPoolLiquidity>,
+        params: instructions::swap::add_pool_liquidity::AddPoolLiquidityParams,
+    ) -> Result<()> {
+        instructions::swap::add_pool_liquidity::handler(ctx, params)
+    }
+
+    pub fn remove_pool_liquidity(
+        ctx: Context<RemovePoolLiquidity>,
+        params: instructions::swap::remove_pool_liquidity::RemovePoolLiquidityParams,
     ) -> Result<()> {
-        instructions::swap::remove_liquidity::handler(ctx, amount)
+        instructions::swap::remove_pool_liquidity::handler(ctx, params)
+    }
+
+    // ── Spot ────────────────────────────────────────────────
+
+    pub fn place_spot_order(
+        ctx: Context<PlaceSpotOrder>,
+        params: instructions::spot::place_spot_order::PlaceSpotOrderParams,
+    ) -> Result<()> {
+        instructions::spot::place_spot_order::handler(ctx, params)
+    }
+
+    pub fn cancel_spot_order(
+        ctx: Context<CancelSpotOrder>,
+        params: instructions::spot::cancel_spot_order::CancelSpotOrderParams,
+    ) -> Result<()> {
+        instructions::spot::cancel_spot_order::handler(ctx, params)
+    }
+
+    pub fn settle_spot_funds(ctx: Context<SettleSpotFunds>) -> Result<()> {
+        instructions::spot::settle_spot_funds::handler(ctx)
     }
 
     // ── Perpetuals ──────────────────────────────────────────
@@ -96,10 +278,28 @@ pub mod beethoven {
         instructions::perp::close_position::handler(ctx)
     }
 
+    pub fn remove_margin(ctx: Context<RemoveMargin>, amount: u64) -> Result<()> {
+        instructions::perp::remove_margin::handler(ctx, amount)
+    }
+
     pub fn liquidate_perp(ctx: Context<LiquidatePerp>) -> Result<()> {
         instructions::perp::liquidate_perp::handler(ctx)
     }
 
+    pub fn deposit_perp_liquidity<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DepositPerpLiquidity<'info>>,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::perp::deposit_perp_liquidity::handler(ctx, amount)
+    }
+
+    pub fn withdraw_perp_liquidity<'info>(
+        ctx: Context<'_, '_, 'info, 'info, WithdrawPerpLiquidity<'info>>,
+        shares: u64,
+    ) -> Result<()> {
+        instructions::perp::withdraw_perp_liquidity::handler(ctx, shares)
+    }
+
     // ── Lending ─────────────────────────────────────────────
 
     pub fn deposit_collateral(ctx: Context<DepositCollateral>, amount: u64) -> Result<()> {
@@ -110,6 +310,10 @@ pub mod beethoven {
         instructions::lending::withdraw_collateral::handler(ctx, amount)
     }
 
+    pub fn transfer_lending_deposit(ctx: Context<TransferLendingDeposit>, amount: u64) -> Result<()> {
+        instructions::lending::transfer_lending_deposit::handler(ctx, amount)
+    }
+
     pub fn borrow(ctx: Context<Borrow>, amount: u64) -> Result<()> {
         instructions::lending::borrow::handler(ctx, amount)
     }
@@ -164,4 +368,10 @@ pub mod beethoven {
     ) -> Result<()> {
         instructions::fund::execute_proposal::handler(ctx)
     }
+
+    // ── Oracle ──────────────────────────────────────────────
+
+    pub fn read_price_accumulator(ctx: Context<ReadPriceAccumulator>, window: i64) -> Result<()> {
+        instructions::oracle::read_price_accumulator::handler(ctx, window)
+    }
 }
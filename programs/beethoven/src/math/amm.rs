@@ -0,0 +1,163 @@
+use anchor_lang::prelude::*;
+use crate::constants::{BPS_DENOMINATOR, MINIMUM_POOL_LIQUIDITY};
+use crate::error::ErrorCode;
+use crate::math::fixed_point::bps_mul;
+
+pub struct SwapQuote {
+    pub amount_in: u64, // Including the fee
+    pub amount_out: u64,
+    pub fee: u64,          // Total swap fee, in the input token
+    pub protocol_fee: u64, // Part of `fee` owed to the protocol vault
+}
+
+/// Integer square root (floor).
+pub fn isqrt(value: u128) -> u128 {
+    if value < 2 {
+        return value;
+    }
+    let mut x = value;
+    let mut y = x.div_ceil(2);
+    while y < x {
+        x = y;
+        y = (x + value / x) / 2;
+    }
+    x
+}
+
+/// Output of an exact-in swap against x * y = k. The LP share of the fee
+/// stays in the input reserve; the protocol share is set aside.
+pub fn swap_exact_in(
+    reserve_in: u64,
+    reserve_out: u64,
+    amount_in: u64,
+    fee_bps: u64,
+    protocol_fee_share_bps: u64,
+) -> Result<SwapQuote> {
+    require!(reserve_in > 0 && reserve_out > 0, ErrorCode::InsufficientReserves);
+
+    let fee = bps_mul(amount_in, fee_bps)?;
+    let protocol_fee = bps_mul(fee, protocol_fee_share_bps)?;
+    let net_in = amount_in.checked_sub(fee).ok_or(ErrorCode::MathUnderflow)? as u128;
+
+    let amount_out = (reserve_out as u128)
+        .checked_mul(net_in)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_div(
+            (reserve_in as u128)
+                .checked_add(net_in)
+                .ok_or(ErrorCode::MathOverflow)?,
+        )
+        .ok_or(ErrorCode::DivisionByZero)?;
+
+    Ok(SwapQuote {
+        amount_in,
+        amount_out: u64::try_from(amount_out).map_err(|_| ErrorCode::MathOverflow)?,
+        fee,
+        protocol_fee,
+    })
+}
+
+/// Input needed for an exact-out swap against x * y = k, rounded up so the
+/// pool never loses to rounding. The fee is grossed up on top of the net input.
+pub fn swap_exact_out(
+    reserve_in: u64,
+    reserve_out: u64,
+    amount_out: u64,
+    fee_bps: u64,
+    protocol_fee_share_bps: u64,
+) -> Result<SwapQuote> {
+    require!(
+        reserve_in > 0 && amount_out < reserve_out,
+        ErrorCode::InsufficientReserves
+    );
+
+    let net_in = mul_div_up(
+        reserve_in as u128,
+        amount_out as u128,
+        (reserve_out - amount_out) as u128,
+    )?;
+    let amount_in = mul_div_up(
+        net_in,
+        BPS_DENOMINATOR as u128,
+        BPS_DENOMINATOR
+            .checked_sub(fee_bps)
+            .ok_or(ErrorCode::MathUnderflow)? as u128,
+    )?;
+    let amount_in = u64::try_from(amount_in).map_err(|_| ErrorCode::MathOverflow)?;
+    let fee = amount_in - net_in as u64;
+
+    Ok(SwapQuote {
+        amount_in,
+        amount_out,
+        fee,
+        protocol_fee: bps_mul(fee, protocol_fee_share_bps)?,
+    })
+}
+
+/// Shares for a deposit and the amounts of each token it uses. The first
+/// deposit sets the price and mints sqrt(a * b); later deposits are capped
+/// by the scarcer side and take the other side pro rata, rounded up.
+pub fn deposit_shares(
+    amount_a: u64,
+    amount_b: u64,
+    reserve_a: u64,
+    reserve_b: u64,
+    total_shares: u64,
+) -> Result<(u64, u64, u64)> {
+    if total_shares == 0 {
+        let shares = isqrt(
+            (amount_a as u128)
+                .checked_mul(amount_b as u128)
+                .ok_or(ErrorCode::MathOverflow)?,
+        );
+        require!(
+            shares > MINIMUM_POOL_LIQUIDITY as u128,
+            ErrorCode::InitialLiquidityTooLow
+        );
+        let shares = u64::try_from(shares).map_err(|_| ErrorCode::MathOverflow)?;
+        return Ok((shares, amount_a, amount_b));
+    }
+
+    require!(reserve_a > 0 && reserve_b > 0, ErrorCode::InsufficientReserves);
+    let total = total_shares as u128;
+    let shares = mul_div(amount_a as u128, total, reserve_a as u128)?
+        .min(mul_div(amount_b as u128, total, reserve_b as u128)?);
+
+    let used_a = mul_div_up(shares, reserve_a as u128, total)?;
+    let used_b = mul_div_up(shares, reserve_b as u128, total)?;
+
+    Ok((
+        u64::try_from(shares).map_err(|_| ErrorCode::MathOverflow)?,
+        u64::try_from(used_a).map_err(|_| ErrorCode::MathOverflow)?,
+        u64::try_from(used_b).map_err(|_| ErrorCode::MathOverflow)?,
+    ))
+}
+
+/// Reserves returned for burning `shares`, rounded down.
+pub fn withdraw_amounts(
+    shares: u64,
+    reserve_a: u64,
+    reserve_b: u64,
+    total_shares: u64,
+) -> Result<(u64, u64)> {
+    require!(shares <= total_shares, ErrorCode::InsufficientReserves);
+    let amount_a = mul_div(shares as u128, reserve_a as u128, total_shares as u128)?;
+    let amount_b = mul_div(shares as u128, reserve_b as u128, total_shares as u128)?;
+    Ok((
+        u64::try_from(amount_a).map_err(|_| ErrorCode::MathOverflow)?,
+        u64::try_from(amount_b).map_err(|_| ErrorCode::MathOverflow)?,
+    ))
+}
+
+fn mul_div(a: u128, b: u128, denominator: u128) -> Result<u128> {
+    a.checked_mul(b)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_div(denominator)
+        .ok_or(ErrorCode::DivisionByZero.into())
+}
+
+fn mul_div_up(a: u128, b: u128, denominator: u128) -> Result<u128> {
+    require!(denominator > 0, ErrorCode::DivisionByZero);
+    let product = a.checked_mul(b).ok_or(ErrorCode::MathOverflow)?;
+    Ok(product.div_ceil(denominator))
+}
@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use crate::constants::BPS_DENOMINATOR;
+use crate::error::ErrorCode;
+use crate::state::CircuitBreaker;
+
+/// A trip recorded by `refresh_circuit_breaker`.
+#[derive(Clone, Copy, Debug)]
+pub struct CircuitBreakerTrip {
+    pub reference_price: u64, // Reference the move was measured against
+    pub move_bps: u64,
+}
+
+/// Advance the breaker with a fresh oracle price.
+/// Clears an expired trip and trips when `price` is `threshold_bps` or more
+/// away from the reference. The comparison uses the current reference even
+/// when its window has elapsed, so a move that lands as the window closes
+/// still trips; the reference then rolls to `price`. Returns the trip when
+/// this call trips the breaker.
+pub fn refresh_circuit_breaker(
+    breaker: &mut CircuitBreaker,
+    price: u64,
+    now: i64,
+) -> Result<Option<CircuitBreakerTrip>> {
+    if breaker.threshold_bps == 0 {
+        return Ok(None);
+    }
+
+    // Cooldown over: resume normal trading from a fresh reference
+    if breaker.tripped_at != 0 && !breaker.is_tripped(now) {
+        breaker.tripped_at = 0;
+        breaker.reference_price = 0;
+    }
+
+    let reference_price = breaker.reference_price;
+    if reference_price == 0 {
+        breaker.reference_price = price;
+        breaker.window_start = now;
+        return Ok(None);
+    }
+
+    let move_bps = (price.abs_diff(reference_price) as u128)
+        .checked_mul(BPS_DENOMINATOR as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_div(reference_price as u128)
+        .ok_or(ErrorCode::DivisionByZero)?;
+
+    if now >= breaker.window_start.saturating_add(breaker.window) {
+        breaker.reference_price = price;
+        breaker.window_start = now;
+    }
+
+    if breaker.tripped_at != 0 || move_bps < breaker.threshold_bps as u128 {
+        return Ok(None);
+    }
+
+    breaker.tripped_at = now;
+    Ok(Some(CircuitBreakerTrip {
+        reference_price,
+        move_bps: u64::try_from(move_bps).unwrap_or(u64::MAX),
+    }))
+}
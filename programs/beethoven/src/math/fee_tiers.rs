@@ -0,0 +1,113 @@
+use anchor_lang::prelude::*;
+use crate::constants::{BPS_DENOMINATOR, SECONDS_PER_DAY, VOLUME_WINDOW_DAYS};
+use crate::error::ErrorCode;
+use crate::state::{Exchange, UserAccount};
+
+const WINDOW: i64 = VOLUME_WINDOW_DAYS as i64;
+
+fn day_number(now: i64) -> i64 {
+    now.div_euclid(SECONDS_PER_DAY)
+}
+
+fn bucket_index(day: i64) -> usize {
+    day.rem_euclid(WINDOW) as usize
+}
+
+/// Add `amount` to today's bucket of a rolling window whose newest bucket is
+/// `last_day`. Buckets for days skipped since then are cleared first.
+fn add_to_window(
+    last_day: &mut i64,
+    buckets: &mut [u64; VOLUME_WINDOW_DAYS],
+    amount: u64,
+    now: i64,
+) -> Result<()> {
+    let today = day_number(now);
+
+    if today > *last_day {
+        let stale_days = (today - *last_day).min(WINDOW);
+        for day in (today - stale_days + 1)..=today {
+            buckets[bucket_index(day)] = 0;
+        }
+        *last_day = today;
+    }
+
+    let bucket = &mut buckets[bucket_index(*last_day)];
+    *bucket = bucket.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+    Ok(())
+}
+
+/// Sum of a rolling window over the last VOLUME_WINDOW_DAYS days, including today.
+fn window_total(last_day: i64, buckets: &[u64; VOLUME_WINDOW_DAYS], now: i64) -> Result<u64> {
+    let today = day_number(now);
+    let oldest = today - WINDOW + 1;
+    if last_day < oldest {
+        return Ok(0);
+    }
+
+    let mut total: u64 = 0;
+    for day in oldest.max(last_day - WINDOW + 1)..=last_day {
+        total = total
+            .checked_add(buckets[bucket_index(day)])
+            .ok_or(ErrorCode::MathOverflow)?;
+    }
+    Ok(total)
+}
+
+/// Add trade notional to the user's lifetime total and rolling daily buckets.
+pub fn record_volume(user: &mut UserAccount, amount: u64, now: i64) -> Result<()> {
+    add_to_window(&mut user.last_volume_day, &mut user.daily_volume, amount, now)?;
+    user.total_volume = user
+        .total_volume
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    Ok(())
+}
+
+/// Notional traded over the last VOLUME_WINDOW_DAYS days, including today.
+pub fn rolling_volume(user: &UserAccount, now: i64) -> Result<u64> {
+    window_total(user.last_volume_day, &user.daily_volume, now)
+}
+
+/// Add swap input to the user's lifetime total and rolling swap buckets.
+/// Swap input is in the input token's units, so it stays out of the
+/// notional buckets.
+pub fn record_swap_volume(user: &mut UserAccount, amount: u64, now: i64) -> Result<()> {
+    add_to_window(
+        &mut user.last_swap_volume_day,
+        &mut user.daily_swap_volume,
+        amount,
+        now,
+    )?;
+    user.total_volume = user
+        .total_volume
+        .checked_add(amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    Ok(())
+}
+
+/// Swap input over the last VOLUME_WINDOW_DAYS days, including today.
+pub fn rolling_swap_volume(user: &UserAccount, now: i64) -> Result<u64> {
+    window_total(user.last_swap_volume_day, &user.daily_swap_volume, now)
+}
+
+/// Highest fee tier the volume qualifies for as (tier index, discount bps).
+/// No tier and no discount when the volume is below every tier.
+pub fn resolve_fee_tier(exchange: &Exchange, volume: u64) -> (Option<u8>, u64) {
+    exchange.fee_tiers[..exchange.fee_tier_count as usize]
+        .iter()
+        .enumerate()
+        .rev()
+        .find(|(_, tier)| volume >= tier.min_volume)
+        .map(|(index, tier)| (Some(index as u8), tier.discount_bps))
+        .unwrap_or((None, 0))
+}
+
+/// Apply a tier discount to a base fee rate in bps.
+pub fn discounted_fee_bps(fee_bps: u64, discount_bps: u64) -> Result<u64> {
+    let discount = (fee_bps as u128)
+        .checked_mul(discount_bps as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_div(BPS_DENOMINATOR as u128)
+        .ok_or(ErrorCode::DivisionByZero)? as u64;
+    Ok(fee_bps.saturating_sub(discount))
+}
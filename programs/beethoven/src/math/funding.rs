@@ -1,8 +1,8 @@
 use anchor_lang::prelude::*;
-use crate::constants::{WAD, MAX_FUNDING_RATE};
+use crate::constants::{WAD, MAX_FUNDING_RATE, PRICE_PRECISION};
 use crate::error::ErrorCode;
 use crate::math::fixed_point::wad_mul_signed;
-use crate::state::PerpMarket;
+use crate::state::{ContractType, PerpMarket};
 
 /// Calculate the funding rate based on OI imbalance.
 /// Positive rate = longs pay shorts; negative = shorts pay longs.
@@ -57,3 +57,23 @@ pub fn compute_position_funding(
 
     i64::try_from(payment).map_err(|_| ErrorCode::MathOverflow.into())
 }
+
+/// Convert a funding payment to collateral units. Payments accrue on size,
+/// which inverse markets denominate in quote units.
+pub fn funding_in_collateral(
+    contract_type: ContractType,
+    payment: i64,
+    price: u64,
+) -> Result<i64> {
+    match contract_type {
+        ContractType::Linear => Ok(payment),
+        ContractType::Inverse => {
+            let converted = (payment as i128)
+                .checked_mul(PRICE_PRECISION as i128)
+                .ok_or(ErrorCode::MathOverflow)?
+                .checked_div(price as i128)
+                .ok_or(ErrorCode::DivisionByZero)?;
+            i64::try_from(converted).map_err(|_| ErrorCode::MathOverflow.into())
+        }
+    }
+}
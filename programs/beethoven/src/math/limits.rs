@@ -0,0 +1,98 @@
+use anchor_lang::prelude::*;
+use crate::error::ErrorCode;
+use crate::math::liquidation::position_notional;
+use crate::state::perp_position::PositionSide;
+use crate::state::{PerpMarket, UserMarketStats};
+
+/// Check notional risk limits for adding `size` to `side`, valued at `price`.
+/// Call with the market's open interest *before* the new position is added.
+/// A limit of 0 disables that check.
+pub fn check_open_interest_limits(
+    market: &PerpMarket,
+    side: &PositionSide,
+    size: u64,
+    price: u64,
+) -> Result<()> {
+    let (mut long_oi, mut short_oi) = (market.long_open_interest, market.short_open_interest);
+    let old_skew = long_oi.abs_diff(short_oi);
+
+    match side {
+        PositionSide::Long => {
+            long_oi = long_oi.checked_add(size).ok_or(ErrorCode::MathOverflow)?;
+        }
+        PositionSide::Short => {
+            short_oi = short_oi.checked_add(size).ok_or(ErrorCode::MathOverflow)?;
+        }
+    }
+
+    // Per-side cap
+    if market.max_oi_notional > 0 {
+        let side_oi = match side {
+            PositionSide::Long => long_oi,
+            PositionSide::Short => short_oi,
+        };
+        require!(
+            position_notional(market.contract_type, side_oi, price)? <= market.max_oi_notional,
+            ErrorCode::OpenInterestNotionalExceeded
+        );
+    }
+
+    // Skew cap; trades that shrink the skew are always allowed
+    if market.max_skew_notional > 0 {
+        let new_skew = long_oi.abs_diff(short_oi);
+        require!(
+            new_skew <= old_skew
+                || position_notional(market.contract_type, new_skew, price)? <= market.max_skew_notional,
+            ErrorCode::SkewLimitExceeded
+        );
+    }
+
+    Ok(())
+}
+
+/// Check a user's total exposure in the market stays under `max_user_notional`.
+pub fn check_user_notional(
+    market: &PerpMarket,
+    stats: &UserMarketStats,
+    size: u64,
+    price: u64,
+) -> Result<()> {
+    if market.max_user_notional == 0 {
+        return Ok(());
+    }
+
+    let total_size = stats
+        .long_size
+        .checked_add(stats.short_size)
+        .and_then(|total| total.checked_add(size))
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(
+        position_notional(market.contract_type, total_size, price)? <= market.max_user_notional,
+        ErrorCode::UserNotionalLimitExceeded
+    );
+    Ok(())
+}
+
+/// Record a position's size in the user's market stats.
+pub fn track_user_open(stats: &mut UserMarketStats, side: &PositionSide, size: u64) -> Result<()> {
+    let total = match side {
+        PositionSide::Long => &mut stats.long_size,
+        PositionSide::Short => &mut stats.short_size,
+    };
+    *total = total.checked_add(size).ok_or(ErrorCode::MathOverflow)?;
+    stats.open_positions = stats
+        .open_positions
+        .checked_add(1)
+        .ok_or(ErrorCode::MathOverflow)?;
+    Ok(())
+}
+
+/// Remove a closed or liquidated position from the user's market stats.
+pub fn track_user_close(stats: &mut UserMarketStats, side: &PositionSide, size: u64) {
+    let total = match side {
+        PositionSide::Long => &mut stats.long_size,
+        PositionSide::Short => &mut stats.short_size,
+    };
+    *total = total.saturating_sub(size);
+    stats.open_positions = stats.open_positions.saturating_sub(1);
+}
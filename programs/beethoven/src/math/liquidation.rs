@@ -1,42 +1,71 @@
 use anchor_lang::prelude::*;
-use crate::constants::{PRICE_PRECISION, BPS_DENOMINATOR, PERP_LIQUIDATION_THRESHOLD};
+use crate::constants::{
+    BPS_DENOMINATOR, LENDING_LIQUIDATION_THRESHOLD, PERP_LIQUIDATION_THRESHOLD, PRICE_PRECISION,
+};
 use crate::error::ErrorCode;
-use crate::math::fixed_point::wad_div;
+use crate::math::fixed_point::{wad_div, wad_mul};
 use crate::state::perp_position::PositionSide;
+use crate::state::{ContractType, LendingPool, LendingPosition, MarginTier, PerpMarket};
 
-/// Compute PnL for a perpetual position.
-/// Long PnL = size * (current_price - entry_price) / PRICE_PRECISION
-/// Short PnL = size * (entry_price - current_price) / PRICE_PRECISION
+/// Position notional in collateral units.
+/// Linear: size (base units) * price / PRICE_PRECISION, in quote units
+/// Inverse: size (quote units) * PRICE_PRECISION / price, in base units
+pub fn position_notional(contract_type: ContractType, size: u64, price: u64) -> Result<u64> {
+    let notional = match contract_type {
+        ContractType::Linear => (size as u128)
+            .checked_mul(price as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(PRICE_PRECISION as u128)
+            .ok_or(ErrorCode::DivisionByZero)?,
+        ContractType::Inverse => (size as u128)
+            .checked_mul(PRICE_PRECISION as u128)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(price as u128)
+            .ok_or(ErrorCode::DivisionByZero)?,
+    };
+    u64::try_from(notional).map_err(|_| ErrorCode::MathOverflow.into())
+}
+
+/// Compute PnL for a perpetual position, in collateral units.
+/// Linear long PnL = size * (current_price - entry_price) / PRICE_PRECISION
+/// Inverse long PnL = size * (1 / entry_price - 1 / current_price) * PRICE_PRECISION
+/// Short PnL is the negation of long PnL.
 pub fn compute_pnl(
+    contract_type: ContractType,
     side: &PositionSide,
     size: u64,
     entry_price: u64,
     current_price: u64,
 ) -> Result<i64> {
-    match side {
-        PositionSide::Long => {
+    let long_pnl = match contract_type {
+        ContractType::Linear => {
             let diff = current_price as i64 - entry_price as i64;
-            Ok((size as i128)
+            (size as i128)
                 .checked_mul(diff as i128)
                 .ok_or(ErrorCode::MathOverflow)?
                 .checked_div(PRICE_PRECISION as i128)
-                .ok_or(ErrorCode::DivisionByZero)? as i64)
+                .ok_or(ErrorCode::DivisionByZero)?
         }
-        PositionSide::Short => {
-            let diff = entry_price as i64 - current_price as i64;
-            Ok((size as i128)
-                .checked_mul(diff as i128)
-                .ok_or(ErrorCode::MathOverflow)?
-                .checked_div(PRICE_PRECISION as i128)
-                .ok_or(ErrorCode::DivisionByZero)? as i64)
+        ContractType::Inverse => {
+            // Value of the contracts in base units falls as price rises
+            let entry_value = position_notional(contract_type, size, entry_price)? as i128;
+            let current_value = position_notional(contract_type, size, current_price)? as i128;
+            entry_value - current_value
         }
-    }
+    };
+
+    let pnl = match side {
+        PositionSide::Long => long_pnl,
+        PositionSide::Short => -long_pnl,
+    };
+    i64::try_from(pnl).map_err(|_| ErrorCode::MathOverflow.into())
 }
 
 /// Compute health factor for a perp position.
-/// health = (collateral + pnl - abs(funding)) / (size * current_price / PRICE_PRECISION)
+/// health = (collateral + pnl - funding) / notional, both in collateral units
 /// Returns value in BPS (e.g., 1000 = 10%).
 pub fn compute_perp_health_factor(
+    contract_type: ContractType,
     collateral: u64,
     pnl: i64,
     funding_payment: i64,
@@ -53,11 +82,7 @@ pub fn compute_perp_health_factor(
         return Ok(0);
     }
 
-    let notional = (size as u128)
-        .checked_mul(current_price as u128)
-        .ok_or(ErrorCode::MathOverflow)?
-        .checked_div(PRICE_PRECISION as u128)
-        .ok_or(ErrorCode::DivisionByZero)?;
+    let notional = position_notional(contract_type, size, current_price)? as u128;
 
     if notional == 0 {
         return Ok(BPS_DENOMINATOR); // No exposure
@@ -73,25 +98,60 @@ pub fn compute_perp_health_factor(
     Ok(health as u64)
 }
 
+/// Find the margin tier for a position of `notional` collateral units.
+/// Markets without a tier table use the flat max_leverage and 5% maintenance.
+/// Notional past the last tier resolves to the last (strictest) tier, so a
+/// position that grew with price stays liquidatable; callers opening new
+/// exposure must check `notional <= tier.max_notional` themselves.
+pub fn resolve_margin_tier(market: &PerpMarket, notional: u64) -> MarginTier {
+    if market.margin_tier_count == 0 {
+        return MarginTier {
+            max_notional: u64::MAX,
+            max_leverage: market.max_leverage,
+            maintenance_margin_bps: PERP_LIQUIDATION_THRESHOLD,
+        };
+    }
+
+    let tiers = &market.margin_tiers[..market.margin_tier_count as usize];
+    tiers
+        .iter()
+        .find(|tier| notional <= tier.max_notional)
+        .copied()
+        .unwrap_or(tiers[tiers.len() - 1])
+}
+
 /// Check if a perp position is liquidatable.
-/// Liquidatable if health_factor < PERP_LIQUIDATION_THRESHOLD (5%).
-pub fn is_perp_liquidatable(health_factor: u64) -> bool {
-    health_factor < PERP_LIQUIDATION_THRESHOLD
+/// Liquidatable if health_factor < the tier's maintenance margin.
+pub fn is_perp_liquidatable(health_factor: u64, maintenance_margin_bps: u64) -> bool {
+    health_factor < maintenance_margin_bps
 }
 
 /// Compute liquidation price for a perp position.
-/// Long liq price = entry_price - (collateral * PRICE_PRECISION / size) * (1 - maintenance_margin)
-/// Short liq price = entry_price + (collateral * PRICE_PRECISION / size) * (1 - maintenance_margin)
+/// Linear long liq price = entry_price - (collateral * PRICE_PRECISION / size) * (1 - maintenance_margin)
+/// Linear short liq price = entry_price + (collateral * PRICE_PRECISION / size) * (1 - maintenance_margin)
+/// Inverse positions use `compute_inverse_liquidation_price`.
 pub fn compute_liquidation_price(
+    contract_type: ContractType,
     side: &PositionSide,
     entry_price: u64,
     collateral: u64,
     size: u64,
+    maintenance_margin_bps: u64,
 ) -> Result<u64> {
     if size == 0 {
         return Ok(0);
     }
 
+    if contract_type == ContractType::Inverse {
+        return compute_inverse_liquidation_price(
+            side,
+            entry_price,
+            collateral,
+            size,
+            maintenance_margin_bps,
+        );
+    }
+
     // margin_per_unit = collateral * PRICE_PRECISION / size
     let margin_per_unit = (collateral as u128)
         .checked_mul(PRICE_PRECISION as u128)
@@ -99,10 +159,10 @@ pub fn compute_liquidation_price(
         .checked_div(size as u128)
         .ok_or(ErrorCode::DivisionByZero)?;
 
-    // Adjust by maintenance margin (PERP_LIQUIDATION_THRESHOLD = 500 bps = 5%)
-    // Effective margin = margin_per_unit * (BPS - threshold) / BPS
+    // Adjust by maintenance margin
+    // Effective margin = margin_per_unit * (BPS - maintenance) / BPS
     let effective_margin = margin_per_unit
-        .checked_mul((BPS_DENOMINATOR - PERP_LIQUIDATION_THRESHOLD) as u128)
+        .checked_mul(BPS_DENOMINATOR.saturating_sub(maintenance_margin_bps) as u128)
         .ok_or(ErrorCode::MathOverflow)?
         .checked_div(BPS_DENOMINATOR as u128)
         .ok_or(ErrorCode::DivisionByZero)?;
@@ -123,6 +183,52 @@ pub fn compute_liquidation_price(
     }
 }
 
+/// Price at which an inverse position's equity falls to its maintenance margin.
+/// With v(p) = size * PRICE_PRECISION / p and m = maintenance_margin:
+/// Long:  collateral + v(entry) - v(p) = m * v(p)  =>  p = size * PP * (1 + m) / (collateral + v(entry))
+/// Short: collateral - v(entry) + v(p) = m * v(p)  =>  p = size * PP * (1 - m) / (v(entry) - collateral)
+/// A short whose collateral covers its entry value can never be liquidated (u64::MAX).
+fn compute_inverse_liquidation_price(
+    side: &PositionSide,
+    entry_price: u64,
+    collateral: u64,
+    size: u64,
+    maintenance_margin_bps: u64,
+) -> Result<u64> {
+    let entry_value = position_notional(ContractType::Inverse, size, entry_price)? as u128;
+    let scaled_size = (size as u128)
+        .checked_mul(PRICE_PRECISION as u128)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    let (margin_factor_bps, denominator) = match side {
+        PositionSide::Long => (
+            BPS_DENOMINATOR.saturating_add(maintenance_margin_bps),
+            entry_value
+                .checked_add(collateral as u128)
+                .ok_or(ErrorCode::MathOverflow)?,
+        ),
+        PositionSide::Short => {
+            if entry_value <= collateral as u128 {
+                return Ok(u64::MAX);
+            }
+            (
+                BPS_DENOMINATOR.saturating_sub(maintenance_margin_bps),
+                entry_value - collateral as u128,
+            )
+        }
+    };
+
+    let liq_price = scaled_size
+        .checked_mul(margin_factor_bps as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_div(BPS_DENOMINATOR as u128)
+        .ok_or(ErrorCode::DivisionByZero)?
+        .checked_div(denominator)
+        .ok_or(ErrorCode::DivisionByZero)?;
+
+    Ok(u64::try_from(liq_price).unwrap_or(u64::MAX))
+}
+
 /// Compute lending health factor.
 /// health_factor = sum(deposit_value * collateral_factor) / total_borrow_value
 /// Returns WAD precision (1e18 = 1.0).
@@ -135,3 +241,33 @@ pub fn compute_lending_health_factor(
     }
     wad_div(weighted_collateral_value, total_borrow_value)
 }
+
+/// Check a lending position stays healthy once `amount` of its deposit is
+/// removed. Deposits and borrows are both in the pool mint, valued at `price`.
+pub fn check_lending_withdrawal(
+    pool: &LendingPool,
+    position: &LendingPosition,
+    amount: u64,
+    price: u64,
+) -> Result<()> {
+    let remaining_deposit = position
+        .deposited_amount
+        .checked_sub(amount)
+        .ok_or(ErrorCode::MathUnderflow)?;
+
+    let collateral_value = (remaining_deposit as u128)
+        .checked_mul(price as u128)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let weighted_collateral = wad_mul(collateral_value, pool.collateral_factor)?;
+
+    let borrow_value = (position.borrowed_amount as u128)
+        .checked_mul(price as u128)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    let health = compute_lending_health_factor(weighted_collateral, borrow_value)?;
+    require!(
+        health >= LENDING_LIQUIDATION_THRESHOLD,
+        ErrorCode::WithdrawalWouldLiquidate
+    );
+    Ok(())
+}
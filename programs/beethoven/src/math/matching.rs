@@ -0,0 +1,160 @@
+use anchor_lang::prelude::*;
+use crate::constants::MAX_MATCHES_PER_ORDER;
+use crate::error::ErrorCode;
+use crate::math::fixed_point::bps_mul;
+use crate::state::{order_price, EventQueue, FillEvent, OrderBookSide, OrderSide};
+
+pub struct MatchParams {
+    pub side: OrderSide,  // Taker side
+    pub limit_price: u64, // Quote atoms per base lot
+    pub base_lots: u64,
+    pub base_lot_size: u64,
+    pub maker_fee_bps: u64,
+    pub taker_fee_bps: u64,
+    pub taker: Pubkey, // Taker's OpenOrders account
+    pub timestamp: i64,
+}
+
+#[derive(Default)]
+pub struct MatchResult {
+    pub base_lots: u64,    // Base lots filled
+    pub quote_amount: u64, // Quote atoms exchanged, before fees
+    pub taker_fee: u64,
+    pub maker_fee: u64,
+
+    // Taker's own resting orders cancelled instead of self-trading
+    pub self_trade_orders: u32,
+    pub self_trade_base: u64,
+    pub self_trade_quote: u64,
+}
+
+/// Whether a maker order at `maker_price` crosses a taker's limit.
+fn crosses(taker_side: OrderSide, limit_price: u64, maker_price: u64) -> bool {
+    match taker_side {
+        OrderSide::Bid => maker_price <= limit_price,
+        OrderSide::Ask => maker_price >= limit_price,
+    }
+}
+
+/// Whether an order at `limit_price` would take liquidity from `book`.
+pub fn would_cross(book: &OrderBookSide, taker_side: OrderSide, limit_price: u64) -> bool {
+    book.best()
+        .map(|handle| crosses(taker_side, limit_price, book.nodes[handle as usize].price()))
+        .unwrap_or(false)
+}
+
+/// Fill a taker order against the opposite side of the book, best price
+/// first. Each maker fill is queued for the crank to settle; the taker is
+/// settled by the caller from the returned totals.
+pub fn match_order(
+    book: &mut OrderBookSide,
+    queue: &mut EventQueue,
+    params: &MatchParams,
+) -> Result<MatchResult> {
+    let mut result = MatchResult::default();
+    let mut remaining = params.base_lots;
+    let maker_side = book.order_side();
+
+    for _ in 0..MAX_MATCHES_PER_ORDER {
+        if remaining == 0 {
+            break;
+        }
+        let Some(handle) = book.best() else {
+            break;
+        };
+        let maker = book.leaf(handle);
+        let price = order_price(maker.key);
+        if !crosses(params.side, params.limit_price, price) {
+            break;
+        }
+
+        // Self-trade prevention: cancel the resting order rather than fill it
+        if maker.owner == params.taker {
+            book.remove(maker.key);
+            match maker_side {
+                OrderSide::Bid => {
+                    result.self_trade_quote = result
+                        .self_trade_quote
+                        .checked_add(maker.locked_quote)
+                        .ok_or(ErrorCode::MathOverflow)?;
+                }
+                OrderSide::Ask => {
+                    let base = maker
+                        .quantity
+                        .checked_mul(params.base_lot_size)
+                        .ok_or(ErrorCode::MathOverflow)?;
+                    result.self_trade_base = result
+                        .self_trade_base
+                        .checked_add(base)
+                        .ok_or(ErrorCode::MathOverflow)?;
+                }
+            }
+            result.self_trade_orders += 1;
+            continue;
+        }
+
+        let fill = remaining.min(maker.quantity);
+        let quote = fill.checked_mul(price).ok_or(ErrorCode::MathOverflow)?;
+        let maker_out = fill == maker.quantity;
+
+        // Bids locked their maker fee up front; the last fill releases
+        // whatever is left so rounding never strands quote on the book
+        let (quote_released, maker_fee) = match maker_side {
+            OrderSide::Bid => {
+                let released = if maker_out {
+                    maker.locked_quote
+                } else {
+                    quote
+                        .checked_add(bps_mul(quote, params.maker_fee_bps)?)
+                        .ok_or(ErrorCode::MathOverflow)?
+                };
+                let fee = released.checked_sub(quote).ok_or(ErrorCode::MathUnderflow)?;
+                (released, fee)
+            }
+            OrderSide::Ask => (0, bps_mul(quote, params.maker_fee_bps)?),
+        };
+        let taker_fee = bps_mul(quote, params.taker_fee_bps)?;
+
+        queue.push(FillEvent {
+            maker: maker.owner,
+            order_key_hi: (maker.key >> 64) as u64,
+            order_key_lo: maker.key as u64,
+            base_lots: fill,
+            quote_amount: quote,
+            quote_released,
+            maker_fee,
+            timestamp: params.timestamp,
+            maker_side: match maker_side {
+                OrderSide::Bid => 0,
+                OrderSide::Ask => 1,
+            },
+            maker_out: maker_out as u8,
+            _padding: [0u8; 6],
+        })?;
+
+        if maker_out {
+            book.remove(maker.key);
+        } else {
+            let node = book.leaf_mut(handle);
+            node.quantity -= fill;
+            node.locked_quote -= quote_released;
+        }
+
+        remaining -= fill;
+        result.base_lots += fill;
+        result.quote_amount = result
+            .quote_amount
+            .checked_add(quote)
+            .ok_or(ErrorCode::MathOverflow)?;
+        result.taker_fee = result
+            .taker_fee
+            .checked_add(taker_fee)
+            .ok_or(ErrorCode::MathOverflow)?;
+        result.maker_fee = result
+            .maker_fee
+            .checked_add(maker_fee)
+            .ok_or(ErrorCode::MathOverflow)?;
+    }
+
+    Ok(result)
+}
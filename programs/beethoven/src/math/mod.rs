@@ -4,6 +4,14 @@ pub mod interest;
 pub mod funding;
 pub mod liquidation;
 pub mod nav;
+pub mod twap;
+pub mod perp_pool;
+pub mod limits;
+pub mod circuit_breaker;
+pub mod fee_tiers;
+pub mod referral;
+pub mod matching;
+pub mod amm;
 
 pub use fixed_point::*;
 pub use oracle::*;
@@ -11,3 +19,11 @@ pub use interest::*;
 pub use funding::*;
 pub use liquidation::*;
 pub use nav::*;
+pub use twap::*;
+pub use perp_pool::*;
+pub use limits::*;
+pub use circuit_breaker::*;
+pub use fee_tiers::*;
+pub use referral::*;
+pub use matching::*;
+pub use amm::*;
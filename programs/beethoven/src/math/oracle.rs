@@ -1,5 +1,5 @@
 use anchor_lang::prelude::*;
-use crate::constants::MAX_ORACLE_STALENESS;
+use crate::constants::{BPS_DENOMINATOR, MAX_ORACLE_STALENESS};
 use crate::error::ErrorCode;
 
 /// Parsed price from oracle feed
@@ -80,3 +80,49 @@ fn normalize_price(raw_price: u64, exponent: i32) -> Result<u64> {
         Ok(raw_price / divisor)
     }
 }
+
+/// One side of a swap for the oracle band check: an amount in atoms, its
+/// mint's decimals and the oracle price per whole token.
+pub struct PricedAmount {
+    pub amount: u64,
+    pub decimals: u8,
+    pub price: u64, // in PRICE_PRECISION (1e6)
+}
+
+/// Reject a fill whose output is worth less than the input, at oracle
+/// prices, by more than `max_deviation_bps`.
+pub fn check_oracle_band(
+    input: &PricedAmount,
+    output: &PricedAmount,
+    max_deviation_bps: u64,
+) -> Result<()> {
+    // Value both sides in the same atom scale: scale up the side with fewer decimals
+    let mut input_value = (input.amount as u128)
+        .checked_mul(input.price as u128)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let mut output_value = (output.amount as u128)
+        .checked_mul(output.price as u128)
+        .ok_or(ErrorCode::MathOverflow)?;
+    if output.decimals >= input.decimals {
+        input_value = input_value
+            .checked_mul(10u128.pow((output.decimals - input.decimals) as u32))
+            .ok_or(ErrorCode::MathOverflow)?;
+    } else {
+        output_value = output_value
+            .checked_mul(10u128.pow((input.decimals - output.decimals) as u32))
+            .ok_or(ErrorCode::MathOverflow)?;
+    }
+
+    // output_value / input_value >= 1 - max_deviation
+    let band = BPS_DENOMINATOR
+        .checked_sub(max_deviation_bps)
+        .ok_or(ErrorCode::InvalidParameter)?;
+    let floor = input_value
+        .checked_mul(band as u128)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let realized = output_value
+        .checked_mul(BPS_DENOMINATOR as u128)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(realized >= floor, ErrorCode::OracleDeviationExceeded);
+    Ok(())
+}
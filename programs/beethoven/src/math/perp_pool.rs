@@ -0,0 +1,248 @@
+use anchor_lang::prelude::*;
+use crate::constants::{BPS_DENOMINATOR, WAD};
+use crate::error::ErrorCode;
+use crate::math::fixed_point::bps_mul;
+use crate::math::funding::funding_in_collateral;
+use crate::math::liquidation::position_notional;
+use crate::math::oracle::get_price;
+use crate::state::perp_position::PositionSide;
+use crate::state::{ContractType, PerpLpPool, PerpMarket};
+
+pub struct PoolMarketsSnapshot {
+    pub trader_pnl: i128,      // Unrealized PnL of all traders, collateral units
+    pub max_oi_notional: u128, // Sum of each market's per-side OI cap in collateral units
+}
+
+/// Add a newly opened position to the market's aggregate entry notional.
+pub fn track_open_notional(
+    market: &mut PerpMarket,
+    side: &PositionSide,
+    size: u64,
+    entry_price: u64,
+) -> Result<()> {
+    let notional = position_notional(market.contract_type, size, entry_price)?;
+    let total = match side {
+        PositionSide::Long => &mut market.long_entry_notional,
+        PositionSide::Short => &mut market.short_entry_notional,
+    };
+    *total = total.checked_add(notional).ok_or(ErrorCode::MathOverflow)?;
+    Ok(())
+}
+
+/// Remove a closed or liquidated position from the aggregate entry notional.
+pub fn track_close_notional(
+    market: &mut PerpMarket,
+    side: &PositionSide,
+    size: u64,
+    entry_price: u64,
+) -> Result<()> {
+    let notional = position_notional(market.contract_type, size, entry_price)?;
+    let total = match side {
+        PositionSide::Long => &mut market.long_entry_notional,
+        PositionSide::Short => &mut market.short_entry_notional,
+    };
+    *total = total.saturating_sub(notional);
+    Ok(())
+}
+
+/// Add a newly opened position's funding snapshot to the market's funding basis.
+pub fn track_open_funding(
+    market: &mut PerpMarket,
+    side: &PositionSide,
+    size: u64,
+    funding_snapshot: i128,
+) -> Result<()> {
+    let weighted = (size as i128)
+        .checked_mul(funding_snapshot)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let basis = match side {
+        PositionSide::Long => &mut market.long_funding_basis,
+        PositionSide::Short => &mut market.short_funding_basis,
+    };
+    *basis = basis.checked_add(weighted).ok_or(ErrorCode::MathOverflow)?;
+    Ok(())
+}
+
+/// Remove a closed or liquidated position from the market's funding basis.
+pub fn track_close_funding(
+    market: &mut PerpMarket,
+    side: &PositionSide,
+    size: u64,
+    funding_snapshot: i128,
+) -> Result<()> {
+    let weighted = (size as i128)
+        .checked_mul(funding_snapshot)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let basis = match side {
+        PositionSide::Long => &mut market.long_funding_basis,
+        PositionSide::Short => &mut market.short_funding_basis,
+    };
+    *basis = basis.checked_sub(weighted).ok_or(ErrorCode::MathOverflow)?;
+    Ok(())
+}
+
+/// Funding accrued but not yet settled by every open position in a market,
+/// in collateral units. Positive when traders owe the pool.
+pub fn market_pending_funding(market: &PerpMarket, price: u64) -> Result<i128> {
+    let side_funding = |open_interest: u64, cumulative: i128, basis: i128| -> Result<i128> {
+        (open_interest as i128)
+            .checked_mul(cumulative)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_sub(basis)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_div(WAD as i128)
+            .ok_or(ErrorCode::DivisionByZero.into())
+    };
+    let pending = side_funding(
+        market.long_open_interest,
+        market.cumulative_funding_long,
+        market.long_funding_basis,
+    )?
+    .checked_add(side_funding(
+        market.short_open_interest,
+        market.cumulative_funding_short,
+        market.short_funding_basis,
+    )?)
+    .ok_or(ErrorCode::MathOverflow)?;
+
+    let pending = i64::try_from(pending).map_err(|_| ErrorCode::MathOverflow)?;
+    Ok(funding_in_collateral(market.contract_type, pending, price)? as i128)
+}
+
+/// Unrealized PnL of every open position in a market, from the traders' side,
+/// net of the funding they have accrued but not yet settled.
+pub fn market_unrealized_pnl(market: &PerpMarket, price: u64) -> Result<i128> {
+    let long_value = position_notional(market.contract_type, market.long_open_interest, price)? as i128;
+    let short_value =
+        position_notional(market.contract_type, market.short_open_interest, price)? as i128;
+
+    // Inverse contracts lose collateral value as price rises, so longs
+    // gain when the current value falls below entry
+    let long_pnl = match market.contract_type {
+        ContractType::Linear => long_value - market.long_entry_notional as i128,
+        ContractType::Inverse => market.long_entry_notional as i128 - long_value,
+    };
+    let short_pnl = match market.contract_type {
+        ContractType::Linear => market.short_entry_notional as i128 - short_value,
+        ContractType::Inverse => short_value - market.short_entry_notional as i128,
+    };
+
+    long_pnl
+        .checked_add(short_pnl)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_sub(market_pending_funding(market, price)?)
+        .ok_or(ErrorCode::MathOverflow.into())
+}
+
+/// Per-side OI cap in collateral units: the notional cap when set, otherwise
+/// the size cap valued at `price`.
+pub fn market_max_oi_notional(market: &PerpMarket, price: u64) -> Result<u64> {
+    if market.max_oi_notional > 0 {
+        return Ok(market.max_oi_notional);
+    }
+    position_notional(market.contract_type, market.max_open_interest, price)
+}
+
+/// Price every market backed by the pool.
+///
+/// remaining_accounts must contain one [PerpMarket, Oracle] pair for each of
+/// the pool's `market_count` markets, so no losing market can be left out.
+pub fn load_pool_markets<'info>(
+    pool: &PerpLpPool,
+    remaining_accounts: &'info [AccountInfo<'info>],
+    clock: &Clock,
+) -> Result<PoolMarketsSnapshot> {
+    require!(
+        remaining_accounts.len() == pool.market_count as usize * 2,
+        ErrorCode::PoolMarketsMismatch
+    );
+
+    let mut snapshot = PoolMarketsSnapshot {
+        trader_pnl: 0,
+        max_oi_notional: 0,
+    };
+
+    for i in 0..pool.market_count as usize {
+        let market_info = &remaining_accounts[i * 2];
+        let oracle_info = &remaining_accounts[i * 2 + 1];
+
+        // Each market may only be counted once
+        require!(
+            remaining_accounts[..i * 2]
+                .iter()
+                .step_by(2)
+                .all(|seen| seen.key() != market_info.key()),
+            ErrorCode::PoolMarketsMismatch
+        );
+
+        let market: Account<PerpMarket> = Account::try_from(market_info)?;
+        require!(
+            market.collateral_mint == pool.quote_mint,
+            ErrorCode::PoolMarketsMismatch
+        );
+        require!(
+            oracle_info.key() == market.oracle,
+            ErrorCode::OracleAccountMismatch
+        );
+
+        let oracle_price = get_price(oracle_info, clock)?;
+
+        snapshot.trader_pnl = snapshot
+            .trader_pnl
+            .checked_add(market_unrealized_pnl(&market, oracle_price.price)?)
+            .ok_or(ErrorCode::MathOverflow)?;
+        snapshot.max_oi_notional = snapshot
+            .max_oi_notional
+            .checked_add(market_max_oi_notional(&market, oracle_price.price)? as u128)
+            .ok_or(ErrorCode::MathOverflow)?;
+    }
+
+    Ok(snapshot)
+}
+
+/// Pool NAV = liquidity - unrealized trader PnL, floored at zero.
+pub fn compute_pool_nav(liquidity: u64, trader_pnl: i128) -> Result<u64> {
+    let nav = (liquidity as i128)
+        .checked_sub(trader_pnl)
+        .ok_or(ErrorCode::MathOverflow)?
+        .max(0);
+    u64::try_from(nav).map_err(|_| ErrorCode::MathOverflow.into())
+}
+
+/// NAV per LP share in WAD precision; 1.0 when no shares exist.
+pub fn pool_nav_per_share(nav: u64, total_shares: u64) -> Result<u128> {
+    if total_shares == 0 {
+        return Ok(WAD);
+    }
+    (nav as u128)
+        .checked_mul(WAD)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_div(total_shares as u128)
+        .ok_or(ErrorCode::DivisionByZero.into())
+}
+
+/// Check the pool stays utilized: max OI notional / NAV >= target_utilization_bps.
+pub fn check_deposit_cap(
+    pool: &PerpLpPool,
+    nav_after_deposit: u64,
+    max_oi_notional: u128,
+) -> Result<()> {
+    if pool.target_utilization_bps == 0 {
+        return Ok(());
+    }
+
+    let required = (nav_after_deposit as u128)
+        .checked_mul(pool.target_utilization_bps as u128)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let available = max_oi_notional
+        .checked_mul(BPS_DENOMINATOR as u128)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(required <= available, ErrorCode::PerpLpDepositCapExceeded);
+    Ok(())
+}
+
+/// Split a trading fee into (lp_share, protocol_share).
+pub fn split_trading_fee(fee: u64, lp_fee_share_bps: u64) -> Result<(u64, u64)> {
+    let lp_share = bps_mul(fee, lp_fee_share_bps)?;
+    Ok((lp_share, fee - lp_share))
+}
@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+use crate::error::ErrorCode;
+use crate::math::fee_tiers::discounted_fee_bps;
+use crate::math::fixed_point::bps_mul;
+use crate::state::{Exchange, ReferrerAccount};
+
+/// Fee rate for a trade, with the referee discount when the user's
+/// referrer account was supplied.
+pub fn referee_fee_bps(exchange: &Exchange, fee_bps: u64, has_referrer: bool) -> Result<u64> {
+    if !has_referrer {
+        return Ok(fee_bps);
+    }
+    discounted_fee_bps(fee_bps, exchange.referee_discount_bps)
+}
+
+/// Credit the referrer's share of `fee`. Returns the share, which the caller
+/// must move into the vault's referral bucket.
+pub fn accrue_referral(
+    exchange: &Exchange,
+    referrer: &mut ReferrerAccount,
+    fee: u64,
+) -> Result<u64> {
+    let share = bps_mul(fee, exchange.referral_share_bps)?;
+    referrer.claimable = referrer
+        .claimable
+        .checked_add(share)
+        .ok_or(ErrorCode::MathOverflow)?;
+    referrer.total_earned = referrer
+        .total_earned
+        .checked_add(share)
+        .ok_or(ErrorCode::MathOverflow)?;
+    Ok(share)
+}
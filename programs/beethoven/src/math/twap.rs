@@ -0,0 +1,187 @@
+use anchor_lang::prelude::*;
+use crate::constants::{WAD, LN_2_WAD, PRICE_OBSERVATION_CAPACITY};
+use crate::error::ErrorCode;
+use crate::math::oracle::get_price;
+use crate::state::{PriceAccumulator, PriceObservation};
+
+/// Record an oracle price into the accumulator.
+/// The EMA is updated on every call; a new ring-buffer slot is only written
+/// once `min_observation_interval` seconds have passed since the last one.
+pub fn record_price_observation(
+    accumulator: &mut PriceAccumulator,
+    price: u64,
+    now: i64,
+) -> Result<()> {
+    require!(price > 0, ErrorCode::OraclePriceInvalid);
+
+    // First observation seeds both the buffer and the EMA
+    if accumulator.observation_count == 0 {
+        accumulator.observations[0] = PriceObservation {
+            timestamp: now,
+            price,
+            cumulative_price: 0,
+        };
+        accumulator.observation_index = 0;
+        accumulator.observation_count = 1;
+        accumulator.ema_price = price;
+        accumulator.last_update = now;
+        return Ok(());
+    }
+
+    if now <= accumulator.last_update {
+        return Ok(());
+    }
+
+    // EMA: ema += (price - ema) * (1 - 2^(-elapsed / half_life))
+    let elapsed = now - accumulator.last_update;
+    let decay = half_life_decay(elapsed, accumulator.ema_half_life)?;
+    let ema = (accumulator.ema_price as u128)
+        .checked_mul(decay)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_add(
+            (price as u128)
+                .checked_mul(WAD - decay)
+                .ok_or(ErrorCode::MathOverflow)?,
+        )
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_div(WAD)
+        .ok_or(ErrorCode::DivisionByZero)?;
+    accumulator.ema_price = u64::try_from(ema).map_err(|_| ErrorCode::MathOverflow)?;
+    accumulator.last_update = now;
+
+    let last = accumulator.observations[accumulator.observation_index as usize];
+    let since_last = now - last.timestamp;
+    if since_last < accumulator.min_observation_interval {
+        return Ok(());
+    }
+
+    // The previous price prevailed over [last.timestamp, now)
+    let cumulative_price = last
+        .cumulative_price
+        .checked_add(
+            (last.price as u128)
+                .checked_mul(since_last as u128)
+                .ok_or(ErrorCode::MathOverflow)?,
+        )
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    let next_index = (accumulator.observation_index as usize + 1) % PRICE_OBSERVATION_CAPACITY;
+    accumulator.observations[next_index] = PriceObservation {
+        timestamp: now,
+        price,
+        cumulative_price,
+    };
+    accumulator.observation_index = next_index as u16;
+    if (accumulator.observation_count as usize) < PRICE_OBSERVATION_CAPACITY {
+        accumulator.observation_count += 1;
+    }
+
+    Ok(())
+}
+
+/// Read the oracle and record it. Used by the crank and by instructions
+/// that do not otherwise fetch a price.
+pub fn observe_oracle_price(
+    accumulator: &mut PriceAccumulator,
+    oracle_account: &AccountInfo,
+    clock: &Clock,
+) -> Result<u64> {
+    require!(
+        oracle_account.key() == accumulator.oracle,
+        ErrorCode::OracleAccountMismatch
+    );
+    let oracle_price = get_price(oracle_account, clock)?;
+    record_price_observation(accumulator, oracle_price.price, clock.unix_timestamp)?;
+    Ok(oracle_price.price)
+}
+
+/// Time-weighted average price over the trailing `window` seconds.
+/// Fails if the ring buffer does not reach back far enough.
+pub fn compute_twap(accumulator: &PriceAccumulator, window: i64, now: i64) -> Result<u64> {
+    require!(window > 0, ErrorCode::InvalidParameter);
+    require!(
+        accumulator.observation_count > 0,
+        ErrorCode::TwapWindowUnavailable
+    );
+
+    let target = now.checked_sub(window).ok_or(ErrorCode::MathOverflow)?;
+    let count = accumulator.observation_count as usize;
+    let newest = accumulator.observation_index as usize;
+    let oldest = (newest + PRICE_OBSERVATION_CAPACITY + 1 - count) % PRICE_OBSERVATION_CAPACITY;
+
+    require!(
+        accumulator.observations[oldest].timestamp <= target,
+        ErrorCode::TwapWindowUnavailable
+    );
+
+    // Walk from oldest to newest, keeping the last observation at or before target
+    let mut start = accumulator.observations[oldest];
+    for i in 1..count {
+        let observation = accumulator.observations[(oldest + i) % PRICE_OBSERVATION_CAPACITY];
+        if observation.timestamp > target {
+            break;
+        }
+        start = observation;
+    }
+
+    let cumulative_at_target = cumulative_at(&start, target)?;
+    let cumulative_now = cumulative_at(&accumulator.observations[newest], now)?;
+
+    let twap = cumulative_now
+        .checked_sub(cumulative_at_target)
+        .ok_or(ErrorCode::MathUnderflow)?
+        .checked_div(window as u128)
+        .ok_or(ErrorCode::DivisionByZero)?;
+
+    u64::try_from(twap).map_err(|_| ErrorCode::MathOverflow.into())
+}
+
+/// Extrapolate an observation's cumulative price forward to `timestamp`.
+fn cumulative_at(observation: &PriceObservation, timestamp: i64) -> Result<u128> {
+    let elapsed = timestamp
+        .checked_sub(observation.timestamp)
+        .ok_or(ErrorCode::MathOverflow)?
+        .max(0);
+    observation
+        .cumulative_price
+        .checked_add(
+            (observation.price as u128)
+                .checked_mul(elapsed as u128)
+                .ok_or(ErrorCode::MathOverflow)?,
+        )
+        .ok_or(ErrorCode::MathOverflow.into())
+}
+
+/// Weight retained by the previous EMA after `elapsed` seconds: 2^(-elapsed / half_life).
+/// Returns WAD precision.
+pub fn half_life_decay(elapsed: i64, half_life: i64) -> Result<u128> {
+    require!(half_life > 0, ErrorCode::InvalidParameter);
+    if elapsed <= 0 {
+        return Ok(WAD);
+    }
+
+    let halvings = elapsed / half_life;
+    if halvings >= 64 {
+        return Ok(0);
+    }
+
+    // 2^(-frac) = e^(-frac * ln2), frac in [0, 1)
+    let frac = ((elapsed % half_life) as u128)
+        .checked_mul(WAD)
+        .ok_or(ErrorCode::MathOverflow)?
+        / half_life as u128;
+    let x = frac
+        .checked_mul(LN_2_WAD)
+        .ok_or(ErrorCode::MathOverflow)?
+        / WAD;
+
+    // Taylor series for e^(-x); x < 0.7 so 12 terms is well past WAD precision
+    let mut term = WAD as i128;
+    let mut sum = WAD as i128;
+    for k in 1..=12i128 {
+        term = -term * x as i128 / WAD as i128 / k;
+        sum += term;
+    }
+
+    Ok((sum.max(0) as u128) >> halvings)
+}
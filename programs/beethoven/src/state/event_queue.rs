@@ -0,0 +1,70 @@
+use anchor_lang::prelude::*;
+use crate::constants::EVENT_QUEUE_CAPACITY;
+use crate::error::ErrorCode;
+
+/// A maker fill awaiting settlement into the maker's OpenOrders account.
+#[zero_copy]
+#[derive(Debug, Default)]
+pub struct FillEvent {
+    pub maker: Pubkey,       // Maker's OpenOrders account
+    pub order_key_hi: u64,   // Maker order id (price)
+    pub order_key_lo: u64,   // Maker order id (sequence)
+    pub base_lots: u64,      // Base lots filled
+    pub quote_amount: u64,   // Quote atoms exchanged, before fees
+    pub quote_released: u64, // Bids: quote unlocked from the order, including the maker fee
+    pub maker_fee: u64,      // Quote atoms charged to the maker
+    pub timestamp: i64,
+    pub maker_side: u8,      // 0 = bid, 1 = ask
+    pub maker_out: u8,       // 1 when the maker order left the book
+    pub _padding: [u8; 6],
+}
+
+/// Ring buffer of maker fills, drained by the consume_spot_events crank.
+#[account(zero_copy)]
+pub struct EventQueue {
+    pub market: Pubkey,
+    pub head: u64,    // Index of the oldest event
+    pub count: u64,   // Events waiting to be consumed
+    pub seq_num: u64, // Total events ever pushed
+    pub events: [FillEvent; EVENT_QUEUE_CAPACITY],
+}
+
+impl EventQueue {
+    pub const LEN: usize = 8 // discriminator
+        + 32  // market
+        + 8   // head
+        + 8   // count
+        + 8   // seq_num
+        + 96 * EVENT_QUEUE_CAPACITY; // events
+
+    pub fn init(&mut self, market: Pubkey) {
+        self.market = market;
+        self.head = 0;
+        self.count = 0;
+        self.seq_num = 0;
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.count as usize == EVENT_QUEUE_CAPACITY
+    }
+
+    pub fn push(&mut self, event: FillEvent) -> Result<()> {
+        require!(!self.is_full(), ErrorCode::EventQueueFull);
+        let slot = (self.head + self.count) as usize % EVENT_QUEUE_CAPACITY;
+        self.events[slot] = event;
+        self.count += 1;
+        self.seq_num += 1;
+        Ok(())
+    }
+
+    pub fn peek(&self) -> Option<&FillEvent> {
+        (self.count > 0).then(|| &self.events[self.head as usize])
+    }
+
+    pub fn pop(&mut self) {
+        if self.count > 0 {
+            self.head = (self.head + 1) % EVENT_QUEUE_CAPACITY as u64;
+            self.count -= 1;
+        }
+    }
+}
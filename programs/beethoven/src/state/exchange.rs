@@ -1,4 +1,18 @@
 use anchor_lang::prelude::*;
+use crate::constants::MAX_FEE_TIERS;
+
+/// One row of the exchange fee schedule. Users whose rolling 30-day volume
+/// is at least `min_volume` get `discount_bps` off swap and perp fees.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug)]
+pub struct FeeTier {
+    pub min_volume: u64,   // Rolling 30-day volume threshold
+    pub discount_bps: u64, // Discount on the base fee rate
+}
+
+impl FeeTier {
+    pub const LEN: usize = 8 // min_volume
+        + 8;  // discount_bps
+}
 
 #[account]
 pub struct Exchange {
@@ -28,8 +42,16 @@ pub struct Exchange {
     pub total_lending_pools: u64,
     pub total_users: u64,
 
+    // Volume fee tiers, ascending by min_volume (empty = no discounts)
+    pub fee_tier_count: u8,
+    pub fee_tiers: [FeeTier; MAX_FEE_TIERS],
+
+    // Referrals (in basis points)
+    pub referral_share_bps: u64,   // Share of a referee's fee paid to the referrer
+    pub referee_discount_bps: u64, // Discount on the fee rate for referred users
+
     // Reserved for future use
-    pub _reserved: [u8; 128],
+    pub _reserved: [u8; 15],
 }
 
 impl Exchange {
@@ -41,5 +63,8 @@ impl Exchange {
         + 8 * 2 // liquidation params
         + 1 * 3 // pause flags
         + 8 * 3 // counters
-        + 128; // reserved
+        + 1   // fee_tier_count
+        + FeeTier::LEN * MAX_FEE_TIERS // fee_tiers
+        + 8 * 2 // referral params
+        + 15; // reserved
 }
@@ -19,8 +19,12 @@ pub struct LendingPosition {
     // Timestamps
     pub last_updated: i64,
 
+    // Sub-account the position belongs to; default for positions opened
+    // before sub-accounts, which belong to the owner's primary account
+    pub user_account: Pubkey,
+
     // Reserved
-    pub _reserved: [u8; 64],
+    pub _reserved: [u8; 32],
 }
 
 impl LendingPosition {
@@ -33,5 +37,6 @@ impl LendingPosition {
         + 16  // cumulative_deposit_rate_snapshot
         + 16  // cumulative_borrow_rate_snapshot
         + 8   // last_updated
-        + 64; // reserved
+        + 32  // user_account
+        + 32; // reserved
 }
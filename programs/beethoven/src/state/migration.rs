@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use crate::error::ErrorCode;
+
+/// Grow a program account written under a shorter layout to `len` bytes.
+/// Fields added since are appended after `_reserved`, so the existing prefix
+/// still decodes and the new tail reads as zeroes. The payer covers the rent.
+pub fn grow_account<'info>(
+    account: &AccountInfo<'info>,
+    discriminator: &[u8],
+    len: usize,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    require_keys_eq!(*account.owner, crate::ID, ErrorCode::InvalidMigrationAccount);
+    let old_len = account.data_len();
+    {
+        let data = account.try_borrow_data()?;
+        require!(
+            data.len() >= discriminator.len() && data[..discriminator.len()] == *discriminator,
+            ErrorCode::InvalidMigrationAccount
+        );
+    }
+    require!(old_len < len, ErrorCode::AccountAlreadyMigrated);
+
+    let rent_due = Rent::get()?
+        .minimum_balance(len)
+        .saturating_sub(account.lamports());
+    if rent_due > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.clone(),
+                system_program::Transfer {
+                    from: payer.clone(),
+                    to: account.clone(),
+                },
+            ),
+            rent_due,
+        )?;
+    }
+
+    account.resize(len)?;
+    account.try_borrow_mut_data()?[old_len..].fill(0);
+    Ok(())
+}
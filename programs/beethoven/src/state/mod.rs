@@ -8,6 +8,18 @@ pub mod vault;
 pub mod fund;
 pub mod fund_holding;
 pub mod proposal;
+pub mod price_accumulator;
+pub mod perp_lp_pool;
+pub mod user_market_stats;
+pub mod referrer_account;
+pub mod spot_market;
+pub mod order_book;
+pub mod event_queue;
+pub mod open_orders;
+pub mod swap_pool;
+pub mod protocol_registry;
+pub mod yield_position;
+pub mod migration;
 
 pub use exchange::*;
 pub use user_account::*;
@@ -19,3 +31,15 @@ pub use vault::*;
 pub use fund::*;
 pub use fund_holding::*;
 pub use proposal::*;
+pub use price_accumulator::*;
+pub use perp_lp_pool::*;
+pub use user_market_stats::*;
+pub use referrer_account::*;
+pub use spot_market::*;
+pub use order_book::*;
+pub use event_queue::*;
+pub use open_orders::*;
+pub use swap_pool::*;
+pub use protocol_registry::*;
+pub use yield_position::*;
+pub use migration::*;
@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use crate::error::ErrorCode;
+
+/// A sub-account's balances in one spot market. Locked funds back resting
+/// orders; free funds are maker proceeds and refunds awaiting settle_spot_funds.
+#[account]
+pub struct OpenOrders {
+    pub owner: Pubkey,
+    pub user_account: Pubkey,
+    pub market: Pubkey,
+    pub bump: u8,
+
+    // Balances (token atoms)
+    pub base_free: u64,
+    pub quote_free: u64,
+    pub base_locked: u64,
+    pub quote_locked: u64,
+
+    // Resting orders on either side
+    pub order_count: u32,
+
+    // Reserved
+    pub _reserved: [u8; 32],
+}
+
+impl OpenOrders {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // owner
+        + 32  // user_account
+        + 32  // market
+        + 1   // bump
+        + 8   // base_free
+        + 8   // quote_free
+        + 8   // base_locked
+        + 8   // quote_locked
+        + 4   // order_count
+        + 32; // reserved
+
+    /// Move funds backing a removed order from locked to free.
+    pub fn unlock(&mut self, base: u64, quote: u64) -> Result<()> {
+        self.base_locked = self
+            .base_locked
+            .checked_sub(base)
+            .ok_or(ErrorCode::MathUnderflow)?;
+        self.quote_locked = self
+            .quote_locked
+            .checked_sub(quote)
+            .ok_or(ErrorCode::MathUnderflow)?;
+        self.base_free = self
+            .base_free
+            .checked_add(base)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.quote_free = self
+            .quote_free
+            .checked_add(quote)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,320 @@
+use anchor_lang::prelude::*;
+use crate::constants::ORDER_BOOK_CAPACITY;
+use crate::error::ErrorCode;
+
+/// Null node handle.
+pub const NIL: u32 = u32::MAX;
+
+const TAG_FREE: u32 = 0;
+const TAG_INNER: u32 = 1;
+const TAG_LEAF: u32 = 2;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum OrderSide {
+    Bid,
+    Ask,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum OrderType {
+    /// Match what crosses, rest the remainder on the book
+    Limit,
+    /// Match what crosses, drop the remainder
+    ImmediateOrCancel,
+    /// Rest on the book; rejected if any of it would cross
+    PostOnly,
+}
+
+/// Order id: price in the high 64 bits, sequence number in the low 64.
+/// Bids store the sequence number inverted so that the largest key is the
+/// best bid and, at equal prices, the earliest order.
+pub fn order_key(side: OrderSide, price: u64, seq_num: u64) -> u128 {
+    let seq = match side {
+        OrderSide::Bid => !seq_num,
+        OrderSide::Ask => seq_num,
+    };
+    ((price as u128) << 64) | seq as u128
+}
+
+/// Price (quote atoms per base lot) of an order id.
+pub fn order_price(key: u128) -> u64 {
+    (key >> 64) as u64
+}
+
+/// One slot of the slab. Inner nodes use `prefix_len`, `children` and the
+/// key; leaves carry the resting order; free nodes link through `children[0]`.
+#[zero_copy]
+#[derive(Debug)]
+pub struct SlabNode {
+    pub tag: u32,
+    pub prefix_len: u32,      // Inner: leading key bits shared by the subtree
+    pub children: [u32; 2],   // Inner: [0-bit, 1-bit] subtrees; free: next free node
+    pub key_hi: u64,          // Order price
+    pub key_lo: u64,          // Sequence number (inverted for bids)
+    pub owner: Pubkey,        // Leaf: OpenOrders account of the maker
+    pub quantity: u64,        // Leaf: base lots still resting
+    pub locked_quote: u64,    // Leaf (bids): quote still locked, including the maker fee
+    pub client_order_id: u64,
+    pub timestamp: i64,
+}
+
+impl SlabNode {
+    pub fn key(&self) -> u128 {
+        ((self.key_hi as u128) << 64) | self.key_lo as u128
+    }
+
+    fn set_key(&mut self, key: u128) {
+        self.key_hi = (key >> 64) as u64;
+        self.key_lo = key as u64;
+    }
+
+    pub fn price(&self) -> u64 {
+        self.key_hi
+    }
+}
+
+/// A resting order as stored in a leaf.
+#[derive(Clone, Copy, Debug)]
+pub struct LeafOrder {
+    pub key: u128,
+    pub owner: Pubkey,
+    pub quantity: u64,
+    pub locked_quote: u64,
+    pub client_order_id: u64,
+    pub timestamp: i64,
+}
+
+/// One side of a spot market's book: a crit-bit tree over order ids, stored
+/// in a fixed slab of nodes. Best ask is the minimum key, best bid the maximum.
+#[account(zero_copy)]
+pub struct OrderBookSide {
+    pub market: Pubkey,
+    pub side: u8, // 0 = bids, 1 = asks
+    pub _padding: [u8; 3],
+    pub root: u32,
+    pub free_head: u32,    // Head of the free list
+    pub next_unused: u32,  // Nodes at or past this index have never been used
+    pub leaf_count: u32,
+    pub _padding2: [u8; 4],
+    pub nodes: [SlabNode; ORDER_BOOK_CAPACITY],
+}
+
+impl OrderBookSide {
+    pub const LEN: usize = 8 // discriminator
+        + 32  // market
+        + 1   // side
+        + 3   // padding
+        + 4   // root
+        + 4   // free_head
+        + 4   // next_unused
+        + 4   // leaf_count
+        + 4   // padding
+        + 96 * ORDER_BOOK_CAPACITY; // nodes
+
+    pub fn init(&mut self, market: Pubkey, side: OrderSide) {
+        self.market = market;
+        self.side = match side {
+            OrderSide::Bid => 0,
+            OrderSide::Ask => 1,
+        };
+        self.root = NIL;
+        self.free_head = NIL;
+        self.next_unused = 0;
+        self.leaf_count = 0;
+    }
+
+    pub fn order_side(&self) -> OrderSide {
+        if self.side == 0 {
+            OrderSide::Bid
+        } else {
+            OrderSide::Ask
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root == NIL
+    }
+
+    fn alloc(&mut self) -> Result<u32> {
+        if self.free_head != NIL {
+            let handle = self.free_head;
+            self.free_head = self.nodes[handle as usize].children[0];
+            return Ok(handle);
+        }
+        require!(
+            (self.next_unused as usize) < ORDER_BOOK_CAPACITY,
+            ErrorCode::OrderBookFull
+        );
+        let handle = self.next_unused;
+        self.next_unused += 1;
+        Ok(handle)
+    }
+
+    fn free(&mut self, handle: u32) {
+        let node = &mut self.nodes[handle as usize];
+        node.tag = TAG_FREE;
+        node.children = [self.free_head, NIL];
+        self.free_head = handle;
+    }
+
+    fn write_leaf(&mut self, handle: u32, order: &LeafOrder) {
+        let node = &mut self.nodes[handle as usize];
+        node.tag = TAG_LEAF;
+        node.prefix_len = 128;
+        node.children = [NIL, NIL];
+        node.set_key(order.key);
+        node.owner = order.owner;
+        node.quantity = order.quantity;
+        node.locked_quote = order.locked_quote;
+        node.client_order_id = order.client_order_id;
+        node.timestamp = order.timestamp;
+    }
+
+    pub fn leaf(&self, handle: u32) -> LeafOrder {
+        let node = &self.nodes[handle as usize];
+        LeafOrder {
+            key: node.key(),
+            owner: node.owner,
+            quantity: node.quantity,
+            locked_quote: node.locked_quote,
+            client_order_id: node.client_order_id,
+            timestamp: node.timestamp,
+        }
+    }
+
+    pub fn leaf_mut(&mut self, handle: u32) -> &mut SlabNode {
+        &mut self.nodes[handle as usize]
+    }
+
+    /// Insert a resting order. Order ids are unique by construction.
+    pub fn insert(&mut self, order: &LeafOrder) -> Result<u32> {
+        if self.root == NIL {
+            let handle = self.alloc()?;
+            self.write_leaf(handle, order);
+            self.root = handle;
+            self.leaf_count += 1;
+            return Ok(handle);
+        }
+
+        // Descend while the key shares the whole prefix of the subtree
+        let mut handle = self.root;
+        loop {
+            let node = &self.nodes[handle as usize];
+            let shared = (node.key() ^ order.key).leading_zeros();
+            if node.tag == TAG_INNER && shared >= node.prefix_len {
+                handle = node.children[crit_bit(order.key, node.prefix_len)];
+                continue;
+            }
+            require!(shared < 128, ErrorCode::InvalidOrder);
+            break;
+        }
+
+        // Split: move the existing subtree to a new slot and turn its old
+        // slot into an inner node, so its parent's pointer stays valid
+        let shared = (self.nodes[handle as usize].key() ^ order.key).leading_zeros();
+        let leaf_handle = self.alloc()?;
+        let moved_handle = self.alloc()?;
+        self.nodes[moved_handle as usize] = self.nodes[handle as usize];
+        self.write_leaf(leaf_handle, order);
+
+        let children = if crit_bit(order.key, shared) == 0 {
+            [leaf_handle, moved_handle]
+        } else {
+            [moved_handle, leaf_handle]
+        };
+        let inner = &mut self.nodes[handle as usize];
+        inner.tag = TAG_INNER;
+        inner.prefix_len = shared;
+        inner.children = children;
+        inner.set_key(order.key);
+
+        self.leaf_count += 1;
+        Ok(leaf_handle)
+    }
+
+    /// Handle of the leaf with `key`, if present.
+    pub fn find(&self, key: u128) -> Option<u32> {
+        let mut handle = self.root;
+        while handle != NIL {
+            let node = &self.nodes[handle as usize];
+            if node.tag == TAG_LEAF {
+                return (node.key() == key).then_some(handle);
+            }
+            if (node.key() ^ key).leading_zeros() < node.prefix_len {
+                return None;
+            }
+            handle = node.children[crit_bit(key, node.prefix_len)];
+        }
+        None
+    }
+
+    /// Remove the order with `key` and return it.
+    pub fn remove(&mut self, key: u128) -> Option<LeafOrder> {
+        if self.root == NIL {
+            return None;
+        }
+
+        let root = &self.nodes[self.root as usize];
+        if root.tag == TAG_LEAF {
+            if root.key() != key {
+                return None;
+            }
+            let order = self.leaf(self.root);
+            self.free(self.root);
+            self.root = NIL;
+            self.leaf_count -= 1;
+            return Some(order);
+        }
+
+        let mut parent = self.root;
+        loop {
+            let node = &self.nodes[parent as usize];
+            if (node.key() ^ key).leading_zeros() < node.prefix_len {
+                return None;
+            }
+            let bit = crit_bit(key, node.prefix_len);
+            let child = node.children[bit];
+            let sibling = node.children[1 - bit];
+
+            if self.nodes[child as usize].tag == TAG_INNER {
+                parent = child;
+                continue;
+            }
+            if self.nodes[child as usize].key() != key {
+                return None;
+            }
+
+            // Collapse the parent into the sibling
+            let order = self.leaf(child);
+            self.nodes[parent as usize] = self.nodes[sibling as usize];
+            self.free(sibling);
+            self.free(child);
+            self.leaf_count -= 1;
+            return Some(order);
+        }
+    }
+
+    fn edge(&self, bit: usize) -> Option<u32> {
+        let mut handle = self.root;
+        if handle == NIL {
+            return None;
+        }
+        while self.nodes[handle as usize].tag == TAG_INNER {
+            handle = self.nodes[handle as usize].children[bit];
+        }
+        Some(handle)
+    }
+
+    /// Handle of the best order: the highest bid or the lowest ask.
+    pub fn best(&self) -> Option<u32> {
+        match self.order_side() {
+            OrderSide::Bid => self.edge(1),
+            OrderSide::Ask => self.edge(0),
+        }
+    }
+}
+
+/// Bit of `key` just past the first `prefix_len` bits.
+fn crit_bit(key: u128, prefix_len: u32) -> usize {
+    ((key >> (127 - prefix_len)) & 1) as usize
+}
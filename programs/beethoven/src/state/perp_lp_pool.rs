@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+/// Liquidity pool backing every perp market whose collateral mint is `quote_mint`.
+/// LPs hold `lp_mint` shares and take the other side of net trader PnL.
+#[account]
+pub struct PerpLpPool {
+    pub exchange: Pubkey,
+    pub bump: u8,
+
+    // Token configuration
+    pub quote_mint: Pubkey,
+    pub lp_mint: Pubkey,     // SPL share token mint (pool PDA as authority)
+    pub vault_state: Pubkey, // Perp vault holding the pool's liquidity bucket
+
+    // Markets settling in quote_mint; all of them are required to price the pool
+    pub market_count: u16,
+
+    // Pool accounting
+    pub total_shares: u64,
+    pub nav_per_share: u128, // WAD precision
+    pub total_nav: u64,      // Quote units, as of last_nav_update
+
+    // Economics (basis points)
+    pub fee_share_bps: u64,          // Share of perp trading fees paid to LPs
+    pub target_utilization_bps: u64, // Min ratio of max OI notional to NAV; 0 = uncapped
+
+    // Timestamps
+    pub created_at: i64,
+    pub last_nav_update: i64,
+
+    // Reserved for future use
+    pub _reserved: [u8; 64],
+}
+
+impl PerpLpPool {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // exchange
+        + 1   // bump
+        + 32  // quote_mint
+        + 32  // lp_mint
+        + 32  // vault_state
+        + 2   // market_count
+        + 8   // total_shares
+        + 16  // nav_per_share
+        + 8   // total_nav
+        + 8   // fee_share_bps
+        + 8   // target_utilization_bps
+        + 8   // created_at
+        + 8   // last_nav_update
+        + 64; // reserved
+}
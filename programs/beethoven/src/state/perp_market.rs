@@ -1,4 +1,86 @@
 use anchor_lang::prelude::*;
+use crate::constants::MAX_MARGIN_TIERS;
+use crate::events::CircuitBreakerTripped;
+use crate::math::circuit_breaker::refresh_circuit_breaker;
+
+/// One row of a market's margin table. Applies to positions whose notional
+/// is at most `max_notional` and above the previous tier's bound.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug)]
+pub struct MarginTier {
+    pub max_notional: u64,           // Upper bound, collateral units
+    pub max_leverage: u64,           // Initial margin cap (1x = 1)
+    pub maintenance_margin_bps: u64, // Liquidation threshold
+}
+
+impl MarginTier {
+    pub const LEN: usize = 8 // max_notional
+        + 8   // max_leverage
+        + 8;  // maintenance_margin_bps
+}
+
+/// Volatility halt. Trips when the oracle moves `threshold_bps` away from the
+/// reference price recorded at the start of the current window; the market is
+/// then reduce-only for `cooldown` seconds and liquidations wait out
+/// `liquidation_grace` seconds. Instructions that trip it return without
+/// acting, so the trip is stored even when the action is rejected.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug)]
+pub struct CircuitBreaker {
+    pub threshold_bps: u64,     // Price move that trips the breaker (0 = disabled)
+    pub window: i64,            // Seconds a reference price is compared against
+    pub cooldown: i64,          // Seconds the market stays reduce-only after a trip
+    pub liquidation_grace: i64, // Seconds liquidations are delayed after a trip
+    pub reference_price: u64,   // Price at window_start (PRICE_PRECISION)
+    pub window_start: i64,
+    pub tripped_at: i64,        // 0 = not tripped
+}
+
+impl CircuitBreaker {
+    pub const LEN: usize = 8 // threshold_bps
+        + 8   // window
+        + 8   // cooldown
+        + 8   // liquidation_grace
+        + 8   // reference_price
+        + 8   // window_start
+        + 8;  // tripped_at
+
+    /// Reduce-only until the cooldown has elapsed.
+    pub fn is_tripped(&self, now: i64) -> bool {
+        self.tripped_at != 0 && now < self.tripped_at.saturating_add(self.cooldown)
+    }
+
+    /// Liquidations are held back for the grace period after a trip.
+    pub fn in_liquidation_grace(&self, now: i64) -> bool {
+        self.tripped_at != 0 && now < self.tripped_at.saturating_add(self.liquidation_grace)
+    }
+}
+
+/// Breaker state after `PerpMarket::refresh_circuit_breaker`.
+#[derive(Clone, Copy, Debug)]
+pub struct CircuitBreakerStatus {
+    pub tripped: bool,     // This call tripped the breaker
+    pub reduce_only: bool, // The market is reduce-only
+}
+
+/// How a market's positions are sized and settled.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum ContractType {
+    /// Size in base units, PnL and collateral in the quote mint
+    #[default]
+    Linear,
+    /// Size in quote units, PnL and collateral in the base mint
+    /// (e.g. SOL-margined SOL perps)
+    Inverse,
+}
+
+impl ContractType {
+    /// Mint a market of this type settles in.
+    pub fn collateral_mint(&self, base_mint: Pubkey, quote_mint: Pubkey) -> Pubkey {
+        match self {
+            ContractType::Linear => quote_mint,
+            ContractType::Inverse => base_mint,
+        }
+    }
+}
 
 #[account]
 pub struct PerpMarket {
@@ -31,8 +113,35 @@ pub struct PerpMarket {
     // Status
     pub paused: bool,
 
+    // Aggregate entry notional of open positions (collateral units), for LP pool NAV
+    pub long_entry_notional: u64,
+    pub short_entry_notional: u64,
+
+    // Notional risk limits (collateral units at oracle price, 0 = disabled)
+    pub max_oi_notional: u64,   // Per side
+    pub max_skew_notional: u64, // |long - short|
+    pub max_user_notional: u64, // Per user, across their positions in this market
+
+    // Contract type and the mint collateral, PnL and fees settle in
+    pub contract_type: ContractType,
+    pub collateral_mint: Pubkey, // quote_mint for linear, base_mint for inverse
+
+    // Sum of size * funding snapshot over open positions (WAD), for pending funding in LP pool NAV
+    pub long_funding_basis: i128,
+    pub short_funding_basis: i128,
+
     // Reserved for future use
-    pub _reserved: [u8; 128],
+    pub _reserved: [u8; 23],
+
+    // Fields below were appended after the original layout; older markets
+    // are grown by migrate_perp_market.
+
+    // Margin tiers, ascending by notional (empty = flat max_leverage and 5% maintenance)
+    pub margin_tier_count: u8,
+    pub margin_tiers: [MarginTier; MAX_MARGIN_TIERS],
+
+    // Volatility halt
+    pub circuit_breaker: CircuitBreaker,
 }
 
 impl PerpMarket {
@@ -49,5 +158,44 @@ impl PerpMarket {
         + 16 * 3 // funding fields (i128)
         + 8   // last_funding_update
         + 1   // paused
-        + 128; // reserved
+        + 8 * 2 // entry notional
+        + 8 * 3 // notional limits
+        + 1   // contract_type
+        + 32  // collateral_mint
+        + 16 * 2 // funding basis (i128)
+        + 23  // reserved
+        + 1   // margin_tier_count
+        + MarginTier::LEN * MAX_MARGIN_TIERS // margin_tiers
+        + CircuitBreaker::LEN; // circuit_breaker
+
+    /// Refresh the volatility halt with a fresh oracle price, emitting
+    /// `CircuitBreakerTripped` when this call trips it.
+    ///
+    /// A trip is only stored if the calling instruction succeeds, so callers
+    /// that reject their action on a fresh trip return `Ok` instead of an
+    /// error; the cooldown and liquidation grace then count from the first
+    /// tripping price.
+    pub fn refresh_circuit_breaker(
+        &mut self,
+        market: Pubkey,
+        price: u64,
+        now: i64,
+    ) -> Result<CircuitBreakerStatus> {
+        let breaker = &mut self.circuit_breaker;
+        let trip = refresh_circuit_breaker(breaker, price, now)?;
+        if let Some(trip) = &trip {
+            emit!(CircuitBreakerTripped {
+                market,
+                reference_price: trip.reference_price,
+                price,
+                move_bps: trip.move_bps,
+                reduce_only_until: breaker.tripped_at.saturating_add(breaker.cooldown),
+                timestamp: now,
+            });
+        }
+        Ok(CircuitBreakerStatus {
+            tripped: trip.is_some(),
+            reduce_only: breaker.is_tripped(now),
+        })
+    }
 }
@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use crate::state::UserAccount;
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default)]
 pub enum PositionSide {
@@ -15,8 +16,8 @@ pub struct PerpPosition {
 
     // Position details
     pub side: PositionSide,
-    pub size: u64,          // Position size in base units
-    pub collateral: u64,    // Collateral in quote units
+    pub size: u64,          // Base units (linear) or quote units (inverse)
+    pub collateral: u64,    // Collateral in the market's collateral mint
     pub entry_price: u64,   // Entry price (PRICE_PRECISION)
     pub leverage: u64,      // Effective leverage (1x = 1)
 
@@ -34,8 +35,12 @@ pub struct PerpPosition {
     pub opened_at: i64,
     pub last_updated: i64,
 
+    // Sub-account the position belongs to; default for positions opened
+    // before sub-accounts, which belong to the owner's primary account
+    pub user_account: Pubkey,
+
     // Reserved
-    pub _reserved: [u8; 64],
+    pub _reserved: [u8; 32],
 }
 
 impl PerpPosition {
@@ -54,5 +59,16 @@ impl PerpPosition {
         + 8   // unrealized_pnl
         + 8   // opened_at
         + 8   // last_updated
-        + 64; // reserved
+        + 32  // user_account
+        + 32; // reserved
+}
+
+impl PerpPosition {
+    /// Whether the position belongs to `account` at address `user_account`.
+    pub fn belongs_to(&self, user_account: &Pubkey, account: &UserAccount) -> bool {
+        if self.user_account == Pubkey::default() {
+            return self.owner == account.owner && account.sub_account_id == 0;
+        }
+        self.user_account == *user_account
+    }
 }
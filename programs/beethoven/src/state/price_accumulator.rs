@@ -0,0 +1,54 @@
+use anchor_lang::prelude::*;
+use crate::constants::PRICE_OBSERVATION_CAPACITY;
+
+/// A single oracle reading in the ring buffer.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug)]
+pub struct PriceObservation {
+    pub timestamp: i64,
+    pub price: u64,             // PRICE_PRECISION, prevails until the next observation
+    pub cumulative_price: u128, // sum(price * seconds) since the first observation
+}
+
+impl PriceObservation {
+    pub const LEN: usize = 8 // timestamp
+        + 8   // price
+        + 16; // cumulative_price
+}
+
+#[account]
+pub struct PriceAccumulator {
+    pub exchange: Pubkey,
+    pub oracle: Pubkey,
+    pub bump: u8,
+
+    // Observation ring buffer (Uniswap-style cumulative price)
+    pub observation_index: u16, // Slot of the most recent observation
+    pub observation_count: u16, // Populated slots, capped at PRICE_OBSERVATION_CAPACITY
+    pub min_observation_interval: i64,
+    pub observations: [PriceObservation; PRICE_OBSERVATION_CAPACITY],
+
+    // Exponential moving average
+    pub ema_half_life: i64, // Seconds for an observation's weight to halve
+    pub ema_price: u64,     // PRICE_PRECISION
+
+    // Timestamps
+    pub last_update: i64,
+
+    // Reserved for future use
+    pub _reserved: [u8; 64],
+}
+
+impl PriceAccumulator {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // exchange
+        + 32  // oracle
+        + 1   // bump
+        + 2   // observation_index
+        + 2   // observation_count
+        + 8   // min_observation_interval
+        + PriceObservation::LEN * PRICE_OBSERVATION_CAPACITY // observations
+        + 8   // ema_half_life
+        + 8   // ema_price
+        + 8   // last_update
+        + 64; // reserved
+}
@@ -93,6 +93,6 @@ pub struct PerpActionData {
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
 pub struct LendingActionData {
-    pub pool_index: u16,
+    pub protocol: Pubkey, // Yield venue program; must be remaining_accounts[0]
     pub amount: u64,
 }
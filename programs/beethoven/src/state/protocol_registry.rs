@@ -0,0 +1,154 @@
+use anchor_lang::prelude::*;
+use crate::constants::{MAX_PROTOCOL_ENTRIES, PROTOCOL_VOLUME_WINDOW};
+use crate::error::ErrorCode;
+
+/// Runtime settings for one compiled adapter.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug)]
+pub struct ProtocolEntry {
+    pub adapter_id: Pubkey, // Program id the adapter was compiled with
+    pub program_id: Pubkey, // Program id routed to the adapter on this cluster
+    pub enabled: bool,
+    pub volume_cap: u64,    // Max input atoms of volume_mint per window; 0 = uncapped
+    pub volume_mint: Pubkey, // Mint the cap is counted in; the only input a capped venue routes
+    pub window_volume: u64, // Input atoms routed in the current window
+    pub window_start: i64,
+}
+
+impl ProtocolEntry {
+    pub const LEN: usize = 32 // adapter_id
+        + 32  // program_id
+        + 1   // enabled
+        + 8   // volume_cap
+        + 32  // volume_mint
+        + 8   // window_volume
+        + 8;  // window_start
+}
+
+/// Admin-managed allowlist over the compiled swap and deposit adapters.
+/// Adapters without an entry route on their compiled program id, uncapped.
+#[account]
+pub struct ProtocolRegistry {
+    pub exchange: Pubkey,
+    pub bump: u8,
+    pub entries: [ProtocolEntry; MAX_PROTOCOL_ENTRIES],
+    pub entry_count: u8,
+
+    // Reserved for future use
+    pub _reserved: [u8; 32],
+}
+
+impl ProtocolRegistry {
+    pub const LEN: usize = 8 // discriminator
+        + 32  // exchange
+        + 1   // bump
+        + ProtocolEntry::LEN * MAX_PROTOCOL_ENTRIES // entries
+        + 1   // entry_count
+        + 32; // reserved
+
+    pub fn entries(&self) -> &[ProtocolEntry] {
+        &self.entries[..self.entry_count as usize]
+    }
+
+    pub fn entry_index(&self, adapter_id: &Pubkey) -> Option<usize> {
+        self.entries()
+            .iter()
+            .position(|entry| entry.adapter_id == *adapter_id)
+    }
+
+    /// Compiled adapter id that `program_id` routes to. Fails for disabled
+    /// adapters and for compiled ids that have been overridden.
+    pub fn resolve_adapter_id(&self, program_id: &Pubkey) -> Result<Pubkey> {
+        if let Some(entry) = self.entries().iter().find(|e| e.program_id == *program_id) {
+            require!(entry.enabled, ErrorCode::ProtocolDisabled);
+            return Ok(entry.adapter_id);
+        }
+        require!(
+            self.entry_index(program_id).is_none(),
+            ErrorCode::UnsupportedProtocol
+        );
+        Ok(*program_id)
+    }
+
+    /// Count `amount` input atoms of `mint` against the adapter's volume
+    /// cap. Returns whether the registry changed, i.e. the adapter is capped.
+    pub fn record_volume(
+        &mut self,
+        adapter_id: &Pubkey,
+        mint: &Pubkey,
+        amount: u64,
+        now: i64,
+    ) -> Result<bool> {
+        let Some(index) = self.entry_index(adapter_id) else {
+            return Ok(false);
+        };
+        let entry = &mut self.entries[index];
+        if entry.volume_cap == 0 {
+            return Ok(false);
+        }
+        require_keys_eq!(*mint, entry.volume_mint, ErrorCode::ProtocolVolumeMintMismatch);
+
+        if now.saturating_sub(entry.window_start) >= PROTOCOL_VOLUME_WINDOW {
+            entry.window_start = now;
+            entry.window_volume = 0;
+        }
+        entry.window_volume = entry
+            .window_volume
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(
+            entry.window_volume <= entry.volume_cap,
+            ErrorCode::ProtocolVolumeCapExceeded
+        );
+        Ok(true)
+    }
+}
+
+/// The registry PDA as swaps and deposits receive it: read-only, and
+/// uninitialized until the admin creates it, in which case every compiled
+/// adapter routes on its own program id, uncapped. Only routing through a
+/// capped venue writes to it, so only those transactions pass it writable.
+pub struct RegistryAccount<'info> {
+    info: AccountInfo<'info>,
+    registry: Option<Box<ProtocolRegistry>>,
+}
+
+impl<'info> RegistryAccount<'info> {
+    pub fn load(info: AccountInfo<'info>) -> Result<Self> {
+        if info.data_is_empty() {
+            return Ok(Self { info, registry: None });
+        }
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::InvalidParameter);
+        let registry = ProtocolRegistry::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        Ok(Self {
+            info,
+            registry: Some(Box::new(registry)),
+        })
+    }
+
+    /// Compiled adapter id that `program_id` routes to.
+    pub fn resolve_adapter_id(&self, program_id: &Pubkey) -> Result<Pubkey> {
+        match &self.registry {
+            Some(registry) => registry.resolve_adapter_id(program_id),
+            None => Ok(*program_id),
+        }
+    }
+
+    /// Count volume against the adapter's cap and persist it, which needs
+    /// the registry passed writable.
+    pub fn record_volume(
+        &mut self,
+        adapter_id: &Pubkey,
+        mint: &Pubkey,
+        amount: u64,
+        now: i64,
+    ) -> Result<()> {
+        let Some(registry) = self.registry.as_mut() else {
+            return Ok(());
+        };
+        if !registry.record_volume(adapter_id, mint, amount, now)? {
+            return Ok(());
+        }
+        require!(self.info.is_writable, ErrorCode::ProtocolRegistryReadOnly);
+        registry.try_serialize(&mut &mut self.info.try_borrow_mut_data()?[..])
+    }
+}
@@ -0,0 +1,33 @@
+use anchor_lang::prelude::*;
+
+/// Referral rewards a referrer has earned in one mint, across every vault
+/// of that mint.
+#[account]
+pub struct ReferrerAccount {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub bump: u8,
+
+    // Rewards
+    pub claimable: u64,
+    pub total_earned: u64,
+    pub total_claimed: u64,
+
+    // Timestamps
+    pub created_at: i64,
+
+    // Reserved
+    pub _reserved: [u8; 32],
+}
+
+impl ReferrerAccount {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // owner
+        + 32  // mint
+        + 1   // bump
+        + 8   // claimable
+        + 8   // total_earned
+        + 8   // total_claimed
+        + 8   // created_at
+        + 32; // reserved
+}
@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+
+/// Native spot order book market. Prices are quote atoms per base lot and
+/// sizes are base lots; matched tokens sit in the market's vaults until
+/// settled.
+#[account]
+pub struct SpotMarket {
+    pub exchange: Pubkey,
+    pub bump: u8,
+    pub market_index: u16,
+
+    // Token configuration
+    pub base_mint: Pubkey,
+    pub quote_mint: Pubkey,
+    pub base_vault: Pubkey,  // Token accounts owned by the market PDA
+    pub quote_vault: Pubkey,
+
+    // Book accounts (zero-copy)
+    pub bids: Pubkey,
+    pub asks: Pubkey,
+    pub event_queue: Pubkey,
+
+    // Order sizing
+    pub base_lot_size: u64, // Base atoms per lot
+    pub tick_size: u64,     // Price increment, quote atoms per base lot
+
+    // Fees (basis points of quote notional), paid to the quote mint's vault
+    pub maker_fee_bps: u64,
+    pub taker_fee_bps: u64,
+
+    // Order ids are price + this sequence number
+    pub seq_num: u64,
+
+    // Stats
+    pub base_volume: u64,  // Base lots traded
+    pub quote_volume: u64, // Quote atoms traded
+    pub fees_collected: u64,
+
+    // Status
+    pub paused: bool,
+
+    // Timestamps
+    pub created_at: i64,
+
+    // Reserved for future use
+    pub _reserved: [u8; 64],
+}
+
+impl SpotMarket {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // exchange
+        + 1   // bump
+        + 2   // market_index
+        + 32  // base_mint
+        + 32  // quote_mint
+        + 32  // base_vault
+        + 32  // quote_vault
+        + 32  // bids
+        + 32  // asks
+        + 32  // event_queue
+        + 8   // base_lot_size
+        + 8   // tick_size
+        + 8   // maker_fee_bps
+        + 8   // taker_fee_bps
+        + 8   // seq_num
+        + 8   // base_volume
+        + 8   // quote_volume
+        + 8   // fees_collected
+        + 1   // paused
+        + 8   // created_at
+        + 64; // reserved
+}
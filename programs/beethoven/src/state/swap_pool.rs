@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+
+/// Native constant-product (x * y = k) pool between two mints, ordered so
+/// that `mint_a < mint_b`. LPs hold `lp_mint` shares of both reserves.
+#[account]
+pub struct SwapPool {
+    pub exchange: Pubkey,
+    pub bump: u8,
+
+    // Token configuration
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub vault_a: Pubkey, // Token accounts owned by the pool PDA
+    pub vault_b: Pubkey,
+    pub lp_mint: Pubkey, // SPL share token mint (pool PDA as authority)
+
+    // Pool accounting (token atoms); vaults also hold unswept protocol fees
+    pub reserve_a: u64,
+    pub reserve_b: u64,
+    pub total_shares: u64, // Includes the shares locked by the first deposit
+
+    // Economics (basis points)
+    pub fee_bps: u64,                // Swap fee on the input amount
+    pub protocol_fee_share_bps: u64, // Share of the swap fee sent to the protocol vault
+
+    // Protocol fees awaiting collect_swap_pool_fees
+    pub protocol_fees_a: u64,
+    pub protocol_fees_b: u64,
+
+    // Stats
+    pub volume_a: u64, // Input volume, mint_a atoms
+    pub volume_b: u64, // Input volume, mint_b atoms
+
+    // Timestamps
+    pub created_at: i64,
+
+    // Reserved for future use
+    pub _reserved: [u8; 64],
+}
+
+impl SwapPool {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // exchange
+        + 1   // bump
+        + 32  // mint_a
+        + 32  // mint_b
+        + 32  // vault_a
+        + 32  // vault_b
+        + 32  // lp_mint
+        + 8   // reserve_a
+        + 8   // reserve_b
+        + 8   // total_shares
+        + 8   // fee_bps
+        + 8   // protocol_fee_share_bps
+        + 8   // protocol_fees_a
+        + 8   // protocol_fees_b
+        + 8   // volume_a
+        + 8   // volume_b
+        + 8   // created_at
+        + 64; // reserved
+}
@@ -1,4 +1,57 @@
 use anchor_lang::prelude::*;
+use crate::constants::{MAX_DELEGATE_MARKETS, VOLUME_WINDOW_DAYS};
+use crate::error::ErrorCode;
+
+/// A key the owner authorizes to trade on their behalf. Delegates can open
+/// and close positions and swap, but every transfer is to or from the
+/// owner's own token accounts, so funds cannot be withdrawn elsewhere.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug)]
+pub struct TradingDelegate {
+    pub authority: Pubkey,  // Pubkey::default() = no delegate
+    pub expires_at: i64,    // 0 = never expires
+    pub max_notional: u64,  // Per-position notional cap, collateral units (0 = unlimited)
+    pub allow_swaps: bool,
+    pub market_count: u8,   // 0 = every perp market
+    pub markets: [u16; MAX_DELEGATE_MARKETS], // Allowed perp market indexes
+}
+
+impl TradingDelegate {
+    pub const LEN: usize = 32 // authority
+        + 8   // expires_at
+        + 8   // max_notional
+        + 1   // allow_swaps
+        + 1   // market_count
+        + 2 * MAX_DELEGATE_MARKETS; // markets
+
+    pub fn is_set(&self) -> bool {
+        self.authority != Pubkey::default()
+    }
+
+    pub fn allows_market(&self, market_index: u16) -> bool {
+        self.market_count == 0
+            || self.markets[..self.market_count as usize].contains(&market_index)
+    }
+
+    /// Reject positions above the delegate's notional cap.
+    pub fn check_notional(&self, notional: u64) -> Result<()> {
+        require!(
+            self.max_notional == 0 || notional <= self.max_notional,
+            ErrorCode::DelegateScopeExceeded
+        );
+        Ok(())
+    }
+}
+
+/// Id seed of a user account PDA. The primary account keeps the original
+/// `[USER_ACCOUNT_SEED, owner]` address: an empty seed adds nothing to the
+/// PDA hash, so only sub-accounts 1.. carry their id.
+pub fn sub_account_seed(id_bytes: &[u8; 2]) -> &[u8] {
+    if *id_bytes == [0, 0] {
+        &[]
+    } else {
+        id_bytes
+    }
+}
 
 #[account]
 pub struct UserAccount {
@@ -22,8 +75,29 @@ pub struct UserAccount {
     pub created_at: i64,
     pub last_activity: i64,
 
+    // Sub-accounts: each has independent positions, stats and margin
+    pub sub_account_id: u16,    // 0 = primary account
+    pub sub_account_count: u16, // Sub-accounts created (primary account only)
+
+    // Rolling volume: day number (unix_timestamp / SECONDS_PER_DAY) of the newest bucket
+    pub last_volume_day: i64,
+    pub last_swap_volume_day: i64, // Same, for daily_swap_volume
+
     // Reserved for future use
-    pub _reserved: [u8; 64],
+    pub _reserved: [u8; 44],
+
+    // Fields below were appended after the original layout; older accounts
+    // are grown by migrate_user_account.
+
+    // Rolling volume: one bucket per day, indexed by day % VOLUME_WINDOW_DAYS
+    pub daily_volume: [u64; VOLUME_WINDOW_DAYS],
+
+    // Delegated trading key
+    pub delegate: TradingDelegate,
+
+    // Rolling swap volume in input token units, kept apart from the notional
+    // buckets above; one bucket per day, indexed by day % VOLUME_WINDOW_DAYS
+    pub daily_swap_volume: [u64; VOLUME_WINDOW_DAYS],
 }
 
 impl UserAccount {
@@ -39,5 +113,41 @@ impl UserAccount {
         + 32  // referrer
         + 8   // created_at
         + 8   // last_activity
-        + 64; // reserved
+        + 2   // sub_account_id
+        + 2   // sub_account_count
+        + 8   // last_volume_day
+        + 8   // last_swap_volume_day
+        + 44  // reserved
+        + 8 * VOLUME_WINDOW_DAYS // daily_volume
+        + TradingDelegate::LEN // delegate
+        + 8 * VOLUME_WINDOW_DAYS; // daily_swap_volume
+
+    /// Key that seeds this account's perp and lending positions: the owner for the
+    /// primary account, as before sub-accounts existed, and the sub-account
+    /// PDA otherwise.
+    pub fn position_seed_key(&self, user_account: Pubkey) -> Pubkey {
+        if self.sub_account_id == 0 {
+            self.owner
+        } else {
+            user_account
+        }
+    }
+
+    /// Check `signer` may trade for this account. Returns the active
+    /// delegate scope when the signer is a delegate, `None` for the owner.
+    pub fn authorize_trader(&self, signer: &Pubkey, now: i64) -> Result<Option<TradingDelegate>> {
+        if *signer == self.owner {
+            return Ok(None);
+        }
+        let delegate = self.delegate;
+        require!(
+            delegate.is_set() && delegate.authority == *signer,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            delegate.expires_at == 0 || now < delegate.expires_at,
+            ErrorCode::DelegateExpired
+        );
+        Ok(Some(delegate))
+    }
 }
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+/// Per-user exposure in a single perp market, summed across positions.
+#[account]
+pub struct UserMarketStats {
+    pub owner: Pubkey,
+    pub market: Pubkey,
+    pub bump: u8,
+
+    // Open exposure (in base units)
+    pub open_positions: u8,
+    pub long_size: u64,
+    pub short_size: u64,
+
+    // Timestamps
+    pub last_updated: i64,
+
+    // Reserved
+    pub _reserved: [u8; 32],
+}
+
+impl UserMarketStats {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // owner
+        + 32  // market
+        + 1   // bump
+        + 1   // open_positions
+        + 8   // long_size
+        + 8   // short_size
+        + 8   // last_updated
+        + 32; // reserved
+}
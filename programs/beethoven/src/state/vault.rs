@@ -1,4 +1,26 @@
 use anchor_lang::prelude::*;
+use crate::constants::{PERP_VAULT_SEED, VAULT_SEED};
+use crate::error::ErrorCode;
+
+/// Which product a vault serves. Determines the PDA seed prefix and
+/// which balance buckets it may hold.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum VaultKind {
+    #[default]
+    Lending,
+    Perp,
+}
+
+/// Accounted sub-balances of a vault's token account.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum VaultBucket {
+    PerpMargin,
+    PerpLiquidity,
+    LendingLiquidity,
+    Fees,
+    Insurance,
+    Referral,
+}
 
 #[account]
 pub struct VaultState {
@@ -11,8 +33,15 @@ pub struct VaultState {
     pub collected_fees: u64,
     pub insurance_balance: u64,
 
+    // Product buckets
+    pub kind: VaultKind,
+    pub perp_margin_balance: u64,       // Trader collateral (perp vaults only)
+    pub lending_liquidity_balance: u64, // Deposits + repayments - borrows (lending vaults only)
+    pub perp_liquidity_balance: u64,    // LP pool capital, counterparty to traders (perp vaults only)
+    pub referral_balance: u64,          // Referral rewards accrued but not yet claimed
+
     // Reserved
-    pub _reserved: [u8; 64],
+    pub _reserved: [u8; 31],
 }
 
 impl VaultState {
@@ -23,5 +52,96 @@ impl VaultState {
         + 1   // bump
         + 8   // collected_fees
         + 8   // insurance_balance
-        + 64; // reserved
+        + 1   // kind
+        + 8   // perp_margin_balance
+        + 8   // lending_liquidity_balance
+        + 8   // perp_liquidity_balance
+        + 8   // referral_balance
+        + 31; // reserved
+
+    /// PDA seed prefix for this vault, used to build signer seeds.
+    pub fn seed_prefix(&self) -> &'static [u8] {
+        match self.kind {
+            VaultKind::Lending => VAULT_SEED,
+            VaultKind::Perp => PERP_VAULT_SEED,
+        }
+    }
+
+    fn bucket_mut(&mut self, bucket: VaultBucket) -> Result<&mut u64> {
+        match (self.kind, bucket) {
+            (_, VaultBucket::Fees) => Ok(&mut self.collected_fees),
+            (_, VaultBucket::Insurance) => Ok(&mut self.insurance_balance),
+            (_, VaultBucket::Referral) => Ok(&mut self.referral_balance),
+            (VaultKind::Perp, VaultBucket::PerpMargin) => Ok(&mut self.perp_margin_balance),
+            (VaultKind::Perp, VaultBucket::PerpLiquidity) => Ok(&mut self.perp_liquidity_balance),
+            (VaultKind::Lending, VaultBucket::LendingLiquidity) => {
+                Ok(&mut self.lending_liquidity_balance)
+            }
+            _ => Err(ErrorCode::InvalidVaultBucket.into()),
+        }
+    }
+
+    pub fn credit(&mut self, bucket: VaultBucket, amount: u64) -> Result<()> {
+        let balance = self.bucket_mut(bucket)?;
+        *balance = balance.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn debit(&mut self, bucket: VaultBucket, amount: u64) -> Result<()> {
+        let balance = self.bucket_mut(bucket)?;
+        *balance = balance
+            .checked_sub(amount)
+            .ok_or(ErrorCode::InsufficientBucketBalance)?;
+        Ok(())
+    }
+
+    /// Release a closed position's margin. `protocol_fee` and `referral_fee`
+    /// move to their buckets, `payout` leaves the vault, and the rest of the
+    /// margin (trader losses and the LP fee share) goes to the LP pool. Trader
+    /// profits are paid by the pool, with insurance covering any shortfall.
+    pub fn settle_perp_margin(
+        &mut self,
+        collateral: u64,
+        protocol_fee: u64,
+        referral_fee: u64,
+        payout: u64,
+    ) -> Result<()> {
+        self.debit(VaultBucket::PerpMargin, collateral)?;
+        self.credit(VaultBucket::Fees, protocol_fee)?;
+        self.credit(VaultBucket::Referral, referral_fee)?;
+
+        let outflow = payout
+            .checked_add(protocol_fee)
+            .and_then(|sum| sum.checked_add(referral_fee))
+            .ok_or(ErrorCode::MathOverflow)?;
+        if outflow <= collateral {
+            return self.credit(VaultBucket::PerpLiquidity, collateral - outflow);
+        }
+
+        let deficit = outflow - collateral;
+        let from_pool = deficit.min(self.perp_liquidity_balance);
+        self.debit(VaultBucket::PerpLiquidity, from_pool)?;
+        self.debit(VaultBucket::Insurance, deficit - from_pool)
+    }
+
+    /// Sum of every bucket; never exceeds the token account balance.
+    pub fn accounted_balance(&self) -> Result<u64> {
+        self.collected_fees
+            .checked_add(self.insurance_balance)
+            .and_then(|sum| sum.checked_add(self.perp_margin_balance))
+            .and_then(|sum| sum.checked_add(self.lending_liquidity_balance))
+            .and_then(|sum| sum.checked_add(self.perp_liquidity_balance))
+            .and_then(|sum| sum.checked_add(self.referral_balance))
+            .ok_or(ErrorCode::MathOverflow.into())
+    }
+
+    /// Check the buckets are fully backed by `token_balance`. Call after
+    /// every transfer into or out of the vault, with a reloaded token account.
+    pub fn assert_backed(&self, token_balance: u64) -> Result<()> {
+        require!(
+            self.accounted_balance()? <= token_balance,
+            ErrorCode::VaultInvariantViolated
+        );
+        Ok(())
+    }
 }
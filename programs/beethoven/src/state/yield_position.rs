@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+use crate::error::ErrorCode;
+
+/// A user's deposits into one external yield venue, per mint.
+#[account]
+pub struct YieldPosition {
+    pub owner: Pubkey,
+    pub user_account: Pubkey, // Sub-account the position belongs to
+    pub protocol: Pubkey,     // Protocol program the deposits routed to
+    pub mint: Pubkey,         // Underlying mint deposited
+    pub bump: u8,
+
+    // Open position (principal in underlying atoms, receipts in receipt-token atoms)
+    pub principal: u64,
+    pub receipt_amount: u64,
+
+    // Lifetime totals
+    pub total_deposited: u64,
+    pub total_withdrawn: u64,
+    pub realized_yield: i64, // Withdrawn above cost basis; negative on a loss
+
+    pub last_updated: i64,
+
+    // Reserved
+    pub _reserved: [u8; 32],
+}
+
+impl YieldPosition {
+    pub const LEN: usize = 8  // discriminator
+        + 32  // owner
+        + 32  // user_account
+        + 32  // protocol
+        + 32  // mint
+        + 1   // bump
+        + 8   // principal
+        + 8   // receipt_amount
+        + 8   // total_deposited
+        + 8   // total_withdrawn
+        + 8   // realized_yield
+        + 8   // last_updated
+        + 32; // reserved
+
+    pub fn record_deposit(&mut self, amount: u64, receipts: u64, now: i64) -> Result<()> {
+        self.principal = self
+            .principal
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.receipt_amount = self
+            .receipt_amount
+            .checked_add(receipts)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.total_deposited = self
+            .total_deposited
+            .checked_add(amount)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.last_updated = now;
+        Ok(())
+    }
+
+    /// Close out the principal behind a withdrawal and book the rest as
+    /// yield. Returns (cost_basis, realized_yield) of this withdrawal.
+    ///
+    /// Venues with a receipt token release principal pro rata to the
+    /// receipts burned; venues without one release principal first, so
+    /// yield is only realized once the principal has come back. Burning
+    /// more receipts than the position holds would realize another
+    /// holder's principal as this position's yield, so it fails.
+    pub fn record_withdrawal(
+        &mut self,
+        returned: u64,
+        receipts_burned: u64,
+        now: i64,
+    ) -> Result<(u64, i64)> {
+        require!(
+            receipts_burned <= self.receipt_amount,
+            ErrorCode::ReceiptsExceedPosition
+        );
+        let cost_basis = if receipts_burned > 0 {
+            let basis = (self.principal as u128)
+                .checked_mul(receipts_burned as u128)
+                .ok_or(ErrorCode::MathOverflow)?
+                / self.receipt_amount as u128;
+            self.receipt_amount -= receipts_burned;
+            u64::try_from(basis).map_err(|_| ErrorCode::MathOverflow)?
+        } else {
+            returned.min(self.principal)
+        };
+        self.principal -= cost_basis;
+
+        let realized = i64::try_from(returned as i128 - cost_basis as i128)
+            .map_err(|_| ErrorCode::MathOverflow)?;
+        self.realized_yield = self
+            .realized_yield
+            .checked_add(realized)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.total_withdrawn = self
+            .total_withdrawn
+            .checked_add(returned)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.last_updated = now;
+        Ok((cost_basis, realized))
+    }
+}
@@ -0,0 +1,18 @@
+#!/usr/bin/env sh
+# Dump the mainnet venue programs and config accounts the swap adapter
+# tests load into the local validator (see [[test.genesis]] in Anchor.toml).
+set -e
+cd "$(dirname "$0")"
+URL="${SOLANA_URL:-https://api.mainnet-beta.solana.com}"
+
+solana program dump -u "$URL" CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C raydium_cpmm.so
+solana program dump -u "$URL" CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK raydium_clmm.so
+solana program dump -u "$URL" whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc orca_whirlpool.so
+solana program dump -u "$URL" PhoeNiXZ8ByJGLkxNfZRnkUfjvmuYqLR89jjFHGqdXY phoenix.so
+
+solana account -u "$URL" D4FPEruKEHrG5TenZ2mpDGEfu1iUvTiqBxvpU8HLBvC2 \
+  --output json -o raydium_cpmm_amm_config.json
+solana account -u "$URL" DNXgeM9EiiaAbaWvwjHj9fQQLAX5ZsfHyvmYUNRAdNC8 \
+  --output json -o raydium_cpmm_create_pool_fee.json
+solana account -u "$URL" 4BLNHtVe942GSs4teSZqGX24xwKNkqU7bGgNn3iUiUpw \
+  --output json -o raydium_clmm_amm_config.json